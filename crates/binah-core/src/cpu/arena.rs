@@ -0,0 +1,44 @@
+use crate::tensor::storage::TensorStorage;
+
+use super::element::Element;
+
+/// Buffers of tensors that are no longer read, handed out again to hold
+/// the results of later kernels instead of allocating new ones. The fused,
+/// matmul, reduction, softmax, gather, index_select, concat and cast
+/// kernels take their output from an arena.
+#[derive(Debug, Default)]
+pub struct Arena {
+    buffers: Vec<TensorStorage>,
+}
+
+impl Arena {
+    /// Keeps the buffer of `storage` for a later [`Arena::take`].
+    pub fn recycle(&mut self, storage: TensorStorage) {
+        if !storage.is_empty() {
+            self.buffers.push(storage);
+        }
+    }
+
+    /// An empty buffer with room for `len` elements of `T`: the smallest
+    /// recycled one that is large enough, or else a new one.
+    pub fn take<T: Element>(&mut self, len: usize) -> Vec<T> {
+        let best = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, storage)| Some((i, T::data(storage)?.len())))
+            .filter(|&(_, capacity)| capacity >= len)
+            .min_by_key(|&(_, capacity)| capacity);
+
+        match best {
+            Some((i, _)) => {
+                let storage = self.buffers.swap_remove(i);
+                let mut data = T::into_data(storage)
+                    .expect("the buffer was chosen for holding T");
+                data.clear();
+                data
+            }
+            None => Vec::with_capacity(len),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::tensor::{storage::TensorStorage, view::TensorView};
+
+use super::{
+    KernelError, broadcast_binary, broadcast_layout, element::Numeric,
+    exclusive,
+};
+
+/// Bool addition is logical OR.
+pub fn cpu_add(
+    lhs: &TensorView,
+    rhs: &TensorView,
+    output_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    if let (
+        TensorStorage::Bool { data: lhs_data, .. },
+        TensorStorage::Bool { data: rhs_data, .. },
+    ) = (lhs.storage(), rhs.storage())
+    {
+        return Ok(TensorStorage::Bool {
+            data: broadcast_binary(
+                "add",
+                lhs_data,
+                lhs.layout(),
+                rhs_data,
+                rhs.layout(),
+                output_shape,
+                |a, b| a || b,
+            )?,
+            shape: output_shape.to_vec(),
+        });
+    }
+
+    broadcast_numeric!("add", lhs, rhs, output_shape, Numeric::add).ok_or_else(
+        || KernelError::unsupported("add", &[lhs.storage(), rhs.storage()]),
+    )
+}
+
+pub fn cpu_sub(
+    lhs: &TensorView,
+    rhs: &TensorView,
+    output_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    broadcast_numeric!("sub", lhs, rhs, output_shape, Numeric::sub).ok_or_else(
+        || KernelError::unsupported("sub", &[lhs.storage(), rhs.storage()]),
+    )
+}
+
+/// Bool multiplication is logical AND.
+pub fn cpu_mul(
+    lhs: &TensorView,
+    rhs: &TensorView,
+    output_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    if let (
+        TensorStorage::Bool { data: lhs_data, .. },
+        TensorStorage::Bool { data: rhs_data, .. },
+    ) = (lhs.storage(), rhs.storage())
+    {
+        return Ok(TensorStorage::Bool {
+            data: broadcast_binary(
+                "mul",
+                lhs_data,
+                lhs.layout(),
+                rhs_data,
+                rhs.layout(),
+                output_shape,
+                |a, b| a && b,
+            )?,
+            shape: output_shape.to_vec(),
+        });
+    }
+
+    broadcast_numeric!("mul", lhs, rhs, output_shape, Numeric::mul).ok_or_else(
+        || KernelError::unsupported("mul", &[lhs.storage(), rhs.storage()]),
+    )
+}
+
+pub fn cpu_div(
+    lhs: &TensorView,
+    rhs: &TensorView,
+    output_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    broadcast_numeric!("div", lhs, rhs, output_shape, Numeric::div).ok_or_else(
+        || KernelError::unsupported("div", &[lhs.storage(), rhs.storage()]),
+    )
+}
+
+/// Defines in-place variants of arithmetic kernels. Each writes over the
+/// buffer of `lhs`, or else of `rhs`, when [`exclusive`] hands it over for
+/// the output shape, reading the other operand through its broadcast
+/// layout. Otherwise, and for bools, it calls the kernel it varies.
+macro_rules! in_place_kernels {
+    ($($name:ident => $kernel:ident, $op:path, $label:literal;)*) => {
+        $(
+            #[doc = concat!(
+                "Like [`", stringify!($kernel), "`], but writes over the ",
+                "buffer of an operand when no other view shares it."
+            )]
+            pub fn $name(
+                lhs: TensorView,
+                rhs: TensorView,
+                output_shape: &[usize],
+            ) -> Result<TensorStorage, KernelError> {
+                if let TensorStorage::Bool { .. } = lhs.storage() {
+                    return $kernel(&lhs, &rhs, output_shape);
+                }
+                let (mut storage, other, writes_lhs) =
+                    match exclusive(lhs, output_shape) {
+                        Ok(storage) => (storage, rhs, true),
+                        Err(lhs) => match exclusive(rhs, output_shape) {
+                            Ok(storage) => (storage, lhs, false),
+                            Err(rhs) => {
+                                return $kernel(&lhs, &rhs, output_shape);
+                            }
+                        },
+                    };
+                let layout =
+                    broadcast_layout($label, other.layout(), output_shape)?;
+
+                with_numeric_pair!(
+                    &mut storage,
+                    other.storage(),
+                    |data, other_data| {
+                        for (i, x) in data.iter_mut().enumerate() {
+                            let y = other_data[layout.index(i)];
+                            *x = match writes_lhs {
+                                true => $op(*x, y),
+                                false => $op(y, *x),
+                            };
+                        }
+                    }
+                )
+                .ok_or_else(|| {
+                    KernelError::unsupported(
+                        $label,
+                        &[&storage, other.storage()],
+                    )
+                })?;
+
+                Ok(storage)
+            }
+        )*
+    };
+}
+
+in_place_kernels! {
+    cpu_add_in_place => cpu_add, Numeric::add, "add";
+    cpu_sub_in_place => cpu_sub, Numeric::sub, "sub";
+    cpu_mul_in_place => cpu_mul, Numeric::mul, "mul";
+    cpu_div_in_place => cpu_div, Numeric::div, "div";
+}
+
+/// Defines a kernel comparing two views of the same variant elementwise into
+/// a `Bool` storage. Floats compare by IEEE 754, so NaN is unequal to
+/// everything.
+macro_rules! comparison_kernels {
+    ($($name:ident => $op:tt, $label:literal;)*) => {
+        $(
+            pub fn $name(
+                lhs: &TensorView,
+                rhs: &TensorView,
+                output_shape: &[usize],
+            ) -> Result<TensorStorage, KernelError> {
+                let data = with_storage_pair!(
+                    lhs.storage(),
+                    rhs.storage(),
+                    |lhs_data, rhs_data| {
+                        broadcast_binary(
+                            $label,
+                            lhs_data,
+                            lhs.layout(),
+                            rhs_data,
+                            rhs.layout(),
+                            output_shape,
+                            |a, b| a $op b,
+                        )?
+                    }
+                )
+                .ok_or_else(|| {
+                    KernelError::unsupported(
+                        $label,
+                        &[lhs.storage(), rhs.storage()],
+                    )
+                })?;
+
+                Ok(TensorStorage::Bool {
+                    data,
+                    shape: output_shape.to_vec(),
+                })
+            }
+        )*
+    };
+}
+
+comparison_kernels! {
+    cpu_equal => ==, "equal";
+    cpu_not_equal => !=, "not_equal";
+    cpu_less => <, "less";
+    cpu_less_equal => <=, "less_equal";
+    cpu_greater => >, "greater";
+    cpu_greater_equal => >=, "greater_equal";
+}
@@ -0,0 +1,61 @@
+use crate::tensor::{
+    storage::{DType, TensorStorage},
+    view::TensorView,
+};
+
+use super::{Arena, contiguous_data, element::Cast};
+
+/// Builds a storage of `$dtype` from `$data` converted element by element,
+/// in a buffer from `$arena`.
+macro_rules! cast_data {
+    ($data:expr, $dtype:expr, $shape:expr, $arena:expr) => {
+        cast_data!(
+            @variants $data, $dtype, $shape, $arena;
+            Bool => bool, U8 => u8, U16 => u16, U32 => u32, U64 => u64,
+            U128 => u128, I8 => i8, I16 => i16, I32 => i32, I64 => i64,
+            I128 => i128, F32 => f32, F64 => f64
+        )
+    };
+    (@variants $data:expr, $dtype:expr, $shape:expr, $arena:expr;
+        $($variant:ident => $ty:ty),*) => {
+        match $dtype {
+            $(
+                DType::$variant => {
+                    let mut data = $arena.take::<$ty>($data.len());
+                    data.extend($data.iter().map(|&x| Cast::<$ty>::cast(x)));
+
+                    TensorStorage::$variant { data, shape: $shape }
+                }
+            )*
+        }
+    };
+}
+
+/// Converts every element to `dtype` as Rust's `as` does: floats truncate
+/// toward zero and saturate when cast to integers, and integers wrap when
+/// narrowed. Bools convert to and from 0 and 1, and any non-zero number is
+/// `true`. The result goes to a buffer from `arena`.
+pub fn cpu_cast(
+    input: &TensorView,
+    dtype: DType,
+    arena: &mut Arena,
+) -> TensorStorage {
+    let shape = input.shape().to_vec();
+
+    macro_rules! cast_from {
+        ($($variant:ident),*) => {
+            match input.storage() {
+                $(
+                    TensorStorage::$variant { data, .. } => {
+                        let data = contiguous_data(data, input.layout());
+                        cast_data!(data, dtype, shape, arena)
+                    }
+                )*
+            }
+        };
+    }
+
+    cast_from!(
+        Bool, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+    )
+}
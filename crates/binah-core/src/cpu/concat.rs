@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+
+use crate::tensor::{storage::TensorStorage, view::TensorView};
+
+use super::{Arena, KernelError, contiguous_data, element::Element};
+
+/// Joins `inputs`, which must all hold the same type and agree on every axis
+/// but `axis`, along `axis`. The result goes to a buffer from `arena`.
+pub fn cpu_concat(
+    inputs: &[&TensorView],
+    axis: usize,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let unsupported = || {
+        let storages: Vec<_> =
+            inputs.iter().map(|input| input.storage()).collect();
+        KernelError::unsupported("concat", &storages)
+    };
+    let first = inputs.first().ok_or_else(unsupported)?;
+
+    let mut shape = first.shape().to_vec();
+    for input in &inputs[1..] {
+        let fits = input.shape().len() == shape.len()
+            && (0..shape.len()).all(|other| {
+                other == axis || input.shape()[other] == shape[other]
+            });
+        if !fits {
+            return Err(KernelError::IncompatibleShapes {
+                op: "concat",
+                lhs: first.shape().to_vec(),
+                rhs: input.shape().to_vec(),
+            });
+        }
+    }
+    shape[axis] = inputs.iter().map(|input| input.shape()[axis]).sum();
+
+    let data = map_storage!(first.storage(), |_data| {
+        (concat(inputs, axis, arena).ok_or_else(unsupported)?, shape)
+    });
+
+    Ok(data)
+}
+
+/// Interleaves the blocks below `axis` of every input, one row of blocks per
+/// index of the leading axes. `None` when the inputs hold different types.
+fn concat<T: Element>(
+    inputs: &[&TensorView],
+    axis: usize,
+    arena: &mut Arena,
+) -> Option<Vec<T>> {
+    let outer: usize = inputs[0].shape()[..axis].iter().product();
+
+    let parts = inputs
+        .iter()
+        .map(|input| {
+            let data = T::data(input.storage())?;
+            let block = input.shape()[axis..].iter().product();
+
+            Some((contiguous_data(data, input.layout()), block))
+        })
+        .collect::<Option<Vec<(Cow<[T]>, usize)>>>()?;
+
+    let len = outer * parts.iter().map(|(_, block)| block).sum::<usize>();
+    let mut result = arena.take(len);
+    for i in 0..outer {
+        for (data, block) in &parts {
+            result.extend_from_slice(&data[i * block..(i + 1) * block]);
+        }
+    }
+
+    Some(result)
+}
@@ -0,0 +1,333 @@
+use crate::tensor::storage::TensorStorage;
+
+/// Scalar types that can live in a [`TensorStorage`].
+pub trait Element: Copy + PartialEq + std::fmt::Debug + 'static {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Converts a fill value; integers saturate and bools test for non-zero.
+    fn from_f64(value: f64) -> Self;
+
+    /// The data of `storage` if it holds this type.
+    fn data(storage: &TensorStorage) -> Option<&[Self]>;
+
+    /// Takes the data out of `storage` if it holds this type, or else
+    /// hands `storage` back.
+    fn into_data(storage: TensorStorage) -> Result<Vec<Self>, TensorStorage>;
+
+    /// The data of `storage` if it holds this type, to write to.
+    fn data_mut(storage: &mut TensorStorage) -> Option<&mut Vec<Self>>;
+}
+
+/// Elementwise arithmetic shared by every numeric storage type.
+///
+/// Integer arithmetic wraps on overflow. Integer division truncates toward
+/// zero and yields zero when dividing by zero. Floats follow IEEE 754.
+pub trait Numeric: Element + PartialOrd {
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+    fn div(self, rhs: Self) -> Self;
+
+    /// Converts an element count, wrapping for narrow integer types.
+    fn from_usize(value: usize) -> Self;
+
+    fn is_nan(self) -> bool {
+        false
+    }
+
+    /// Wraps for unsigned types and for the minimum signed value.
+    fn neg(self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO { self.neg() } else { self }
+    }
+
+    /// Whether `self` should win a max-reduction against `rhs`. NaN wins
+    /// against any number so that it propagates.
+    fn greater_than(self, rhs: Self) -> bool {
+        self > rhs || (self.is_nan() && !rhs.is_nan())
+    }
+
+    /// Whether `self` should win a min-reduction against `rhs`.
+    fn less_than(self, rhs: Self) -> bool {
+        self < rhs || (self.is_nan() && !rhs.is_nan())
+    }
+}
+
+/// Transcendental and rounding functions of the floating-point types.
+pub trait Float: Numeric {
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tanh(self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    /// Rounds half to even, as NumPy does.
+    fn round(self) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Element for bool {
+    const ZERO: Self = false;
+    const ONE: Self = true;
+
+    fn from_f64(value: f64) -> Self {
+        value != 0.0
+    }
+
+    fn data(storage: &TensorStorage) -> Option<&[Self]> {
+        match storage {
+            TensorStorage::Bool { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+
+    fn into_data(storage: TensorStorage) -> Result<Vec<Self>, TensorStorage> {
+        match storage {
+            TensorStorage::Bool { data, .. } => Ok(data),
+            other => Err(other),
+        }
+    }
+
+    fn data_mut(storage: &mut TensorStorage) -> Option<&mut Vec<Self>> {
+        match storage {
+            TensorStorage::Bool { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl Element for $ty {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn from_f64(value: f64) -> Self {
+                    value as Self
+                }
+
+                fn data(storage: &TensorStorage) -> Option<&[Self]> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Some(data),
+                        _ => None,
+                    }
+                }
+
+                fn into_data(
+                    storage: TensorStorage,
+                ) -> Result<Vec<Self>, TensorStorage> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Ok(data),
+                        other => Err(other),
+                    }
+                }
+
+                fn data_mut(
+                    storage: &mut TensorStorage,
+                ) -> Option<&mut Vec<Self>> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Some(data),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl Numeric for $ty {
+                fn add(self, rhs: Self) -> Self {
+                    self.wrapping_add(rhs)
+                }
+
+                fn sub(self, rhs: Self) -> Self {
+                    self.wrapping_sub(rhs)
+                }
+
+                fn mul(self, rhs: Self) -> Self {
+                    self.wrapping_mul(rhs)
+                }
+
+                fn div(self, rhs: Self) -> Self {
+                    if rhs == 0 { 0 } else { self.wrapping_div(rhs) }
+                }
+
+                fn from_usize(value: usize) -> Self {
+                    value as Self
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl Element for $ty {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                fn from_f64(value: f64) -> Self {
+                    value as Self
+                }
+
+                fn data(storage: &TensorStorage) -> Option<&[Self]> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Some(data),
+                        _ => None,
+                    }
+                }
+
+                fn into_data(
+                    storage: TensorStorage,
+                ) -> Result<Vec<Self>, TensorStorage> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Ok(data),
+                        other => Err(other),
+                    }
+                }
+
+                fn data_mut(
+                    storage: &mut TensorStorage,
+                ) -> Option<&mut Vec<Self>> {
+                    match storage {
+                        TensorStorage::$variant { data, .. } => Some(data),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl Numeric for $ty {
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn div(self, rhs: Self) -> Self {
+                    self / rhs
+                }
+
+                fn from_usize(value: usize) -> Self {
+                    value as Self
+                }
+
+                fn is_nan(self) -> bool {
+                    self.is_nan()
+                }
+
+                fn neg(self) -> Self {
+                    -self
+                }
+
+                fn abs(self) -> Self {
+                    self.abs()
+                }
+            }
+
+            impl Float for $ty {
+                fn exp(self) -> Self {
+                    self.exp()
+                }
+
+                fn ln(self) -> Self {
+                    self.ln()
+                }
+
+                fn sqrt(self) -> Self {
+                    self.sqrt()
+                }
+
+                fn sin(self) -> Self {
+                    self.sin()
+                }
+
+                fn cos(self) -> Self {
+                    self.cos()
+                }
+
+                fn tanh(self) -> Self {
+                    self.tanh()
+                }
+
+                fn floor(self) -> Self {
+                    self.floor()
+                }
+
+                fn ceil(self) -> Self {
+                    self.ceil()
+                }
+
+                fn round(self) -> Self {
+                    self.round_ties_even()
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64, u128 => U128,
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64, i128 => I128
+);
+impl_float!(f32 => F32, f64 => F64);
+
+/// Converts an element to another storage type with the semantics of `as`.
+/// Bools become 0 or 1, and numbers become bools by testing for non-zero.
+pub trait Cast<T> {
+    fn cast(self) -> T;
+}
+
+macro_rules! impl_numeric_cast {
+    ($($from:ty),*) => {
+        $(
+            impl_numeric_cast!(
+                @to $from;
+                u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64
+            );
+
+            impl Cast<bool> for $from {
+                fn cast(self) -> bool {
+                    self != <$from as Element>::ZERO
+                }
+            }
+
+            impl Cast<$from> for bool {
+                fn cast(self) -> $from {
+                    self as u8 as $from
+                }
+            }
+        )*
+    };
+    (@to $from:ty; $($to:ty),*) => {
+        $(
+            impl Cast<$to> for $from {
+                fn cast(self) -> $to {
+                    self as $to
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_cast!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Cast<bool> for bool {
+    fn cast(self) -> bool {
+        self
+    }
+}
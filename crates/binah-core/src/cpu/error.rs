@@ -0,0 +1,62 @@
+use crate::tensor::storage::{DType, TensorStorage};
+
+/// Why a kernel cannot run on its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KernelError {
+    /// `op` has no kernel for operands of `dtypes`, in operand order.
+    UnsupportedDtypes {
+        op: &'static str,
+        dtypes: Vec<DType>,
+    },
+    /// Operands of `op` whose shapes do not fit together.
+    IncompatibleShapes {
+        op: &'static str,
+        lhs: Vec<usize>,
+        rhs: Vec<usize>,
+    },
+    /// An index read by `op` lies outside an axis of size `len`.
+    IndexOutOfRange {
+        op: &'static str,
+        index: i128,
+        len: usize,
+    },
+    /// `op` reduces over an axis of size zero and has no identity.
+    EmptyReduction { op: &'static str },
+}
+
+impl KernelError {
+    pub(crate) fn unsupported(
+        op: &'static str,
+        operands: &[&TensorStorage],
+    ) -> Self {
+        KernelError::UnsupportedDtypes {
+            op,
+            dtypes: operands.iter().map(|storage| storage.dtype()).collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for KernelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KernelError::UnsupportedDtypes { op, dtypes } => {
+                let dtypes: Vec<_> =
+                    dtypes.iter().map(|dtype| dtype.to_string()).collect();
+                write!(f, "Unsupported types [{}] for {op}", dtypes.join(", "))
+            }
+            KernelError::IncompatibleShapes { op, lhs, rhs } => {
+                write!(f, "Incompatible shapes {lhs:?} and {rhs:?} for {op}")
+            }
+            KernelError::IndexOutOfRange { op, index, len } => write!(
+                f,
+                "Index {index} is out of range for an axis of size {len} in \
+                 {op}"
+            ),
+            KernelError::EmptyReduction { op } => {
+                write!(f, "Cannot {op} over an empty dimension")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KernelError {}
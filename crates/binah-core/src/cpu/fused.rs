@@ -0,0 +1,244 @@
+use crate::{
+    op::{FusedStep, Operation},
+    tensor::{
+        layout::Layout, shape::Shape, storage::TensorStorage, view::TensorView,
+    },
+};
+
+use super::{
+    Arena, KernelError, broadcast_layout,
+    element::{Element, Float, Numeric},
+    unary::{erf, gelu, reciprocal, relu, rsqrt, sigmoid, sign},
+};
+
+/// Elements evaluated per step before moving on to the next step, small
+/// enough for every value of a block to stay in cache.
+const BLOCK: usize = 1024;
+
+/// Evaluates `steps` over `inputs`, which must all have the same dtype,
+/// broadcast to one output shape. The output is built block by block, so
+/// no step allocates a tensor of its own. It overwrites an input that no
+/// other view shares and that is laid out like the output, or else goes
+/// to a buffer from `arena`.
+pub fn cpu_fused(
+    mut inputs: Vec<TensorView>,
+    steps: &[FusedStep],
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let shape =
+        inputs
+            .iter()
+            .try_fold(Vec::new(), |shape: Vec<usize>, input| {
+                Shape::from(shape.as_slice())
+                    .broadcast_with(&Shape::from(input.shape()))
+                    .ok()
+                    .and_then(|shape| shape.fixed_dims())
+                    .ok_or_else(|| KernelError::IncompatibleShapes {
+                        op: "fused",
+                        lhs: shape.clone(),
+                        rhs: input.shape().to_vec(),
+                    })
+            })?;
+    let layouts = inputs
+        .iter()
+        .map(|input| broadcast_layout("fused", input.layout(), &shape))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let unsupported = |inputs: &[TensorView]| {
+        let storages: Vec<&TensorStorage> =
+            inputs.iter().map(TensorView::storage).collect();
+
+        KernelError::unsupported("fused", &storages)
+    };
+    // An empty storage of the inputs' dtype, which only fixes `T`
+    let witness = match inputs.first() {
+        Some(first) => TensorStorage::zeros(first.storage().dtype(), vec![0]),
+        None => return Err(unsupported(&inputs)),
+    };
+    let storage = map_storage!(&witness, |witness| {
+        let result =
+            fused(witness, &mut inputs, &layouts, steps, &shape, arena)
+                .ok_or_else(|| unsupported(&inputs))?;
+
+        (result, shape.clone())
+    });
+
+    Ok(storage)
+}
+
+/// `witness` only fixes `T`. Returns `None` when an input is not of type
+/// `T` or a step has no kernel for it.
+fn fused<T: Fusable>(
+    _witness: &[T],
+    inputs: &mut [TensorView],
+    layouts: &[Layout],
+    steps: &[FusedStep],
+    shape: &[usize],
+    arena: &mut Arena,
+) -> Option<Vec<T>> {
+    let len: usize = shape.iter().product();
+
+    // Each block of the target is read before the same block of the
+    // output is written over it.
+    let target = inputs.iter_mut().enumerate().find_map(|(i, input)| {
+        let layout = input.layout();
+        let aligned = input.shape() == shape
+            && layout.is_contiguous()
+            && layout.offset() == 0;
+        let data = input.storage_mut().filter(|_| aligned)?;
+
+        Some((i, std::mem::take(T::data_mut(data)?)))
+    });
+    let (target, mut result) = match target {
+        Some((i, mut data)) => {
+            data.truncate(len);
+            (Some(i), data)
+        }
+        None => (None, arena.take(len)),
+    };
+
+    let data = inputs
+        .iter()
+        .map(|input| T::data(input.storage()))
+        .collect::<Option<Vec<_>>>()?;
+    let mut values = vec![vec![T::ZERO; BLOCK]; inputs.len() + steps.len()];
+
+    for start in (0..len).step_by(BLOCK) {
+        let size = BLOCK.min(len - start);
+
+        for (i, ((value, data), layout)) in
+            values.iter_mut().zip(&data).zip(layouts).enumerate()
+        {
+            if target == Some(i) {
+                value[..size].copy_from_slice(&result[start..start + size]);
+            } else if layout.is_contiguous() {
+                let offset = layout.offset() + start;
+                value[..size].copy_from_slice(&data[offset..offset + size]);
+            } else {
+                for (i, slot) in value[..size].iter_mut().enumerate() {
+                    *slot = data[layout.index(start + i)];
+                }
+            }
+        }
+
+        for (i, step) in steps.iter().enumerate() {
+            let (done, rest) = values.split_at_mut(inputs.len() + i);
+            let out = &mut rest[0][..size];
+            if let Operation::FullLike { value } = step.op {
+                out.fill(T::from_f64(value));
+                continue;
+            }
+
+            let args: Vec<&[T]> = step
+                .operands
+                .iter()
+                .map(|&operand| &done[operand][..size])
+                .collect();
+            if !T::step(&step.op, &args, out) {
+                return None;
+            }
+        }
+
+        let output = &values.last()?[..size];
+        match target {
+            Some(_) => result[start..start + size].copy_from_slice(output),
+            None => result.extend_from_slice(output),
+        }
+    }
+
+    Some(result)
+}
+
+/// Element types with kernels for some elementwise operations.
+trait Fusable: Element {
+    /// Applies `op` to the values in `args`, writing to `out`. Returns
+    /// whether `op` has a kernel for this type.
+    fn step(op: &Operation, args: &[&[Self]], out: &mut [Self]) -> bool;
+}
+
+/// Bool addition and multiplication are logical OR and AND, as in
+/// [`cpu_add`](super::cpu_add) and [`cpu_mul`](super::cpu_mul).
+impl Fusable for bool {
+    fn step(op: &Operation, args: &[&[Self]], out: &mut [Self]) -> bool {
+        match op {
+            Operation::Add => binary(args, out, |a, b| a || b),
+            Operation::Mul => binary(args, out, |a, b| a && b),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+macro_rules! impl_fusable {
+    ($step:ident: $($ty:ty),*) => {
+        $(
+            impl Fusable for $ty {
+                fn step(
+                    op: &Operation,
+                    args: &[&[Self]],
+                    out: &mut [Self],
+                ) -> bool {
+                    $step(op, args, out)
+                }
+            }
+        )*
+    };
+}
+
+impl_fusable!(numeric_step: u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl_fusable!(float_step: f32, f64);
+
+fn numeric_step<T: Numeric>(
+    op: &Operation,
+    args: &[&[T]],
+    out: &mut [T],
+) -> bool {
+    match op {
+        Operation::Add => binary(args, out, Numeric::add),
+        Operation::Sub => binary(args, out, Numeric::sub),
+        Operation::Mul => binary(args, out, Numeric::mul),
+        Operation::Div => binary(args, out, Numeric::div),
+        Operation::Neg => unary(args, out, Numeric::neg),
+        Operation::Abs => unary(args, out, Numeric::abs),
+        Operation::Sign => unary(args, out, sign),
+        Operation::Relu => unary(args, out, relu),
+        _ => return false,
+    }
+
+    true
+}
+
+fn float_step<T: Float>(op: &Operation, args: &[&[T]], out: &mut [T]) -> bool {
+    match op {
+        Operation::Exp => unary(args, out, Float::exp),
+        Operation::Log => unary(args, out, Float::ln),
+        Operation::Sqrt => unary(args, out, Float::sqrt),
+        Operation::Rsqrt => unary(args, out, rsqrt),
+        Operation::Sin => unary(args, out, Float::sin),
+        Operation::Cos => unary(args, out, Float::cos),
+        Operation::Tanh => unary(args, out, Float::tanh),
+        Operation::Sigmoid => unary(args, out, sigmoid),
+        Operation::Gelu => unary(args, out, gelu),
+        Operation::Erf => unary(args, out, erf),
+        Operation::Floor => unary(args, out, Float::floor),
+        Operation::Ceil => unary(args, out, Float::ceil),
+        Operation::Round => unary(args, out, Float::round),
+        Operation::Reciprocal => unary(args, out, reciprocal),
+        _ => return numeric_step(op, args, out),
+    }
+
+    true
+}
+
+fn unary<T: Copy>(args: &[&[T]], out: &mut [T], op: impl Fn(T) -> T) {
+    for (out, &x) in out.iter_mut().zip(args[0]) {
+        *out = op(x);
+    }
+}
+
+fn binary<T: Copy>(args: &[&[T]], out: &mut [T], op: impl Fn(T, T) -> T) {
+    for ((out, &a), &b) in out.iter_mut().zip(args[0]).zip(args[1]) {
+        *out = op(a, b);
+    }
+}
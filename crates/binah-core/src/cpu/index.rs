@@ -0,0 +1,270 @@
+use crate::tensor::{
+    layout::{Layout, compute_index, contiguous_strides},
+    shape::Slice,
+    storage::TensorStorage,
+    view::TensorView,
+};
+
+use super::{
+    Arena, KernelError, broadcast_layout, contiguous_data,
+    element::{Element, Numeric},
+};
+
+/// Reads integer `$indices` of any width as `i128`; `None` for other types.
+macro_rules! integer_values {
+    ($indices:expr; $($variant:ident),*) => {
+        match $indices.storage() {
+            $(
+                TensorStorage::$variant { data, .. } => Some(
+                    contiguous_data(data, $indices.layout())
+                        .iter()
+                        .map(|&index| index as i128)
+                        .collect::<Vec<_>>(),
+                ),
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// A copy of `input` with the region selected by `slices` replaced by `src`.
+pub fn cpu_slice_scatter(
+    input: &TensorView,
+    src: &TensorView,
+    slices: &[Slice],
+) -> Result<TensorStorage, KernelError> {
+    let shape = input.shape();
+    let strides = contiguous_strides(shape);
+
+    let mut offset = 0;
+    let mut region_strides = strides.clone();
+    for (axis, slice) in slices.iter().enumerate() {
+        offset += slice.resolve(shape[axis]).0 * strides[axis];
+        region_strides[axis] *= slice.step as usize;
+    }
+
+    zip_storage!(input.storage(), src.storage(), |input_data, src_data| {
+        let mut data = contiguous_data(input_data, input.layout()).into_owned();
+        for i in 0..src.layout().num_elements() {
+            let target =
+                offset + compute_index(i, &region_strides, src.shape());
+            data[target] = src_data[src.layout().index(i)];
+        }
+
+        (data, shape.to_vec())
+    })
+    .ok_or_else(|| {
+        KernelError::unsupported(
+            "slice_scatter",
+            &[input.storage(), src.storage()],
+        )
+    })
+}
+
+/// Entries of `input` along `axis` at the 1-D `indices`, in a buffer from
+/// `arena`.
+pub fn cpu_index_select(
+    input: &TensorView,
+    indices: &TensorView,
+    axis: usize,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let indices = index_values("index_select", indices, input.shape()[axis])?;
+    let mut shape = input.shape().to_vec();
+    shape[axis] = indices.len();
+
+    let data = map_storage!(input.storage(), |data| {
+        let data = take_along(
+            data,
+            input.layout(),
+            axis,
+            &shape,
+            arena,
+            |_, coord| indices[coord],
+        );
+
+        (data, shape.clone())
+    });
+
+    Ok(data)
+}
+
+/// Entries of `input` along `axis` at `indices`, which has the output's
+/// shape, in a buffer from `arena`.
+pub fn cpu_gather(
+    input: &TensorView,
+    indices: &TensorView,
+    axis: usize,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let shape = indices.shape().to_vec();
+    let indices = index_values("gather", indices, input.shape()[axis])?;
+
+    let data = map_storage!(input.storage(), |data| {
+        let data =
+            take_along(data, input.layout(), axis, &shape, arena, |i, _| {
+                indices[i]
+            });
+
+        (data, shape.clone())
+    });
+
+    Ok(data)
+}
+
+/// A copy of `input` with `src` written along `axis` at `indices`, which
+/// has the shape of `src`. `accumulate` adds instead of overwriting.
+pub fn cpu_scatter(
+    input: &TensorView,
+    indices: &TensorView,
+    src: &TensorView,
+    axis: usize,
+    accumulate: bool,
+) -> Result<TensorStorage, KernelError> {
+    let name = if accumulate { "scatter_add" } else { "scatter" };
+    let shape = input.shape();
+    let index_shape = indices.shape().to_vec();
+    let indices = index_values(name, indices, shape[axis])?;
+
+    let mut strides = contiguous_strides(shape);
+    let axis_stride = std::mem::replace(&mut strides[axis], 0);
+    let targets: Vec<usize> = indices
+        .iter()
+        .enumerate()
+        .map(|(i, &index)| {
+            compute_index(i, &strides, &index_shape) + index * axis_stride
+        })
+        .collect();
+
+    if accumulate {
+        zip_numeric!(input.storage(), src.storage(), |input_data, src_data| {
+            let mut data =
+                contiguous_data(input_data, input.layout()).into_owned();
+            for (i, &target) in targets.iter().enumerate() {
+                data[target] =
+                    data[target].add(src_data[src.layout().index(i)]);
+            }
+
+            (data, shape.to_vec())
+        })
+        .ok_or_else(|| {
+            KernelError::unsupported(name, &[input.storage(), src.storage()])
+        })
+    } else {
+        zip_storage!(input.storage(), src.storage(), |input_data, src_data| {
+            let mut data =
+                contiguous_data(input_data, input.layout()).into_owned();
+            for (i, &target) in targets.iter().enumerate() {
+                data[target] = src_data[src.layout().index(i)];
+            }
+
+            (data, shape.to_vec())
+        })
+        .ok_or_else(|| {
+            KernelError::unsupported(name, &[input.storage(), src.storage()])
+        })
+    }
+}
+
+/// `on_true` where the bool `condition` holds and `on_false` elsewhere,
+/// broadcasting all three to `output_shape`.
+pub fn cpu_where(
+    condition: &TensorView,
+    on_true: &TensorView,
+    on_false: &TensorView,
+    output_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    let unsupported = || {
+        KernelError::unsupported(
+            "where",
+            &[condition.storage(), on_true.storage(), on_false.storage()],
+        )
+    };
+    let TensorStorage::Bool { data: mask, .. } = condition.storage() else {
+        return Err(unsupported());
+    };
+    let broadcast = |view: &TensorView| {
+        broadcast_layout("where", view.layout(), output_shape)
+    };
+    let mask_layout = broadcast(condition)?;
+    let true_layout = broadcast(on_true)?;
+    let false_layout = broadcast(on_false)?;
+
+    zip_storage!(
+        on_true.storage(),
+        on_false.storage(),
+        |true_data, false_data| {
+            let data = (0..output_shape.iter().product())
+                .map(|i| match mask[mask_layout.index(i)] {
+                    true => true_data[true_layout.index(i)],
+                    false => false_data[false_layout.index(i)],
+                })
+                .collect();
+
+            (data, output_shape.to_vec())
+        }
+    )
+    .ok_or_else(unsupported)
+}
+
+/// Reads integer `indices`, resolving negative ones against an axis of size
+/// `dim`. Fails on indices outside the axis.
+fn index_values(
+    name: &'static str,
+    indices: &TensorView,
+    dim: usize,
+) -> Result<Vec<usize>, KernelError> {
+    let values = integer_values!(
+        indices;
+        U8, U16, U32, U64, U128, I8, I16, I32, I64, I128
+    )
+    .ok_or_else(|| KernelError::unsupported(name, &[indices.storage()]))?;
+
+    values
+        .into_iter()
+        .map(|index| {
+            let resolved = if index < 0 {
+                index + dim as i128
+            } else {
+                index
+            };
+            if !(0..dim as i128).contains(&resolved) {
+                return Err(KernelError::IndexOutOfRange {
+                    op: name,
+                    index,
+                    len: dim,
+                });
+            }
+
+            Ok(resolved as usize)
+        })
+        .collect()
+}
+
+/// Reads `data` through `layout` at every position of `shape`, except that
+/// the coordinate along `axis` is replaced by `index(position, coord)`.
+fn take_along<T: Element>(
+    data: &[T],
+    layout: &Layout,
+    axis: usize,
+    shape: &[usize],
+    arena: &mut Arena,
+    index: impl Fn(usize, usize) -> usize,
+) -> Vec<T> {
+    let mut strides = layout.strides().to_vec();
+    let axis_stride = std::mem::replace(&mut strides[axis], 0);
+    let inner: usize = shape[axis + 1..].iter().product();
+
+    let len = shape.iter().product();
+    let mut result = arena.take(len);
+    result.extend((0..len).map(|i| {
+        let coord = (i / inner) % shape[axis];
+        let position = layout.offset()
+            + compute_index(i, &strides, shape)
+            + index(i, coord) * axis_stride;
+
+        data[position]
+    }));
+
+    result
+}
@@ -0,0 +1,157 @@
+use crate::tensor::{
+    layout::Layout, shape::Shape, storage::TensorStorage, view::TensorView,
+};
+
+use super::{Arena, KernelError, element::Numeric};
+
+/// Edge length of the square tiles the product is computed in.
+const BLOCK_SIZE: usize = 64;
+
+/// Batched matrix product of `lhs` and `rhs`, optionally reading either
+/// operand with its last two dimensions swapped. Operands are read through
+/// their strides, so transposed views need no copy. The result goes to a
+/// buffer from `arena`.
+pub fn cpu_matmul(
+    lhs: &TensorView,
+    rhs: &TensorView,
+    transpose_lhs: bool,
+    transpose_rhs: bool,
+    output_shape: &[usize],
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let incompatible = || KernelError::IncompatibleShapes {
+        op: "matmul",
+        lhs: lhs.shape().to_vec(),
+        rhs: rhs.shape().to_vec(),
+    };
+    if lhs.shape().is_empty() || rhs.shape().is_empty() {
+        return Err(incompatible());
+    }
+    let lhs_matrix = MatrixLayout::new(lhs.layout(), transpose_lhs, false);
+    let rhs_matrix = MatrixLayout::new(rhs.layout(), transpose_rhs, true);
+    if lhs_matrix.cols != rhs_matrix.rows {
+        return Err(incompatible());
+    }
+    let batch = Shape::from(lhs_matrix.batch.shape())
+        .broadcast_with(&Shape::from(rhs_matrix.batch.shape()))
+        .ok()
+        .and_then(|batch| batch.fixed_dims())
+        .ok_or_else(incompatible)?;
+
+    zip_numeric!(lhs.storage(), rhs.storage(), |lhs_data, rhs_data| {
+        (
+            matmul(lhs_data, &lhs_matrix, rhs_data, &rhs_matrix, &batch, arena),
+            output_shape.to_vec(),
+        )
+    })
+    .ok_or_else(|| {
+        KernelError::unsupported("matmul", &[lhs.storage(), rhs.storage()])
+    })
+}
+
+/// How the logical `[rows, cols]` matrices of one operand sit in memory.
+/// `batch` locates the first element of each matrix.
+struct MatrixLayout {
+    batch: Layout,
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl MatrixLayout {
+    fn new(layout: &Layout, transpose: bool, is_rhs: bool) -> Self {
+        let split = layout.shape().len().saturating_sub(2);
+        let (batch, matrix) = layout.shape().split_at(split);
+        let (batch_strides, strides) = layout.strides().split_at(split);
+
+        let (rows, cols, row_stride, col_stride) = match (matrix, strides) {
+            // A vector is a single row on the left and a column on the right.
+            (&[len], &[stride]) if is_rhs => (len, 1, stride, 0),
+            (&[len], &[stride]) => (1, len, 0, stride),
+            (&[rows, cols], &[row_stride, col_stride]) if transpose => {
+                (cols, rows, col_stride, row_stride)
+            }
+            (&[rows, cols], &[row_stride, col_stride]) => {
+                (rows, cols, row_stride, col_stride)
+            }
+            _ => unreachable!("matmul operands have at least one dimension"),
+        };
+
+        Self {
+            batch: Layout::new(
+                batch.to_vec(),
+                batch_strides.to_vec(),
+                layout.offset(),
+            ),
+            rows,
+            cols,
+            row_stride,
+            col_stride,
+        }
+    }
+
+    fn offset(&self, row: usize, col: usize) -> usize {
+        row * self.row_stride + col * self.col_stride
+    }
+}
+
+fn matmul<T: Numeric>(
+    lhs_data: &[T],
+    lhs: &MatrixLayout,
+    rhs_data: &[T],
+    rhs: &MatrixLayout,
+    batch: &[usize],
+    arena: &mut Arena,
+) -> Vec<T> {
+    let (m, k, n) = (lhs.rows, lhs.cols, rhs.cols);
+
+    // Both batches broadcast to `batch`, checked by the caller.
+    let lhs_batch = lhs.batch.broadcast_to(batch).unwrap();
+    let rhs_batch = rhs.batch.broadcast_to(batch).unwrap();
+
+    let batch_len: usize = batch.iter().product();
+    let mut result = arena.take(batch_len * m * n);
+    result.resize(batch_len * m * n, T::ZERO);
+    let mut rhs_tile = [T::ZERO; BLOCK_SIZE * BLOCK_SIZE];
+
+    for batch_idx in 0..batch_len {
+        let lhs_matrix = &lhs_data[lhs_batch.index(batch_idx)..];
+        let rhs_matrix = &rhs_data[rhs_batch.index(batch_idx)..];
+        let output = &mut result[batch_idx * m * n..(batch_idx + 1) * m * n];
+
+        for k0 in (0..k).step_by(BLOCK_SIZE) {
+            let k1 = (k0 + BLOCK_SIZE).min(k);
+
+            for j0 in (0..n).step_by(BLOCK_SIZE) {
+                let j1 = (j0 + BLOCK_SIZE).min(n);
+                let width = j1 - j0;
+
+                // Pack the rhs tile row-major so the inner loop is contiguous
+                // whether or not rhs is transposed.
+                for kk in k0..k1 {
+                    for j in j0..j1 {
+                        rhs_tile[(kk - k0) * width + (j - j0)] =
+                            rhs_matrix[rhs.offset(kk, j)];
+                    }
+                }
+
+                for i in 0..m {
+                    let output_row = &mut output[i * n + j0..i * n + j1];
+
+                    for kk in k0..k1 {
+                        let a = lhs_matrix[lhs.offset(i, kk)];
+                        let tile_row =
+                            &rhs_tile[(kk - k0) * width..(kk - k0 + 1) * width];
+
+                        for (value, &b) in output_row.iter_mut().zip(tile_row) {
+                            *value = value.add(a.mul(b));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
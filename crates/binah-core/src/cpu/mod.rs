@@ -1,136 +1,401 @@
-use crate::tensor::{shape::Shape, storage::TensorStorage};
-
-pub fn cpu_add(lhs: &TensorStorage, rhs: &TensorStorage, output_shape: &Shape) -> TensorStorage {
-    match (lhs, rhs) {
-        (TensorStorage::F32 { data: lhs_data, shape: lhs_shape }, 
-         TensorStorage::F32 { data: rhs_data, shape: rhs_shape }) => {
-            let lhs_shape = Shape::from(lhs_shape.clone());
-            let rhs_shape = Shape::from(rhs_shape.clone());
-            
-            let lhs_strides = lhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            let rhs_strides = rhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            
-            let output_size = output_shape.num_elements();
-            let mut result = vec![0.0f32; output_size];
-            
-            for i in 0..output_size {
-                let lhs_idx = compute_index(i, &lhs_strides, &output_shape.dims());
-                let rhs_idx = compute_index(i, &rhs_strides, &output_shape.dims());
-                
-                result[i] = lhs_data[lhs_idx] + rhs_data[rhs_idx];
-            }
-            
-            TensorStorage::F32 { 
-                data: result, 
-                shape: output_shape.dims().to_vec() 
-            }
+use std::borrow::Cow;
+
+use crate::tensor::{
+    layout::{Layout, compute_index},
+    storage::TensorStorage,
+    view::TensorView,
+};
+
+use element::{Element, Numeric};
+
+/// Expands to a `match` over every storage variant, rebuilding the same
+/// variant from `$body` evaluated with the variant's data bound. `$body`
+/// returns the new data and shape.
+macro_rules! map_storage {
+    ($storage:expr, |$data:ident| $body:expr) => {
+        map_storage!(
+            @variants $storage, |$data| $body;
+            Bool, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+    (@variants $storage:expr, |$data:ident| $body:expr;
+        $($variant:ident),*) => {
+        match $storage {
+            $(
+                TensorStorage::$variant { data: $data, .. } => {
+                    let (data, shape) = $body;
+                    TensorStorage::$variant { data, shape }
+                }
+            )*
         }
-        _ => panic!("Unsupported tensor types for addition"),
-    }
+    };
 }
 
-pub fn cpu_sub(lhs: &TensorStorage, rhs: &TensorStorage, output_shape: &Shape) -> TensorStorage {
-    match (lhs, rhs) {
-        (TensorStorage::F32 { data: lhs_data, shape: lhs_shape }, 
-         TensorStorage::F32 { data: rhs_data, shape: rhs_shape }) => {
-            let lhs_shape = Shape::from(lhs_shape.clone());
-            let rhs_shape = Shape::from(rhs_shape.clone());
-            
-            let lhs_strides = lhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            let rhs_strides = rhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            
-            let output_size = output_shape.num_elements();
-            let mut result = vec![0.0f32; output_size];
-            
-            for i in 0..output_size {
-                let lhs_idx = compute_index(i, &lhs_strides, &output_shape.dims());
-                let rhs_idx = compute_index(i, &rhs_strides, &output_shape.dims());
-                
-                result[i] = lhs_data[lhs_idx] - rhs_data[rhs_idx];
-            }
-            
-            TensorStorage::F32 { 
-                data: result, 
-                shape: output_shape.dims().to_vec() 
-            }
+/// Like [`map_storage!`] for numeric variants only; evaluates to `None` for
+/// `Bool`.
+macro_rules! map_numeric {
+    ($storage:expr, |$data:ident| $body:expr) => {
+        map_numeric!(
+            @variants $storage, |$data| $body;
+            U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+    (@variants $storage:expr, |$data:ident| $body:expr;
+        $($variant:ident),*) => {
+        match $storage {
+            $(
+                TensorStorage::$variant { data: $data, .. } => {
+                    let (data, shape) = $body;
+                    Some(TensorStorage::$variant { data, shape })
+                }
+            )*
+            TensorStorage::Bool { .. } => None,
         }
-        _ => panic!("Unsupported tensor types for subtraction"),
-    }
+    };
 }
 
-pub fn cpu_mul(lhs: &TensorStorage, rhs: &TensorStorage, output_shape: &Shape) -> TensorStorage {
-    match (lhs, rhs) {
-        (TensorStorage::F32 { data: lhs_data, shape: lhs_shape }, 
-         TensorStorage::F32 { data: rhs_data, shape: rhs_shape }) => {
-            let lhs_shape = Shape::from(lhs_shape.clone());
-            let rhs_shape = Shape::from(rhs_shape.clone());
-            
-            let lhs_strides = lhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            let rhs_strides = rhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            
-            let output_size = output_shape.num_elements();
-            let mut result = vec![0.0f32; output_size];
-            
-            for i in 0..output_size {
-                let lhs_idx = compute_index(i, &lhs_strides, &output_shape.dims());
-                let rhs_idx = compute_index(i, &rhs_strides, &output_shape.dims());
-                
-                result[i] = lhs_data[lhs_idx] * rhs_data[rhs_idx];
+/// Like [`map_storage!`] for floating-point variants only; evaluates to
+/// `None` otherwise.
+macro_rules! map_float {
+    ($storage:expr, |$data:ident| $body:expr) => {
+        match $storage {
+            TensorStorage::F32 { data: $data, .. } => {
+                let (data, shape) = $body;
+                Some(TensorStorage::F32 { data, shape })
             }
-            
-            TensorStorage::F32 { 
-                data: result, 
-                shape: output_shape.dims().to_vec() 
+            TensorStorage::F64 { data: $data, .. } => {
+                let (data, shape) = $body;
+                Some(TensorStorage::F64 { data, shape })
             }
+            _ => None,
         }
-        _ => panic!("Unsupported tensor types for multiplication"),
-    }
+    };
 }
 
-pub fn cpu_div(lhs: &TensorStorage, rhs: &TensorStorage, output_shape: &Shape) -> TensorStorage {
-    match (lhs, rhs) {
-        (TensorStorage::F32 { data: lhs_data, shape: lhs_shape }, 
-         TensorStorage::F32 { data: rhs_data, shape: rhs_shape }) => {
-            let lhs_shape = Shape::from(lhs_shape.clone());
-            let rhs_shape = Shape::from(rhs_shape.clone());
-            
-            let lhs_strides = lhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            let rhs_strides = rhs_shape.compute_broadcast_strides(output_shape).unwrap();
-            
-            let output_size = output_shape.num_elements();
-            let mut result = vec![0.0f32; output_size];
-            
-            for i in 0..output_size {
-                let lhs_idx = compute_index(i, &lhs_strides, &output_shape.dims());
-                let rhs_idx = compute_index(i, &rhs_strides, &output_shape.dims());
-                
-                result[i] = lhs_data[lhs_idx] / rhs_data[rhs_idx];
-            }
-            
-            TensorStorage::F32 { 
-                data: result, 
-                shape: output_shape.dims().to_vec() 
-            }
+/// Like [`map_float!`], evaluating `$body` with the data bound instead of
+/// rebuilding the storage.
+macro_rules! with_float {
+    ($storage:expr, |$data:ident| $body:expr) => {
+        match $storage {
+            TensorStorage::F32 { data: $data, .. } => Some($body),
+            TensorStorage::F64 { data: $data, .. } => Some($body),
+            _ => None,
         }
-        _ => panic!("Unsupported tensor types for division"),
+    };
+}
+
+/// Evaluates `$body` with the data of a numeric storage bound. Evaluates to
+/// `None` for `Bool`.
+macro_rules! with_numeric {
+    ($storage:expr, |$data:ident| $body:expr) => {
+        with_numeric!(
+            @variants $storage, |$data| $body;
+            U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+    (@variants $storage:expr, |$data:ident| $body:expr;
+        $($variant:ident),*) => {
+        match $storage {
+            $(
+                TensorStorage::$variant { data: $data, .. } => Some($body),
+            )*
+            TensorStorage::Bool { .. } => None,
+        }
+    };
+}
+
+/// Matches two storages of the same numeric variant and rebuilds that
+/// variant from `$body`. Evaluates to `None` when the variants differ or are
+/// `Bool`.
+macro_rules! zip_numeric {
+    ($lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident| $body:expr) => {
+        zip_numeric!(
+            @variants $lhs, $rhs, |$lhs_data, $rhs_data| $body;
+            U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+    (
+        @variants $lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident|
+        $body:expr;
+        $($variant:ident),*
+    ) => {
+        match ($lhs, $rhs) {
+            $(
+                (
+                    TensorStorage::$variant { data: $lhs_data, .. },
+                    TensorStorage::$variant { data: $rhs_data, .. },
+                ) => {
+                    let (data, shape) = $body;
+                    Some(TensorStorage::$variant { data, shape })
+                }
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// Like [`zip_numeric!`] over every variant, `Bool` included.
+macro_rules! zip_storage {
+    ($lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident| $body:expr) => {
+        zip_numeric!(
+            @variants $lhs, $rhs, |$lhs_data, $rhs_data| $body;
+            Bool, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+}
+
+/// Evaluates `$body` with the data of two storages of the same variant
+/// bound, `Bool` included. Evaluates to `None` when the variants differ.
+macro_rules! with_storage_pair {
+    ($lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident| $body:expr) => {
+        with_storage_pair!(
+            @variants $lhs, $rhs, |$lhs_data, $rhs_data| $body;
+            Bool, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+    (
+        @variants $lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident|
+        $body:expr;
+        $($variant:ident),*
+    ) => {
+        match ($lhs, $rhs) {
+            $(
+                (
+                    TensorStorage::$variant { data: $lhs_data, .. },
+                    TensorStorage::$variant { data: $rhs_data, .. },
+                ) => Some($body),
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// Like [`with_storage_pair!`] for numeric variants only.
+macro_rules! with_numeric_pair {
+    ($lhs:expr, $rhs:expr, |$lhs_data:ident, $rhs_data:ident| $body:expr) => {
+        with_storage_pair!(
+            @variants $lhs, $rhs, |$lhs_data, $rhs_data| $body;
+            U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64
+        )
+    };
+}
+
+/// Applies `$op` to every pair of elements of two numeric views of the same
+/// variant, broadcasting to `$output_shape`. Must be used in a function
+/// returning [`KernelError`], which broadcasting failures are returned as.
+macro_rules! broadcast_numeric {
+    ($name:expr, $lhs:expr, $rhs:expr, $output_shape:expr, $op:expr) => {
+        zip_numeric!($lhs.storage(), $rhs.storage(), |lhs_data, rhs_data| {
+            let data = $crate::cpu::broadcast_binary(
+                $name,
+                lhs_data,
+                $lhs.layout(),
+                rhs_data,
+                $rhs.layout(),
+                $output_shape,
+                $op,
+            )?;
+            (data, $output_shape.to_vec())
+        })
+    };
+}
+
+mod arena;
+mod binary;
+mod cast;
+mod concat;
+pub mod element;
+mod error;
+mod fused;
+mod index;
+mod matmul;
+mod random;
+mod reduce;
+mod softmax;
+mod unary;
+
+pub use arena::Arena;
+pub use binary::{
+    cpu_add, cpu_add_in_place, cpu_div, cpu_div_in_place, cpu_equal,
+    cpu_greater, cpu_greater_equal, cpu_less, cpu_less_equal, cpu_mul,
+    cpu_mul_in_place, cpu_not_equal, cpu_sub, cpu_sub_in_place,
+};
+pub use cast::cpu_cast;
+pub use concat::cpu_concat;
+pub use error::KernelError;
+pub use fused::cpu_fused;
+pub use index::{
+    cpu_gather, cpu_index_select, cpu_scatter, cpu_slice_scatter, cpu_where,
+};
+pub use matmul::cpu_matmul;
+pub(crate) use random::splitmix64;
+pub use random::cpu_uniform;
+pub use reduce::{
+    cpu_argmax, cpu_argmin, cpu_max, cpu_mean, cpu_min, cpu_prod, cpu_sum,
+};
+pub use softmax::{cpu_log_softmax, cpu_softmax};
+pub use unary::{
+    cpu_abs, cpu_abs_in_place, cpu_ceil, cpu_ceil_in_place, cpu_cos,
+    cpu_cos_in_place, cpu_erf, cpu_erf_in_place, cpu_exp, cpu_exp_in_place,
+    cpu_floor, cpu_floor_in_place, cpu_gelu, cpu_gelu_in_place, cpu_log,
+    cpu_log_in_place, cpu_neg, cpu_neg_in_place, cpu_reciprocal,
+    cpu_reciprocal_in_place, cpu_relu, cpu_relu_in_place, cpu_round,
+    cpu_round_in_place, cpu_rsqrt, cpu_rsqrt_in_place, cpu_sigmoid,
+    cpu_sigmoid_in_place, cpu_sign, cpu_sign_in_place, cpu_sin,
+    cpu_sin_in_place, cpu_sqrt, cpu_sqrt_in_place, cpu_tanh, cpu_tanh_in_place,
+};
+
+pub fn cpu_sum_to(
+    input: &TensorView,
+    target_shape: &[usize],
+) -> Result<TensorStorage, KernelError> {
+    let output_strides = Layout::contiguous(target_shape)
+        .broadcast_strides(input.shape())
+        .map_err(|_| KernelError::IncompatibleShapes {
+            op: "sum_to",
+            lhs: input.shape().to_vec(),
+            rhs: target_shape.to_vec(),
+        })?;
+
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+
+        (
+            sum_to(&data, input.shape(), target_shape, &output_strides),
+            target_shape.to_vec(),
+        )
+    })
+    .ok_or_else(|| KernelError::unsupported("sum_to", &[input.storage()]))
+}
+
+pub fn cpu_ones_like(input: &TensorView) -> TensorStorage {
+    cpu_full_like(input, 1.0)
+}
+
+pub fn cpu_zeros_like(input: &TensorView) -> TensorStorage {
+    cpu_full_like(input, 0.0)
+}
+
+pub fn cpu_full_like(input: &TensorView, value: f64) -> TensorStorage {
+    let len = input.layout().num_elements();
+
+    map_storage!(input.storage(), |_data| {
+        (vec![Element::from_f64(value); len], input.shape().to_vec())
+    })
+}
+
+/// Like [`cpu_full_like`], but fills the buffer of `input` when
+/// [`exclusive`] hands it over.
+pub fn cpu_full_like_in_place(input: TensorView, value: f64) -> TensorStorage {
+    let shape = input.shape().to_vec();
+    let storage = match exclusive(input, &shape) {
+        Ok(storage) => storage,
+        Err(input) => return cpu_full_like(&input, value),
+    };
+
+    map_storage!(storage, |data| {
+        let mut data = data;
+        data.fill(Element::from_f64(value));
+        (data, shape)
+    })
+}
+
+/// The storage of `view`, to write a result of `shape` over, when `view`
+/// is the only view of it and reads all of it in row-major order with
+/// that shape. Hands `view` back otherwise.
+pub(crate) fn exclusive(
+    mut view: TensorView,
+    shape: &[usize],
+) -> Result<TensorStorage, TensorView> {
+    let layout = view.layout();
+    let aligned = view.shape() == shape
+        && view.storage().shape() == shape
+        && layout.is_contiguous()
+        && layout.offset() == 0;
+    if !aligned || view.storage_mut().is_none() {
+        return Err(view);
     }
+
+    Ok(view.into_storage().expect("no other view shares the storage"))
 }
 
-fn compute_index(linear_idx: usize, strides: &[usize], shape: &[usize]) -> usize {
-    let mut index = 0;
-    let mut remaining = linear_idx;
-    
-    for i in 0..shape.len() {
-        let dim_size = shape[i];
-        let stride = strides[i];
-        
-        // Calculate size of remaining dimensions
-        let next_size: usize = shape[i+1..].iter().product();
-        
-        let coord = (remaining / next_size) % dim_size;
-        index += coord * stride;
-        remaining %= next_size;
+/// Copies the elements of `input` out in row-major order.
+pub fn cpu_contiguous(input: &TensorView) -> TensorStorage {
+    map_storage!(input.storage(), |data| {
+        (
+            contiguous_data(data, input.layout()).into_owned(),
+            input.shape().to_vec(),
+        )
+    })
+}
+
+/// The elements of a view in row-major order, borrowed when they already
+/// sit that way in `data`.
+pub(crate) fn contiguous_data<'a, T: Copy>(
+    data: &'a [T],
+    layout: &Layout,
+) -> Cow<'a, [T]> {
+    let len = layout.num_elements();
+
+    if layout.is_contiguous() {
+        let start = layout.offset();
+        Cow::Borrowed(&data[start..start + len])
+    } else {
+        Cow::Owned((0..len).map(|i| data[layout.index(i)]).collect())
+    }
+}
+
+fn sum_to<T: Numeric>(
+    data: &[T],
+    shape: &[usize],
+    target_shape: &[usize],
+    output_strides: &[usize],
+) -> Vec<T> {
+    let mut result = vec![T::ZERO; target_shape.iter().product()];
+
+    for (i, &value) in data.iter().enumerate() {
+        let output_idx = compute_index(i, output_strides, shape);
+
+        result[output_idx] = result[output_idx].add(value);
     }
-    
-    index
-}
\ No newline at end of file
+
+    result
+}
+
+/// Reads both operands through their layouts, so broadcast and permuted
+/// views are consumed without being copied first.
+pub(crate) fn broadcast_binary<T: Copy, U>(
+    name: &'static str,
+    lhs_data: &[T],
+    lhs_layout: &Layout,
+    rhs_data: &[T],
+    rhs_layout: &Layout,
+    output_shape: &[usize],
+    op: impl Fn(T, T) -> U,
+) -> Result<Vec<U>, KernelError> {
+    let lhs_layout = broadcast_layout(name, lhs_layout, output_shape)?;
+    let rhs_layout = broadcast_layout(name, rhs_layout, output_shape)?;
+
+    let data = (0..output_shape.iter().product())
+        .map(|i| {
+            op(lhs_data[lhs_layout.index(i)], rhs_data[rhs_layout.index(i)])
+        })
+        .collect();
+
+    Ok(data)
+}
+
+/// `layout` broadcast to `target`, for an operand of `name`.
+pub(crate) fn broadcast_layout(
+    name: &'static str,
+    layout: &Layout,
+    target: &[usize],
+) -> Result<Layout, KernelError> {
+    layout
+        .broadcast_to(target)
+        .map_err(|_| KernelError::IncompatibleShapes {
+            op: name,
+            lhs: layout.shape().to_vec(),
+            rhs: target.to_vec(),
+        })
+}
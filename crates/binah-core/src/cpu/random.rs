@@ -0,0 +1,48 @@
+use crate::tensor::{
+    storage::{DType, TensorStorage},
+    view::TensorView,
+};
+
+use super::{Arena, KernelError, cpu_cast, element::Element};
+
+/// Uniform samples in `[0, 1)` with the shape and float type of `like`,
+/// determined by `seed`, the integer scalar `key` and each element's
+/// position. Samples have 24 random bits so that they stay below 1 in
+/// either float type.
+pub fn cpu_uniform(
+    like: &TensorView,
+    key: &TensorView,
+    seed: u64,
+) -> Result<TensorStorage, KernelError> {
+    let unsupported = || {
+        KernelError::unsupported("uniform", &[like.storage(), key.storage()])
+    };
+
+    let key = match cpu_cast(key, DType::U64, &mut Arena::default()) {
+        TensorStorage::U64 { data, .. } if data.len() == 1 => data[0],
+        _ => return Err(unsupported()),
+    };
+    let stream = splitmix64(seed ^ splitmix64(key));
+    let len = like.layout().num_elements() as u64;
+
+    map_float!(like.storage(), |_data| {
+        let data = (0..len)
+            .map(|i| {
+                let bits = splitmix64(stream.wrapping_add(i)) >> 40;
+                Element::from_f64(bits as f64 / (1u64 << 24) as f64)
+            })
+            .collect();
+
+        (data, like.shape().to_vec())
+    })
+    .ok_or_else(unsupported)
+}
+
+/// One step of the SplitMix64 generator: a bijective scramble of `state`
+/// advanced by the golden-ratio increment.
+pub(crate) fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,280 @@
+use crate::tensor::{
+    layout::{compute_index, contiguous_strides},
+    storage::TensorStorage,
+    view::TensorView,
+};
+
+use super::{Arena, KernelError, contiguous_data, element::Numeric};
+
+pub fn cpu_sum(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        (
+            sum(&data, shape, axes, arena),
+            reduced_dims(shape, axes, keepdims),
+        )
+    })
+    .ok_or_else(|| KernelError::unsupported("sum", &[input.storage()]))
+}
+
+/// Integer means truncate toward zero.
+pub fn cpu_mean(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        (
+            mean(&data, shape, axes, arena),
+            reduced_dims(shape, axes, keepdims),
+        )
+    })
+    .ok_or_else(|| KernelError::unsupported("mean", &[input.storage()]))
+}
+
+pub fn cpu_prod(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        (
+            prod(&data, shape, axes, arena),
+            reduced_dims(shape, axes, keepdims),
+        )
+    })
+    .ok_or_else(|| KernelError::unsupported("prod", &[input.storage()]))
+}
+
+/// NaN propagates. Fails when reducing over an empty dimension.
+pub fn cpu_max(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        let best =
+            arg_reduce("max", &data, shape, axes, Numeric::greater_than)?;
+        let mut result = arena.take(best.len());
+        result.extend(best.into_iter().map(|(value, _)| value));
+
+        (result, reduced_dims(shape, axes, keepdims))
+    })
+    .ok_or_else(|| KernelError::unsupported("max", &[input.storage()]))
+}
+
+/// NaN propagates. Fails when reducing over an empty dimension.
+pub fn cpu_min(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        let best = arg_reduce("min", &data, shape, axes, Numeric::less_than)?;
+        let mut result = arena.take(best.len());
+        result.extend(best.into_iter().map(|(value, _)| value));
+
+        (result, reduced_dims(shape, axes, keepdims))
+    })
+    .ok_or_else(|| KernelError::unsupported("min", &[input.storage()]))
+}
+
+/// Returns `I64` indices of the first maximum, flattened row-major over the
+/// reduced axes.
+pub fn cpu_argmax(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let indices = with_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+
+        let best = arg_reduce(
+            "argmax",
+            &data,
+            input.shape(),
+            axes,
+            Numeric::greater_than,
+        )?;
+        let mut indices = arena.take(best.len());
+        indices.extend(best.into_iter().map(|(_, index)| index as i64));
+        indices
+    })
+    .ok_or_else(|| KernelError::unsupported("argmax", &[input.storage()]))?;
+
+    Ok(TensorStorage::I64 {
+        data: indices,
+        shape: reduced_dims(input.shape(), axes, keepdims),
+    })
+}
+
+/// Returns `I64` indices of the first minimum, flattened row-major over the
+/// reduced axes.
+pub fn cpu_argmin(
+    input: &TensorView,
+    axes: &[usize],
+    keepdims: bool,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    let indices = with_numeric!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+
+        let best = arg_reduce(
+            "argmin",
+            &data,
+            input.shape(),
+            axes,
+            Numeric::less_than,
+        )?;
+        let mut indices = arena.take(best.len());
+        indices.extend(best.into_iter().map(|(_, index)| index as i64));
+        indices
+    })
+    .ok_or_else(|| KernelError::unsupported("argmin", &[input.storage()]))?;
+
+    Ok(TensorStorage::I64 {
+        data: indices,
+        shape: reduced_dims(input.shape(), axes, keepdims),
+    })
+}
+
+fn reduced_dims(shape: &[usize], axes: &[usize], keepdims: bool) -> Vec<usize> {
+    shape
+        .iter()
+        .enumerate()
+        .filter_map(|(axis, &dim)| match axes.contains(&axis) {
+            true if keepdims => Some(1),
+            true => None,
+            false => Some(dim),
+        })
+        .collect()
+}
+
+fn sum<T: Numeric>(
+    data: &[T],
+    shape: &[usize],
+    axes: &[usize],
+    arena: &mut Arena,
+) -> Vec<T> {
+    let result = arena.take(reduced_dims(shape, axes, false).iter().product());
+
+    reduce(data, shape, axes, T::ZERO, result, |acc, value, _| {
+        acc.add(value)
+    })
+}
+
+fn mean<T: Numeric>(
+    data: &[T],
+    shape: &[usize],
+    axes: &[usize],
+    arena: &mut Arena,
+) -> Vec<T> {
+    let count: usize = axes.iter().map(|&axis| shape[axis]).product();
+
+    let mut result = sum(data, shape, axes, arena);
+    for total in &mut result {
+        *total = total.div(T::from_usize(count));
+    }
+
+    result
+}
+
+fn prod<T: Numeric>(
+    data: &[T],
+    shape: &[usize],
+    axes: &[usize],
+    arena: &mut Arena,
+) -> Vec<T> {
+    let result = arena.take(reduced_dims(shape, axes, false).iter().product());
+
+    reduce(data, shape, axes, T::ONE, result, |acc, value, _| {
+        acc.mul(value)
+    })
+}
+
+/// Keeps the first element for which `wins` holds against the current best,
+/// together with its position within the reduced axes.
+fn arg_reduce<T: Numeric>(
+    name: &'static str,
+    data: &[T],
+    shape: &[usize],
+    axes: &[usize],
+    wins: impl Fn(T, T) -> bool,
+) -> Result<Vec<(T, usize)>, KernelError> {
+    reduce(
+        data,
+        shape,
+        axes,
+        None,
+        Vec::new(),
+        |best, value, index| match best {
+            Some((best_value, _)) if !wins(value, best_value) => best,
+            _ => Some((value, index)),
+        },
+    )
+    .into_iter()
+    .map(|best| best.ok_or(KernelError::EmptyReduction { op: name }))
+    .collect()
+}
+
+/// Folds every input element into its output slot of `result`, an empty
+/// buffer. `combine` also receives the element's row-major position within
+/// the reduced axes.
+fn reduce<T: Copy, A: Copy>(
+    data: &[T],
+    shape: &[usize],
+    axes: &[usize],
+    init: A,
+    mut result: Vec<A>,
+    combine: impl Fn(A, T, usize) -> A,
+) -> Vec<A> {
+    // Row-major strides of the output, skipping the reduced axes.
+    let output_shape = reduced_dims(shape, axes, true);
+    let mut output_strides = contiguous_strides(&output_shape);
+    for &axis in axes {
+        output_strides[axis] = 0;
+    }
+
+    // Strides of the reduced axes alone, zero elsewhere.
+    let mut reduced_strides = vec![0; shape.len()];
+    let mut stride = 1;
+    for &axis in axes.iter().rev() {
+        reduced_strides[axis] = stride;
+        stride *= shape[axis];
+    }
+
+    result.resize(output_shape.iter().product(), init);
+
+    for (i, &value) in data.iter().enumerate() {
+        let output_idx = compute_index(i, &output_strides, shape);
+        let reduced_idx = compute_index(i, &reduced_strides, shape);
+
+        result[output_idx] = combine(result[output_idx], value, reduced_idx);
+    }
+
+    result
+}
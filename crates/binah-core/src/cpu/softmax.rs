@@ -0,0 +1,84 @@
+use crate::tensor::{storage::TensorStorage, view::TensorView};
+
+use super::{Arena, KernelError, contiguous_data, element::Float};
+
+/// `exp(x) / sum(exp(x))` along `axis`. Each lane is shifted by its
+/// maximum first, so no element overflows `exp`. The result goes to a
+/// buffer from `arena`.
+pub fn cpu_softmax(
+    input: &TensorView,
+    axis: usize,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_float!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        (softmax(&data, shape, axis, false, arena), shape.to_vec())
+    })
+    .ok_or_else(|| KernelError::unsupported("softmax", &[input.storage()]))
+}
+
+/// `x - log(sum(exp(x)))` along `axis`, shifted like [`cpu_softmax`].
+pub fn cpu_log_softmax(
+    input: &TensorView,
+    axis: usize,
+    arena: &mut Arena,
+) -> Result<TensorStorage, KernelError> {
+    map_float!(input.storage(), |data| {
+        let data = contiguous_data(data, input.layout());
+        let shape = input.shape();
+
+        (softmax(&data, shape, axis, true, arena), shape.to_vec())
+    })
+    .ok_or_else(|| KernelError::unsupported("log_softmax", &[input.storage()]))
+}
+
+/// Normalizes every lane along `axis` of row-major `data` in one sweep:
+/// its maximum, then the sum of the shifted exponentials, then the output.
+/// A lane holding NaN becomes NaN.
+fn softmax<T: Float>(
+    data: &[T],
+    shape: &[usize],
+    axis: usize,
+    log: bool,
+    arena: &mut Arena,
+) -> Vec<T> {
+    let mut result = arena.take(data.len());
+    result.resize(data.len(), T::ZERO);
+    let len = shape[axis];
+    let stride: usize = shape[axis + 1..].iter().product();
+    if len == 0 || stride == 0 {
+        return result;
+    }
+
+    let block = len * stride;
+    for (input, output) in data.chunks(block).zip(result.chunks_mut(block)) {
+        for lane in 0..stride {
+            let positions = (0..len).map(|k| k * stride + lane);
+
+            let max = positions
+                .clone()
+                .map(|i| input[i])
+                .reduce(|max, x| if x.greater_than(max) { x } else { max })
+                .unwrap_or(T::ZERO);
+
+            let mut total = T::ZERO;
+            for i in positions.clone() {
+                let e = input[i].sub(max).exp();
+                total = total.add(e);
+                output[i] = e;
+            }
+
+            let log_total = total.ln();
+            for i in positions {
+                output[i] = match log {
+                    true => input[i].sub(max).sub(log_total),
+                    false => output[i].div(total),
+                };
+            }
+        }
+    }
+
+    result
+}
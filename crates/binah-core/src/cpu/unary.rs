@@ -0,0 +1,210 @@
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI, PI};
+
+use crate::tensor::{storage::TensorStorage, view::TensorView};
+
+use super::{
+    KernelError, contiguous_data,
+    element::{Float, Numeric},
+    exclusive,
+};
+
+/// Defines a kernel applying a scalar function to every element of any
+/// numeric storage, and its in-place variant.
+macro_rules! numeric_kernels {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident, $in_place:ident => $op:path, $label:literal;
+    )*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(
+                input: &TensorView,
+            ) -> Result<TensorStorage, KernelError> {
+                map_numeric!(input.storage(), |data| {
+                    let data = contiguous_data(data, input.layout());
+
+                    (
+                        data.iter().map(|&x| $op(x)).collect(),
+                        input.shape().to_vec(),
+                    )
+                })
+                .ok_or_else(|| {
+                    KernelError::unsupported($label, &[input.storage()])
+                })
+            }
+
+            in_place_kernel!($name, $in_place, with_numeric, $op, $label);
+        )*
+    };
+}
+
+/// Defines a kernel applying a scalar function to every element of a
+/// floating-point storage, and its in-place variant.
+macro_rules! float_kernels {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident, $in_place:ident => $op:path, $label:literal;
+    )*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(
+                input: &TensorView,
+            ) -> Result<TensorStorage, KernelError> {
+                map_float!(input.storage(), |data| {
+                    let data = contiguous_data(data, input.layout());
+
+                    (
+                        data.iter().map(|&x| $op(x)).collect(),
+                        input.shape().to_vec(),
+                    )
+                })
+                .ok_or_else(|| {
+                    KernelError::unsupported($label, &[input.storage()])
+                })
+            }
+
+            in_place_kernel!($name, $in_place, with_float, $op, $label);
+        )*
+    };
+}
+
+/// Defines `$in_place`, which applies `$op` over the buffer of its input
+/// when [`exclusive`] hands it over and otherwise calls `$name`. `$with`
+/// is the macro binding the data of the storages `$op` supports.
+macro_rules! in_place_kernel {
+    ($name:ident, $in_place:ident, $with:ident, $op:path, $label:literal) => {
+        #[doc = concat!(
+            "Like [`", stringify!($name), "`], but writes over the buffer ",
+            "of `input` when no other view shares it."
+        )]
+        pub fn $in_place(
+            input: TensorView,
+        ) -> Result<TensorStorage, KernelError> {
+            let shape = input.shape().to_vec();
+            let mut storage = match exclusive(input, &shape) {
+                Ok(storage) => storage,
+                Err(input) => return $name(&input),
+            };
+
+            $with!(&mut storage, |data| {
+                for x in data.iter_mut() {
+                    *x = $op(*x);
+                }
+            })
+            .ok_or_else(|| KernelError::unsupported($label, &[&storage]))?;
+
+            Ok(storage)
+        }
+    };
+}
+
+numeric_kernels! {
+    /// Wraps for unsigned integers.
+    cpu_neg, cpu_neg_in_place => Numeric::neg, "neg";
+    cpu_abs, cpu_abs_in_place => Numeric::abs, "abs";
+    /// -1, 0 or 1; NaN stays NaN.
+    cpu_sign, cpu_sign_in_place => sign, "sign";
+    cpu_relu, cpu_relu_in_place => relu, "relu";
+}
+
+float_kernels! {
+    cpu_exp, cpu_exp_in_place => Float::exp, "exp";
+    /// Natural logarithm.
+    cpu_log, cpu_log_in_place => Float::ln, "log";
+    cpu_sqrt, cpu_sqrt_in_place => Float::sqrt, "sqrt";
+    cpu_rsqrt, cpu_rsqrt_in_place => rsqrt, "rsqrt";
+    cpu_sin, cpu_sin_in_place => Float::sin, "sin";
+    cpu_cos, cpu_cos_in_place => Float::cos, "cos";
+    cpu_tanh, cpu_tanh_in_place => Float::tanh, "tanh";
+    cpu_sigmoid, cpu_sigmoid_in_place => sigmoid, "sigmoid";
+    /// Exact GELU, `x * Φ(x)`.
+    cpu_gelu, cpu_gelu_in_place => gelu, "gelu";
+    cpu_erf, cpu_erf_in_place => erf, "erf";
+    cpu_floor, cpu_floor_in_place => Float::floor, "floor";
+    cpu_ceil, cpu_ceil_in_place => Float::ceil, "ceil";
+    /// Rounds half to even.
+    cpu_round, cpu_round_in_place => Float::round, "round";
+    cpu_reciprocal, cpu_reciprocal_in_place => reciprocal, "reciprocal";
+}
+
+pub(crate) fn sign<T: Numeric>(x: T) -> T {
+    if x.is_nan() {
+        x
+    } else if x > T::ZERO {
+        T::ONE
+    } else if x < T::ZERO {
+        T::ONE.neg()
+    } else {
+        T::ZERO
+    }
+}
+
+/// NaN stays NaN.
+pub(crate) fn relu<T: Numeric>(x: T) -> T {
+    if x < T::ZERO { T::ZERO } else { x }
+}
+
+pub(crate) fn rsqrt<T: Float>(x: T) -> T {
+    T::ONE.div(x.sqrt())
+}
+
+pub(crate) fn reciprocal<T: Float>(x: T) -> T {
+    T::ONE.div(x)
+}
+
+/// Evaluated so that `exp` never overflows.
+pub(crate) fn sigmoid<T: Float>(x: T) -> T {
+    if x >= T::ZERO {
+        T::ONE.div(T::ONE.add(x.neg().exp()))
+    } else {
+        let e = x.exp();
+        e.div(T::ONE.add(e))
+    }
+}
+
+pub(crate) fn gelu<T: Float>(x: T) -> T {
+    let cdf = 0.5 * (1.0 + erf_f64(x.to_f64() * FRAC_1_SQRT_2));
+
+    x.mul(T::from_f64(cdf))
+}
+
+pub(crate) fn erf<T: Float>(x: T) -> T {
+    T::from_f64(erf_f64(x.to_f64()))
+}
+
+/// Maclaurin series near zero and the continued fraction for `erfc`
+/// further out, both accurate to a few ulps in `f64`.
+fn erf_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+
+    let z = x.abs();
+    let result = if z < 2.5 {
+        let z2 = z * z;
+        let mut term = z;
+        let mut sum = z;
+        let mut n = 0.0;
+        loop {
+            n += 1.0;
+            term *= -z2 / n;
+            let contribution = term / (2.0 * n + 1.0);
+            sum += contribution;
+            if contribution.abs() <= f64::EPSILON * sum.abs() {
+                break;
+            }
+        }
+        sum * FRAC_2_SQRT_PI
+    } else if z < 6.0 {
+        // erfc(z) = exp(-z^2) / sqrt(pi) / (z + (1/2) / (z + 1 / (z + ...)))
+        let mut fraction = z;
+        for k in (1..=60).rev() {
+            fraction = z + (k as f64 / 2.0) / fraction;
+        }
+        1.0 - (-z * z).exp() / (PI.sqrt() * fraction)
+    } else {
+        1.0
+    };
+
+    result.copysign(x)
+}
@@ -0,0 +1,915 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::{Direction, algo::toposort, graph::NodeIndex, visit::EdgeRef};
+
+use crate::{
+    op::Operation,
+    tensor::shape::{Dim, Shape, Slice},
+};
+
+use super::{Graph, GraphError, GraphInner, GraphTensor};
+
+impl Graph {
+    /// Appends the backward pass of `loss` to the graph and returns one
+    /// gradient tensor per entry of `variables`, in the same order.
+    ///
+    /// A non-scalar `loss` is treated as if it were summed first. Variables
+    /// that `loss` does not depend on get a zero gradient. Fails when the
+    /// graph has a cycle.
+    pub fn gradients(
+        &mut self,
+        loss: &GraphTensor,
+        variables: &[&GraphTensor],
+    ) -> Result<Vec<GraphTensor>, GraphError> {
+        let mut inner = self.inner.borrow_mut();
+
+        let sources: Vec<NodeIndex> =
+            variables.iter().map(|tensor| tensor.node_id()).collect();
+        let relevant = relevant_nodes(&inner, loss.node_id(), &sources);
+
+        let order = toposort(inner.graph(), None).map_err(|cycle| {
+            GraphError::CyclicGraph {
+                node: cycle.node_id(),
+            }
+        })?;
+
+        let mut grads: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let seed = inner.add_unary_op(
+            loss.node_id(),
+            Operation::OnesLike,
+            loss.shape(),
+        );
+        grads.insert(loss.node_id(), seed);
+
+        for node_id in order.into_iter().rev() {
+            if !relevant.contains(&node_id) {
+                continue;
+            }
+            let Some(&grad) = grads.get(&node_id) else {
+                continue;
+            };
+
+            let op = inner.graph()[node_id].clone();
+            let inputs = inner.operands(node_id);
+            let input_grads =
+                backward(&mut inner, &op, node_id, &inputs, grad)?;
+
+            for (input, input_grad) in inputs.into_iter().zip(input_grads) {
+                let Some(input_grad) = input_grad else {
+                    continue;
+                };
+                if !relevant.contains(&input) {
+                    continue;
+                }
+
+                let accumulated = match grads.get(&input) {
+                    Some(&existing) => binary(
+                        &mut inner,
+                        Operation::Add,
+                        existing,
+                        input_grad,
+                    )?,
+                    None => input_grad,
+                };
+                grads.insert(input, accumulated);
+            }
+        }
+
+        let grads = sources
+            .into_iter()
+            .map(|node_id| {
+                let grad = match grads.get(&node_id) {
+                    Some(&grad) => grad,
+                    None => unary(&mut inner, Operation::ZerosLike, node_id),
+                };
+                let shape = inner.shape(grad).clone();
+                let dtype = inner.dtype(grad);
+
+                GraphTensor::new(self.inner.clone(), grad, shape, dtype)
+            })
+            .collect();
+
+        Ok(grads)
+    }
+}
+
+/// Nodes that lie on some path from one of `sources` to `loss`.
+fn relevant_nodes(
+    inner: &GraphInner,
+    loss: NodeIndex,
+    sources: &[NodeIndex],
+) -> HashSet<NodeIndex> {
+    let graph = inner.graph();
+
+    let mut reaches_loss = HashSet::from([loss]);
+    let mut stack = vec![loss];
+    while let Some(node_id) = stack.pop() {
+        for edge in graph.edges_directed(node_id, Direction::Incoming) {
+            if reaches_loss.insert(edge.source()) {
+                stack.push(edge.source());
+            }
+        }
+    }
+
+    let mut relevant = HashSet::new();
+    let mut stack: Vec<NodeIndex> = sources
+        .iter()
+        .copied()
+        .filter(|node_id| reaches_loss.contains(node_id))
+        .collect();
+    while let Some(node_id) = stack.pop() {
+        if !relevant.insert(node_id) {
+            continue;
+        }
+        for edge in graph.edges_directed(node_id, Direction::Outgoing) {
+            if reaches_loss.contains(&edge.target()) {
+                stack.push(edge.target());
+            }
+        }
+    }
+
+    relevant
+}
+
+/// Builds the gradient of each input of the `op` node `output` given the
+/// gradient of its output. Inputs that are not differentiable, such as
+/// indices, get `None`.
+fn backward(
+    inner: &mut GraphInner,
+    op: &Operation,
+    output: NodeIndex,
+    inputs: &[NodeIndex],
+    grad: NodeIndex,
+) -> Result<Vec<Option<NodeIndex>>, GraphError> {
+    let grads = match op {
+        Operation::Constant
+        | Operation::Variable
+        | Operation::Assign
+        | Operation::AssignAdd
+        | Operation::Placeholder { .. }
+        | Operation::OnesLike
+        | Operation::ZerosLike
+        | Operation::FullLike { .. }
+        | Operation::Uniform { .. }
+        | Operation::ArgMax { .. }
+        | Operation::ArgMin { .. }
+        | Operation::Floor
+        | Operation::Ceil
+        | Operation::Round
+        | Operation::Sign
+        | Operation::Equal
+        | Operation::NotEqual
+        | Operation::Less
+        | Operation::LessEqual
+        | Operation::Greater
+        | Operation::GreaterEqual => Vec::new(),
+        Operation::Add => {
+            let (lhs, rhs) = (inputs[0], inputs[1]);
+
+            vec![
+                Some(sum_to(inner, grad, lhs)),
+                Some(sum_to(inner, grad, rhs)),
+            ]
+        }
+        Operation::Sub => {
+            let (lhs, rhs) = (inputs[0], inputs[1]);
+            let neg_grad = neg(inner, grad);
+
+            vec![
+                Some(sum_to(inner, grad, lhs)),
+                Some(sum_to(inner, neg_grad, rhs)),
+            ]
+        }
+        Operation::Mul => {
+            let (lhs, rhs) = (inputs[0], inputs[1]);
+            let lhs_grad = binary(inner, Operation::Mul, grad, rhs)?;
+            let rhs_grad = binary(inner, Operation::Mul, grad, lhs)?;
+
+            vec![
+                Some(sum_to(inner, lhs_grad, lhs)),
+                Some(sum_to(inner, rhs_grad, rhs)),
+            ]
+        }
+        Operation::Div => {
+            // d(a / b) = da / b - a * db / b^2
+            let (lhs, rhs) = (inputs[0], inputs[1]);
+            let lhs_grad = binary(inner, Operation::Div, grad, rhs)?;
+
+            let rhs_squared = binary(inner, Operation::Mul, rhs, rhs)?;
+            let scaled = binary(inner, Operation::Mul, grad, lhs)?;
+            let quotient = binary(inner, Operation::Div, scaled, rhs_squared)?;
+            let rhs_grad = neg(inner, quotient);
+
+            vec![
+                Some(sum_to(inner, lhs_grad, lhs)),
+                Some(sum_to(inner, rhs_grad, rhs)),
+            ]
+        }
+        Operation::MatMul {
+            transpose_lhs,
+            transpose_rhs,
+        } => {
+            let (lhs, rhs) = (inputs[0], inputs[1]);
+
+            // A vector is a [1, k] row on the left and a [k, 1] column on
+            // the right. Lift vectors, and the gradient, to matrices so the
+            // matrix rules apply, then drop the added axes again.
+            let lhs_is_vector = inner.shape(lhs).dims().len() == 1;
+            let rhs_is_vector = inner.shape(rhs).dims().len() == 1;
+            let rank = inner.shape(grad).dims().len()
+                + lhs_is_vector as usize
+                + rhs_is_vector as usize;
+
+            let mut grad_axes = Vec::new();
+            if lhs_is_vector {
+                grad_axes.push(rank - 2);
+            }
+            if rhs_is_vector {
+                grad_axes.push(rank - 1);
+            }
+            let grad = unsqueeze(inner, grad, &grad_axes);
+            let lhs = match lhs_is_vector {
+                true => unsqueeze(inner, lhs, &[0]),
+                false => lhs,
+            };
+            let rhs = match rhs_is_vector {
+                true => unsqueeze(inner, rhs, &[1]),
+                false => rhs,
+            };
+            let transpose_lhs = *transpose_lhs && !lhs_is_vector;
+            let transpose_rhs = *transpose_rhs && !rhs_is_vector;
+
+            // With C = op(A) @ op(B), dop(A) = G @ op(B)^T and
+            // dop(B) = op(A)^T @ G; transposing back gives each rule below.
+            let (lhs_grad, rhs_grad) = match (transpose_lhs, transpose_rhs) {
+                (false, false) => (
+                    matmul(inner, grad, rhs, false, true)?,
+                    matmul(inner, lhs, grad, true, false)?,
+                ),
+                (false, true) => (
+                    matmul(inner, grad, rhs, false, false)?,
+                    matmul(inner, grad, lhs, true, false)?,
+                ),
+                (true, false) => (
+                    matmul(inner, rhs, grad, false, true)?,
+                    matmul(inner, lhs, grad, false, false)?,
+                ),
+                (true, true) => (
+                    matmul(inner, rhs, grad, true, true)?,
+                    matmul(inner, grad, lhs, true, true)?,
+                ),
+            };
+
+            let mut lhs_grad = sum_to(inner, lhs_grad, lhs);
+            if lhs_is_vector {
+                lhs_grad = squeeze(inner, lhs_grad, &[0]);
+            }
+            let mut rhs_grad = sum_to(inner, rhs_grad, rhs);
+            if rhs_is_vector {
+                rhs_grad = squeeze(inner, rhs_grad, &[1]);
+            }
+
+            vec![Some(lhs_grad), Some(rhs_grad)]
+        }
+        Operation::Neg => vec![Some(neg(inner, grad))],
+        Operation::Cast { .. } => {
+            let dtype = inner.dtype(inputs[0]);
+
+            vec![Some(unary(inner, Operation::Cast { dtype }, grad))]
+        }
+        Operation::Abs => {
+            let sign = unary(inner, Operation::Sign, inputs[0]);
+
+            vec![Some(binary(inner, Operation::Mul, grad, sign)?)]
+        }
+        Operation::Exp => {
+            vec![Some(binary(inner, Operation::Mul, grad, output)?)]
+        }
+        Operation::Log => {
+            vec![Some(binary(inner, Operation::Div, grad, inputs[0])?)]
+        }
+        Operation::Sqrt => {
+            // d(sqrt x) = 1 / (2 sqrt x)
+            let twice = binary(inner, Operation::Add, output, output)?;
+
+            vec![Some(binary(inner, Operation::Div, grad, twice)?)]
+        }
+        Operation::Rsqrt => {
+            // d(x^-1/2) = -x^-1/2 / (2x)
+            let input = inputs[0];
+            let scaled = binary(inner, Operation::Mul, grad, output)?;
+            let twice = binary(inner, Operation::Add, input, input)?;
+            let quotient = binary(inner, Operation::Div, scaled, twice)?;
+
+            vec![Some(neg(inner, quotient))]
+        }
+        Operation::Sin => {
+            let cos = unary(inner, Operation::Cos, inputs[0]);
+
+            vec![Some(binary(inner, Operation::Mul, grad, cos)?)]
+        }
+        Operation::Cos => {
+            let sin = unary(inner, Operation::Sin, inputs[0]);
+            let scaled = binary(inner, Operation::Mul, grad, sin)?;
+
+            vec![Some(neg(inner, scaled))]
+        }
+        Operation::Tanh => {
+            // d(tanh x) = 1 - tanh^2 x
+            let ones = unary(inner, Operation::OnesLike, output);
+            let squared = binary(inner, Operation::Mul, output, output)?;
+            let slope = binary(inner, Operation::Sub, ones, squared)?;
+
+            vec![Some(binary(inner, Operation::Mul, grad, slope)?)]
+        }
+        Operation::Sigmoid => {
+            // d(sigmoid x) = sigmoid x * (1 - sigmoid x)
+            let ones = unary(inner, Operation::OnesLike, output);
+            let complement = binary(inner, Operation::Sub, ones, output)?;
+            let slope = binary(inner, Operation::Mul, output, complement)?;
+
+            vec![Some(binary(inner, Operation::Mul, grad, slope)?)]
+        }
+        Operation::Relu => {
+            // The slope is 1 exactly where the output is positive.
+            let slope = unary(inner, Operation::Sign, output);
+
+            vec![Some(binary(inner, Operation::Mul, grad, slope)?)]
+        }
+        Operation::Gelu => {
+            // d(x * cdf(x)) = cdf(x) + x * pdf(x)
+            let input = inputs[0];
+
+            let scale =
+                full_like(inner, input, std::f64::consts::FRAC_1_SQRT_2);
+            let scaled = binary(inner, Operation::Mul, input, scale)?;
+            let erf = unary(inner, Operation::Erf, scaled);
+            let ones = unary(inner, Operation::OnesLike, input);
+            let halves = full_like(inner, input, 0.5);
+            let shifted = binary(inner, Operation::Add, ones, erf)?;
+            let cdf = binary(inner, Operation::Mul, halves, shifted)?;
+
+            let squared = binary(inner, Operation::Mul, input, input)?;
+            let exponent = binary(inner, Operation::Mul, squared, halves)?;
+            let exponent = neg(inner, exponent);
+            let density = unary(inner, Operation::Exp, exponent);
+            let norm = full_like(
+                inner,
+                input,
+                1.0 / (2.0 * std::f64::consts::PI).sqrt(),
+            );
+            let pdf = binary(inner, Operation::Mul, density, norm)?;
+            let weighted = binary(inner, Operation::Mul, input, pdf)?;
+
+            let slope = binary(inner, Operation::Add, cdf, weighted)?;
+
+            vec![Some(binary(inner, Operation::Mul, grad, slope)?)]
+        }
+        Operation::Erf => {
+            // d(erf x) = 2 / sqrt(pi) * exp(-x^2)
+            let input = inputs[0];
+            let squared = binary(inner, Operation::Mul, input, input)?;
+            let exponent = neg(inner, squared);
+            let density = unary(inner, Operation::Exp, exponent);
+            let norm =
+                full_like(inner, input, std::f64::consts::FRAC_2_SQRT_PI);
+            let slope = binary(inner, Operation::Mul, density, norm)?;
+
+            vec![Some(binary(inner, Operation::Mul, grad, slope)?)]
+        }
+        Operation::Reciprocal => {
+            // d(1 / x) = -1 / x^2
+            let squared = binary(inner, Operation::Mul, output, output)?;
+            let scaled = binary(inner, Operation::Mul, grad, squared)?;
+
+            vec![Some(neg(inner, scaled))]
+        }
+        Operation::Sum { axes, keepdims } => {
+            vec![Some(unreduce(inner, grad, inputs[0], axes, *keepdims))]
+        }
+        Operation::Mean { axes, keepdims } => {
+            let input = inputs[0];
+            let grad = unreduce(inner, grad, input, axes, *keepdims);
+
+            let ones = unary(inner, Operation::OnesLike, input);
+            let count = inner.add_unary_op(
+                ones,
+                Operation::Sum {
+                    axes: axes.clone(),
+                    keepdims: true,
+                },
+                inner.shape(input).reduced(axes, true),
+            );
+
+            vec![Some(binary(inner, Operation::Div, grad, count)?)]
+        }
+        Operation::Prod { axes, keepdims } => {
+            // d(prod x) / dx_i is the product of the other elements: the
+            // product of the non-zero elements over x_i, with zeros taken
+            // as one, unless another element is zero.
+            let input = inputs[0];
+            let reduced = inner.shape(input).reduced(axes, true);
+            let ones = unary(inner, Operation::OnesLike, input);
+            let zeros = unary(inner, Operation::ZerosLike, input);
+            let is_zero = binary(inner, Operation::Equal, input, zeros)?;
+            let zero_mask = select(inner, is_zero, ones, zeros)?;
+            let zero_count = inner.add_unary_op(
+                zero_mask,
+                Operation::Sum {
+                    axes: axes.clone(),
+                    keepdims: true,
+                },
+                reduced.clone(),
+            );
+            let nonzero = select(inner, is_zero, ones, input)?;
+            let nonzero_prod = inner.add_unary_op(
+                nonzero,
+                Operation::Prod {
+                    axes: axes.clone(),
+                    keepdims: true,
+                },
+                reduced,
+            );
+
+            let others = binary(inner, Operation::Div, nonzero_prod, nonzero)?;
+            let other_zeros =
+                binary(inner, Operation::Sub, zero_count, zero_mask)?;
+            let no_other_zero =
+                binary(inner, Operation::Equal, other_zeros, zeros)?;
+            let others = select(inner, no_other_zero, others, zeros)?;
+
+            let grad = unreduce(inner, grad, input, axes, *keepdims);
+
+            vec![Some(binary(inner, Operation::Mul, grad, others)?)]
+        }
+        Operation::Max { axes, keepdims }
+        | Operation::Min { axes, keepdims } => {
+            // Tied extrema share the gradient evenly.
+            let input = inputs[0];
+            let extremum = unreduce(inner, output, input, axes, *keepdims);
+            let is_extremum = binary(inner, Operation::Equal, input, extremum)?;
+            let ones = unary(inner, Operation::OnesLike, input);
+            let zeros = unary(inner, Operation::ZerosLike, input);
+            let mask = select(inner, is_extremum, ones, zeros)?;
+            let count = inner.add_unary_op(
+                mask,
+                Operation::Sum {
+                    axes: axes.clone(),
+                    keepdims: true,
+                },
+                inner.shape(input).reduced(axes, true),
+            );
+
+            let grad = unreduce(inner, grad, input, axes, *keepdims);
+            let masked = binary(inner, Operation::Mul, grad, mask)?;
+
+            vec![Some(binary(inner, Operation::Div, masked, count)?)]
+        }
+        Operation::Softmax { axis } => {
+            // dx = y * (g - sum(g * y))
+            let weighted = binary(inner, Operation::Mul, grad, output)?;
+            let total = sum_keepdims(inner, weighted, *axis);
+            let centered = binary(inner, Operation::Sub, grad, total)?;
+
+            vec![Some(binary(inner, Operation::Mul, output, centered)?)]
+        }
+        Operation::LogSoftmax { axis } => {
+            // dx = g - softmax(x) * sum(g)
+            let total = sum_keepdims(inner, grad, *axis);
+            let probs = unary(inner, Operation::Exp, output);
+            let spread = binary(inner, Operation::Mul, probs, total)?;
+
+            vec![Some(binary(inner, Operation::Sub, grad, spread)?)]
+        }
+        Operation::Reshape { .. } => {
+            let shape = inner.shape(inputs[0]).clone();
+
+            vec![Some(inner.add_unary_op(
+                grad,
+                Operation::Reshape {
+                    shape: shape.clone(),
+                },
+                shape,
+            ))]
+        }
+        Operation::Permute { axes } => {
+            let mut inverse = vec![0; axes.len()];
+            for (i, &axis) in axes.iter().enumerate() {
+                inverse[axis] = i;
+            }
+            let shape = inner.shape(inputs[0]).clone();
+
+            vec![Some(inner.add_unary_op(
+                grad,
+                Operation::Permute { axes: inverse },
+                shape,
+            ))]
+        }
+        Operation::Squeeze { axes } => vec![Some(unsqueeze(inner, grad, axes))],
+        Operation::Unsqueeze { axes } => vec![Some(squeeze(inner, grad, axes))],
+        Operation::Expand { .. } => vec![Some(sum_to(inner, grad, inputs[0]))],
+        Operation::Slice { slices } => {
+            let input = inputs[0];
+            let zeros = unary(inner, Operation::ZerosLike, input);
+            let shape = inner.shape(input).clone();
+
+            vec![Some(inner.add_variadic_op(
+                &[zeros, grad],
+                Operation::SliceScatter {
+                    slices: slices.clone(),
+                },
+                shape,
+            ))]
+        }
+        Operation::SliceScatter { slices } => {
+            let (input, src) = (inputs[0], inputs[1]);
+            let zeros = unary(inner, Operation::ZerosLike, src);
+            let input_grad = inner.add_variadic_op(
+                &[grad, zeros],
+                Operation::SliceScatter {
+                    slices: slices.clone(),
+                },
+                inner.shape(input).clone(),
+            );
+            let src_grad = inner.add_unary_op(
+                grad,
+                Operation::Slice {
+                    slices: slices.clone(),
+                },
+                inner.shape(src).clone(),
+            );
+
+            vec![Some(input_grad), Some(src_grad)]
+        }
+        Operation::IndexSelect { axis } => {
+            // Spread the 1-D indices over the gradient's shape so that the
+            // selection can be undone by a scatter.
+            let (input, indices) = (inputs[0], inputs[1]);
+            let rank = inner.shape(input).dims().len();
+            let other_axes: Vec<usize> =
+                (0..rank).filter(|other| other != axis).collect();
+            let indices = unsqueeze(inner, indices, &other_axes);
+            let indices = broadcast_like(inner, indices, grad);
+
+            vec![Some(scatter_add(inner, input, indices, grad, *axis)), None]
+        }
+        Operation::Gather { axis } => {
+            let (input, indices) = (inputs[0], inputs[1]);
+
+            vec![Some(scatter_add(inner, input, indices, grad, *axis)), None]
+        }
+        Operation::Scatter { axis, accumulate } => {
+            let (indices, src) = (inputs[1], inputs[2]);
+
+            // Overwritten entries do not reach the output.
+            let input_grad = match accumulate {
+                true => grad,
+                false => {
+                    let zeros = unary(inner, Operation::ZerosLike, src);
+                    let shape = inner.shape(grad).clone();
+                    inner.add_variadic_op(
+                        &[grad, indices, zeros],
+                        Operation::Scatter {
+                            axis: *axis,
+                            accumulate: false,
+                        },
+                        shape,
+                    )
+                }
+            };
+            let src_grad = inner.add_variadic_op(
+                &[grad, indices],
+                Operation::Gather { axis: *axis },
+                inner.shape(indices).clone(),
+            );
+
+            vec![Some(input_grad), None, Some(src_grad)]
+        }
+        Operation::Where => {
+            let (condition, on_true, on_false) =
+                (inputs[0], inputs[1], inputs[2]);
+            let zeros = unary(inner, Operation::ZerosLike, grad);
+            let true_grad = select(inner, condition, grad, zeros)?;
+            let false_grad = select(inner, condition, zeros, grad)?;
+
+            vec![
+                None,
+                Some(sum_to(inner, true_grad, on_true)),
+                Some(sum_to(inner, false_grad, on_false)),
+            ]
+        }
+        Operation::Concat { axis } => {
+            let sizes = inputs
+                .iter()
+                .map(|&input| {
+                    // Concat only accepts fixed sizes along its axis.
+                    inner.shape(input).dims()[*axis]
+                        .fixed()
+                        .expect("Concat axes have a fixed size")
+                })
+                .collect();
+
+            split(inner, grad, *axis, sizes)
+                .into_iter()
+                .map(Some)
+                .collect()
+        }
+        // The gradients of the pieces already sit in place in the input's
+        // shape, see `Output` below.
+        Operation::Split { .. } => vec![Some(grad)],
+        Operation::Output { index } => {
+            let Operation::Split { axis, sizes } = &inner.graph()[inputs[0]]
+            else {
+                unreachable!("Output nodes read split nodes");
+            };
+            let start: usize = sizes[..*index].iter().sum();
+            let end = start + sizes[*index];
+            let slices: Vec<Slice> = (0..=*axis)
+                .map(|other| match other == *axis {
+                    true => Slice::from(start as isize..end as isize),
+                    false => Slice::full(),
+                })
+                .collect();
+
+            let source = inner.operands(inputs[0])[0];
+            let zeros = unary(inner, Operation::ZerosLike, source);
+            let shape = inner.shape(source).clone();
+
+            vec![Some(inner.add_variadic_op(
+                &[zeros, grad],
+                Operation::SliceScatter { slices },
+                shape,
+            ))]
+        }
+        Operation::SumTo { .. } => {
+            vec![Some(broadcast_like(inner, grad, inputs[0]))]
+        }
+        Operation::Fused { .. } => {
+            unreachable!("Fused nodes only exist in compiled executables")
+        }
+    };
+
+    Ok(grads)
+}
+
+/// Splits `input` along `axis` into pieces of `sizes`.
+fn split(
+    inner: &mut GraphInner,
+    input: NodeIndex,
+    axis: usize,
+    sizes: Vec<usize>,
+) -> Vec<NodeIndex> {
+    let shape = inner.shape(input).clone();
+    let node_id = inner.add_unary_op(
+        input,
+        Operation::Split {
+            axis,
+            sizes: sizes.clone(),
+        },
+        shape.clone(),
+    );
+
+    sizes
+        .into_iter()
+        .enumerate()
+        .map(|(index, size)| {
+            let mut dims = shape.dims().to_vec();
+            dims[axis] = size.into();
+            inner.add_unary_op(
+                node_id,
+                Operation::Output { index },
+                dims.into(),
+            )
+        })
+        .collect()
+}
+
+fn binary(
+    inner: &mut GraphInner,
+    op: Operation,
+    lhs: NodeIndex,
+    rhs: NodeIndex,
+) -> Result<NodeIndex, GraphError> {
+    let shape = inner
+        .shape(lhs)
+        .broadcast_with(inner.shape(rhs))
+        .map_err(|_| incompatible(inner, lhs, rhs))?;
+
+    Ok(inner.add_binary_op(lhs, rhs, op, shape))
+}
+
+fn matmul(
+    inner: &mut GraphInner,
+    lhs: NodeIndex,
+    rhs: NodeIndex,
+    transpose_lhs: bool,
+    transpose_rhs: bool,
+) -> Result<NodeIndex, GraphError> {
+    let shape = inner
+        .shape(lhs)
+        .matmul_with_transposed(inner.shape(rhs), transpose_lhs, transpose_rhs)
+        .map_err(|_| incompatible(inner, lhs, rhs))?;
+
+    Ok(inner.add_binary_op(
+        lhs,
+        rhs,
+        Operation::MatMul {
+            transpose_lhs,
+            transpose_rhs,
+        },
+        shape,
+    ))
+}
+
+/// `on_true` where `condition` holds and `on_false` elsewhere.
+fn select(
+    inner: &mut GraphInner,
+    condition: NodeIndex,
+    on_true: NodeIndex,
+    on_false: NodeIndex,
+) -> Result<NodeIndex, GraphError> {
+    let mut shape = inner.shape(condition).clone();
+    for node_id in [on_true, on_false] {
+        shape = shape
+            .broadcast_with(inner.shape(node_id))
+            .map_err(|_| incompatible(inner, condition, node_id))?;
+    }
+
+    Ok(inner.add_variadic_op(
+        &[condition, on_true, on_false],
+        Operation::Where,
+        shape,
+    ))
+}
+
+/// The error for gradient operands `lhs` and `rhs` whose shapes do not fit
+/// together.
+fn incompatible(
+    inner: &GraphInner,
+    lhs: NodeIndex,
+    rhs: NodeIndex,
+) -> GraphError {
+    GraphError::IncompatibleShapes {
+        op: "gradients",
+        node: rhs,
+        lhs: inner.shape(lhs).clone(),
+        rhs: inner.shape(rhs).clone(),
+    }
+}
+
+/// Zeros shaped like `input` with `grad` added along `axis` at `indices`.
+fn scatter_add(
+    inner: &mut GraphInner,
+    input: NodeIndex,
+    indices: NodeIndex,
+    grad: NodeIndex,
+    axis: usize,
+) -> NodeIndex {
+    let zeros = unary(inner, Operation::ZerosLike, input);
+    let shape = inner.shape(input).clone();
+
+    inner.add_variadic_op(
+        &[zeros, indices, grad],
+        Operation::Scatter {
+            axis,
+            accumulate: true,
+        },
+        shape,
+    )
+}
+
+/// Broadcasts `node` up to the shape of `like`.
+fn broadcast_like(
+    inner: &mut GraphInner,
+    node_id: NodeIndex,
+    like: NodeIndex,
+) -> NodeIndex {
+    let shape = inner.shape(like).clone();
+    if *inner.shape(node_id) == shape {
+        return node_id;
+    }
+
+    inner.add_unary_op(
+        node_id,
+        Operation::Expand {
+            shape: shape.clone(),
+        },
+        shape,
+    )
+}
+
+/// Undoes a reduction of `input` over `axes` by broadcasting the reduced
+/// `grad` back to the input shape.
+fn unreduce(
+    inner: &mut GraphInner,
+    grad: NodeIndex,
+    input: NodeIndex,
+    axes: &[usize],
+    keepdims: bool,
+) -> NodeIndex {
+    let grad = match keepdims {
+        true => grad,
+        false => unsqueeze(inner, grad, axes),
+    };
+
+    broadcast_like(inner, grad, input)
+}
+
+/// Sums `node_id` over `axis`, keeping it with size 1.
+fn sum_keepdims(
+    inner: &mut GraphInner,
+    node_id: NodeIndex,
+    axis: usize,
+) -> NodeIndex {
+    let shape = inner.shape(node_id).reduced(&[axis], true);
+
+    inner.add_unary_op(
+        node_id,
+        Operation::Sum {
+            axes: vec![axis],
+            keepdims: true,
+        },
+        shape,
+    )
+}
+
+/// Removes the size-1 dimensions at sorted `axes`.
+fn squeeze(
+    inner: &mut GraphInner,
+    node_id: NodeIndex,
+    axes: &[usize],
+) -> NodeIndex {
+    let shape = inner.shape(node_id).reduced(axes, false);
+
+    inner.add_unary_op(
+        node_id,
+        Operation::Squeeze {
+            axes: axes.to_vec(),
+        },
+        shape,
+    )
+}
+
+/// Inserts size-1 dimensions at sorted `axes` of the result.
+fn unsqueeze(
+    inner: &mut GraphInner,
+    node_id: NodeIndex,
+    axes: &[usize],
+) -> NodeIndex {
+    if axes.is_empty() {
+        return node_id;
+    }
+
+    let dims = inner.shape(node_id).dims();
+    let mut existing = dims.iter();
+    let shape: Vec<Dim> = (0..dims.len() + axes.len())
+        .map(|axis| match axes.contains(&axis) {
+            true => Dim::Fixed(1),
+            false => *existing.next().unwrap(),
+        })
+        .collect();
+
+    inner.add_unary_op(
+        node_id,
+        Operation::Unsqueeze {
+            axes: axes.to_vec(),
+        },
+        shape.into(),
+    )
+}
+
+fn neg(inner: &mut GraphInner, node_id: NodeIndex) -> NodeIndex {
+    unary(inner, Operation::Neg, node_id)
+}
+
+fn unary(inner: &mut GraphInner, op: Operation, input: NodeIndex) -> NodeIndex {
+    let shape = inner.shape(input).clone();
+
+    inner.add_unary_op(input, op, shape)
+}
+
+/// A tensor shaped and typed like `like`, filled with `value`.
+fn full_like(inner: &mut GraphInner, like: NodeIndex, value: f64) -> NodeIndex {
+    unary(inner, Operation::FullLike { value }, like)
+}
+
+/// Reduces `grad` over the dimensions that were broadcast to produce it
+/// from `input`.
+fn sum_to(
+    inner: &mut GraphInner,
+    grad: NodeIndex,
+    input: NodeIndex,
+) -> NodeIndex {
+    let shape: Shape = inner.shape(input).clone();
+    if *inner.shape(grad) == shape {
+        return grad;
+    }
+
+    inner.add_unary_op(
+        grad,
+        Operation::SumTo {
+            shape: shape.clone(),
+        },
+        shape,
+    )
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    hash::{Hash, Hasher},
+    mem::discriminant,
+};
+
+use petgraph::{Direction, graph::NodeIndex, visit::EdgeRef};
+
+use crate::op::Operation;
+
+use super::inner::{OpGraph, operands};
+
+/// An operation together with its operands, in slot order. Fill values
+/// compare by their bits, so `0.0` and `-0.0` fills stay apart.
+#[derive(Debug)]
+struct NodeKey {
+    op: Operation,
+    operands: Vec<NodeIndex>,
+}
+
+impl PartialEq for NodeKey {
+    fn eq(&self, other: &Self) -> bool {
+        let same_op = match (&self.op, &other.op) {
+            (
+                Operation::FullLike { value: lhs },
+                Operation::FullLike { value: rhs },
+            ) => lhs.to_bits() == rhs.to_bits(),
+            (lhs, rhs) => lhs == rhs,
+        };
+
+        same_op && self.operands == other.operands
+    }
+}
+
+impl Eq for NodeKey {}
+
+impl Hash for NodeKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(&self.op).hash(state);
+        match &self.op {
+            Operation::MatMul {
+                transpose_lhs,
+                transpose_rhs,
+            } => {
+                transpose_lhs.hash(state);
+                transpose_rhs.hash(state);
+            }
+            Operation::Sum { axes, keepdims }
+            | Operation::Mean { axes, keepdims }
+            | Operation::Max { axes, keepdims }
+            | Operation::Min { axes, keepdims }
+            | Operation::Prod { axes, keepdims }
+            | Operation::ArgMax { axes, keepdims }
+            | Operation::ArgMin { axes, keepdims } => {
+                axes.hash(state);
+                keepdims.hash(state);
+            }
+            Operation::Softmax { axis }
+            | Operation::LogSoftmax { axis }
+            | Operation::IndexSelect { axis }
+            | Operation::Gather { axis }
+            | Operation::Concat { axis } => axis.hash(state),
+            Operation::Reshape { shape }
+            | Operation::Expand { shape }
+            | Operation::SumTo { shape } => shape.hash(state),
+            Operation::Permute { axes }
+            | Operation::Squeeze { axes }
+            | Operation::Unsqueeze { axes } => axes.hash(state),
+            Operation::Slice { slices }
+            | Operation::SliceScatter { slices } => slices.hash(state),
+            Operation::Scatter { axis, accumulate } => {
+                axis.hash(state);
+                accumulate.hash(state);
+            }
+            Operation::Split { axis, sizes } => {
+                axis.hash(state);
+                sizes.hash(state);
+            }
+            Operation::Output { index } => index.hash(state),
+            Operation::FullLike { value } => value.to_bits().hash(state),
+            Operation::Uniform { seed } => seed.hash(state),
+            Operation::Cast { dtype } => dtype.hash(state),
+            _ => {}
+        }
+        self.operands.hash(state);
+    }
+}
+
+/// Merges every node of `order`, which must be topologically sorted, into
+/// an earlier node applying the same operation to the same operands: the
+/// duplicate's consumers read the earlier node instead and the duplicate
+/// is removed. Nodes in `keep` are not removed, nor are nodes that hold
+/// data or write to variables. Returns the removed nodes.
+pub(crate) fn eliminate_common_subexpressions(
+    graph: &mut OpGraph,
+    order: &[NodeIndex],
+    keep: &[NodeIndex],
+) -> HashSet<NodeIndex> {
+    let mut seen: HashMap<NodeKey, NodeIndex> = HashMap::new();
+    let mut removed = HashSet::new();
+
+    for &node_idx in order {
+        let op = &graph[node_idx];
+        if matches!(
+            op,
+            Operation::Constant
+                | Operation::Variable
+                | Operation::Placeholder { .. }
+                | Operation::Assign
+                | Operation::AssignAdd
+        ) {
+            continue;
+        }
+
+        // Operands were merged before their consumers, so equal
+        // subexpressions already share their operand nodes.
+        let key = NodeKey {
+            op: op.clone(),
+            operands: operands(graph, node_idx),
+        };
+        let original = match seen.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(node_idx);
+                continue;
+            }
+            Entry::Occupied(_) if keep.contains(&node_idx) => continue,
+            Entry::Occupied(entry) => *entry.get(),
+        };
+
+        let consumers: Vec<(NodeIndex, usize)> = graph
+            .edges_directed(node_idx, Direction::Outgoing)
+            .map(|edge| (edge.target(), *edge.weight()))
+            .collect();
+        for (consumer, slot) in consumers {
+            graph.add_edge(original, consumer, slot);
+        }
+        graph.remove_node(node_idx);
+        removed.insert(node_idx);
+    }
+
+    removed
+}
@@ -0,0 +1,119 @@
+use petgraph::graph::NodeIndex;
+
+use crate::tensor::{
+    shape::{Shape, ShapeError},
+    storage::DType,
+};
+
+/// Why an operation cannot be added to a graph.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    /// Operands of `op` whose shapes do not fit together; `node` is the
+    /// right-hand operand.
+    IncompatibleShapes {
+        op: &'static str,
+        node: NodeIndex,
+        lhs: Shape,
+        rhs: Shape,
+    },
+    /// `op` cannot be applied to the tensor at `node`.
+    InvalidShape {
+        op: &'static str,
+        node: NodeIndex,
+        source: ShapeError,
+    },
+    /// Operands of `op` with different dtypes in a graph that does not
+    /// promote them; `node` is the operand of dtype `rhs`.
+    MixedDtypes {
+        op: &'static str,
+        node: NodeIndex,
+        lhs: DType,
+        rhs: DType,
+    },
+    /// `op` cannot take the tensor at `node`, which is of `dtype`.
+    UnsupportedDtype {
+        op: &'static str,
+        node: NodeIndex,
+        dtype: DType,
+    },
+    /// `op` writes to the tensor at `node`, which is not a variable.
+    NotAVariable { op: &'static str, node: NodeIndex },
+    /// `op` needs at least one operand and got none.
+    NoOperands { op: &'static str },
+    /// `op` got a number of gradients other than one per parameter.
+    GradientCountMismatch {
+        op: &'static str,
+        params: usize,
+        grads: usize,
+    },
+    /// The tensor at `node` outlived the graph it was built in.
+    GraphDropped { node: NodeIndex },
+    /// The graph has a cycle through `node`.
+    CyclicGraph { node: NodeIndex },
+    /// `op` got a learning rate schedule whose decay factor is negative.
+    InvalidDecay { op: &'static str, gamma: f64 },
+    /// `name` is already taken by the tensor at `node`.
+    DuplicateName { name: String, node: NodeIndex },
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::IncompatibleShapes { op, node, lhs, rhs } => write!(
+                f,
+                "Incompatible shapes {:?} and {:?} for {op} at node {}",
+                lhs.dims(),
+                rhs.dims(),
+                node.index()
+            ),
+            GraphError::InvalidShape { op, node, source } => {
+                write!(f, "Invalid {op} at node {}: {source}", node.index())
+            }
+            GraphError::MixedDtypes { op, node, lhs, rhs } => write!(
+                f,
+                "Mixed dtypes {lhs} and {rhs} for {op} at node {}",
+                node.index()
+            ),
+            GraphError::UnsupportedDtype { op, node, dtype } => write!(
+                f,
+                "Unsupported dtype {dtype} for {op} at node {}",
+                node.index()
+            ),
+            GraphError::NotAVariable { op, node } => {
+                write!(f, "Node {} is not a variable for {op}", node.index())
+            }
+            GraphError::NoOperands { op } => {
+                write!(f, "Expected at least one operand for {op}")
+            }
+            GraphError::GradientCountMismatch { op, params, grads } => write!(
+                f,
+                "Expected one gradient per parameter for {op}, got {grads} \
+                 for {params}"
+            ),
+            GraphError::GraphDropped { node } => {
+                write!(f, "The graph of node {} was dropped", node.index())
+            }
+            GraphError::CyclicGraph { node } => {
+                write!(f, "The graph has a cycle through node {}", node.index())
+            }
+            GraphError::InvalidDecay { op, gamma } => write!(
+                f,
+                "Decay factor must not be negative for {op}, got {gamma}"
+            ),
+            GraphError::DuplicateName { name, node } => write!(
+                f,
+                "The name {name:?} is already used by node {}",
+                node.index()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GraphError::InvalidShape { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
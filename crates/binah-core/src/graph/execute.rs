@@ -1,28 +1,138 @@
 use crate::{
-    cpu::{cpu_add, cpu_sub, cpu_mul, cpu_div},
-    op::Operation, 
-    tensor::{shape::Shape, storage::TensorStorage}
+    cpu::{
+        Arena, KernelError, broadcast_layout, cpu_abs, cpu_abs_in_place,
+        cpu_add, cpu_add_in_place, cpu_argmax, cpu_argmin, cpu_cast, cpu_ceil,
+        cpu_ceil_in_place, cpu_concat, cpu_contiguous, cpu_cos,
+        cpu_cos_in_place, cpu_div, cpu_div_in_place, cpu_equal, cpu_erf,
+        cpu_erf_in_place, cpu_exp, cpu_exp_in_place, cpu_floor,
+        cpu_floor_in_place, cpu_full_like, cpu_full_like_in_place, cpu_fused,
+        cpu_gather, cpu_gelu, cpu_gelu_in_place, cpu_greater,
+        cpu_greater_equal, cpu_index_select, cpu_less, cpu_less_equal, cpu_log,
+        cpu_log_in_place, cpu_log_softmax, cpu_matmul, cpu_max, cpu_mean,
+        cpu_min, cpu_mul, cpu_mul_in_place, cpu_neg, cpu_neg_in_place,
+        cpu_not_equal, cpu_ones_like, cpu_prod, cpu_reciprocal,
+        cpu_reciprocal_in_place, cpu_relu, cpu_relu_in_place, cpu_round,
+        cpu_round_in_place, cpu_rsqrt, cpu_rsqrt_in_place, cpu_scatter,
+        cpu_sigmoid, cpu_sigmoid_in_place, cpu_sign, cpu_sign_in_place,
+        cpu_sin, cpu_sin_in_place, cpu_slice_scatter, cpu_softmax, cpu_sqrt,
+        cpu_sqrt_in_place, cpu_sub, cpu_sub_in_place, cpu_sum, cpu_sum_to,
+        cpu_tanh, cpu_tanh_in_place, cpu_uniform, cpu_where, cpu_zeros_like,
+    },
+    op::{FusedStep, Operation},
+    tensor::{
+        layout::Layout,
+        shape::{BroadcastError, Dim, Shape},
+        storage::{DType, TensorStorage},
+        view::TensorView,
+    },
 };
-use petgraph::{
-    Direction, algo::toposort, graph::NodeIndex, prelude::StableGraph,
-    visit::EdgeRef,
+use petgraph::{Direction, algo::toposort, graph::NodeIndex, visit::EdgeRef};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
 };
-use std::collections::{HashMap, HashSet};
 
-use super::tensor::GraphTensor;
+use super::{
+    cse::eliminate_common_subexpressions,
+    fuse::fuse_elementwise,
+    inner::{OpGraph, operands},
+    io::{Outputs, TensorKey},
+    memory::MemoryPlan,
+    tensor::GraphTensor,
+};
+
+/// Optimizations that [`GraphExecutable::new`] applies to the graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// Merges every operation into an earlier one that applies it to the
+    /// same operands. Turning this off keeps one node per operation built,
+    /// which can make a graph easier to debug.
+    pub eliminate_common_subexpressions: bool,
+    /// Evaluates chains of elementwise operations in a single pass, without
+    /// a tensor for each intermediate result.
+    pub fuse_elementwise: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            eliminate_common_subexpressions: true,
+            fuse_elementwise: true,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct GraphExecutable {
-    graph: StableGraph<Operation, ()>,
+    graph: OpGraph,
     execution_plan: Vec<NodeIndex>,
-    tensor_storage: HashMap<NodeIndex, TensorStorage>,
+    tensor_storage: HashMap<NodeIndex, TensorView>,
+    /// Results of multi-output nodes, read through their `Output` nodes.
+    tuple_storage: HashMap<NodeIndex, Vec<TensorView>>,
     inputs: Vec<NodeIndex>,
     outputs: Vec<NodeIndex>,
+    /// Current values of every variable of the graph, which assignments
+    /// replace at the end of a successful execution.
+    variables: HashMap<NodeIndex, TensorView>,
+    /// Names of the inputs, outputs and variables.
+    names: HashMap<String, NodeIndex>,
+    /// Sizes of the symbolic dimensions, bound from the current inputs.
+    bindings: HashMap<&'static str, usize>,
+    /// Number of operations merged into an identical one at compile time.
+    merged: usize,
+    /// Number of operations evaluated once at compile time.
+    folded: usize,
+    /// Number of operations evaluated inside another's fused kernel.
+    fused: usize,
+    /// When each tensor is released during an execution, and which
+    /// released buffers are reused.
+    memory: MemoryPlan,
 }
 
 #[derive(Debug)]
 pub enum ExecutionError {
     MissingInput(NodeIndex),
+    /// A fed tensor whose key names no input of the executable.
+    UnexpectedInput(TensorKey),
+    /// A key that names no variable of the executable.
+    UnknownVariable(TensorKey),
+    /// The input fed to `node` is not of the shape it was declared with.
+    ShapeMismatch {
+        node: NodeIndex,
+        expected: Shape,
+        actual: Vec<usize>,
+    },
+    /// The input fed to `node` is not of the dtype it was declared with.
+    DtypeMismatch {
+        node: NodeIndex,
+        expected: DType,
+        actual: DType,
+    },
+    /// The input fed to `node` gives `symbol` the size `actual`, but an
+    /// earlier input bound it to `bound`.
+    SymbolMismatch {
+        node: NodeIndex,
+        symbol: &'static str,
+        bound: usize,
+        actual: usize,
+    },
+    /// `node` needs the size of `symbol`, which no input binds.
+    UnboundSymbol {
+        node: NodeIndex,
+        symbol: &'static str,
+    },
+    /// The input fed to `node` holds `len` elements, which its own shape
+    /// does not account for.
+    MalformedInput {
+        node: NodeIndex,
+        len: usize,
+        shape: Vec<usize>,
+    },
+    /// The kernel computing `node` rejected its operands.
+    Kernel {
+        node: NodeIndex,
+        source: KernelError,
+    },
     InvalidOperation,
     CyclicGraph,
 }
@@ -31,19 +141,91 @@ impl std::fmt::Display for ExecutionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ExecutionError::MissingInput(node) => write!(f, "Missing input for node {:?}", node),
+            ExecutionError::UnexpectedInput(TensorKey::Name(name)) => {
+                write!(f, "No input is named {name:?}")
+            }
+            ExecutionError::UnexpectedInput(TensorKey::Node(node)) => {
+                write!(f, "Node {} is not an input", node.index())
+            }
+            ExecutionError::UnknownVariable(TensorKey::Name(name)) => {
+                write!(f, "No variable is named {name:?}")
+            }
+            ExecutionError::UnknownVariable(TensorKey::Node(node)) => {
+                write!(f, "Node {} is not a variable", node.index())
+            }
+            ExecutionError::ShapeMismatch {
+                node,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected an input of shape {:?} for node {}, got {actual:?}",
+                expected.dims(),
+                node.index()
+            ),
+            ExecutionError::DtypeMismatch {
+                node,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected an input of dtype {expected} for node {}, got \
+                 {actual}",
+                node.index()
+            ),
+            ExecutionError::SymbolMismatch {
+                node,
+                symbol,
+                bound,
+                actual,
+            } => write!(
+                f,
+                "The input fed to node {} sets {symbol} to {actual}, but it \
+                 is already {bound}",
+                node.index()
+            ),
+            ExecutionError::UnboundSymbol { node, symbol } => write!(
+                f,
+                "Node {} needs the size of {symbol}, which no input sets",
+                node.index()
+            ),
+            ExecutionError::MalformedInput { node, len, shape } => write!(
+                f,
+                "The input fed to node {} holds {len} elements but has shape \
+                 {shape:?}",
+                node.index()
+            ),
+            ExecutionError::Kernel { node, source } => {
+                write!(f, "Cannot compute node {}: {source}", node.index())
+            }
             ExecutionError::InvalidOperation => write!(f, "Invalid operation"),
             ExecutionError::CyclicGraph => write!(f, "Graph contains cycles"),
         }
     }
 }
 
-impl std::error::Error for ExecutionError {}
+impl std::error::Error for ExecutionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExecutionError::Kernel { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
 impl GraphExecutable {
+    /// Compiles the part of `graph` that `target_tensors` depend on, or the
+    /// whole graph when there are no targets. The outputs are the targets
+    /// in the order given, or else every node nothing else consumes.
+    /// `value_type` gives the shape and dtype of a node's value, for
+    /// planning the memory of an execution.
     pub fn new(
-        graph: &StableGraph<Operation, ()>,
+        graph: &OpGraph,
         tensor_storage: HashMap<NodeIndex, TensorStorage>,
+        names: &HashMap<String, NodeIndex>,
         target_tensors: &[&GraphTensor],
+        options: CompileOptions,
+        value_type: impl Fn(NodeIndex) -> Option<(Shape, DType)>,
     ) -> Result<Self, ExecutionError> {
         let required_nodes = if target_tensors.is_empty() {
             graph.node_indices().collect()
@@ -53,48 +235,133 @@ impl GraphExecutable {
 
         let full_execution_plan =
             toposort(graph, None).map_err(|_| ExecutionError::CyclicGraph)?;
-        let execution_plan: Vec<NodeIndex> = full_execution_plan
+        let mut execution_plan: Vec<NodeIndex> = full_execution_plan
             .into_iter()
             .filter(|node| required_nodes.contains(node))
             .collect();
 
-        let mut inputs = Vec::new();
-        let mut outputs = Vec::new();
+        let inputs: Vec<NodeIndex> = execution_plan
+            .iter()
+            .copied()
+            .filter(|&node_idx| {
+                matches!(
+                    graph.node_weight(node_idx),
+                    Some(Operation::Placeholder { .. })
+                )
+            })
+            .collect();
 
-        for &node_idx in &required_nodes {
-            if let Some(op) = graph.node_weight(node_idx) {
-                match op {
-                    Operation::Placeholder => inputs.push(node_idx),
-                    _ => {}
-                }
-            }
+        // Targets are returned even when other targets consume them, e.g. a
+        // loss compiled together with its gradients.
+        let outputs: Vec<NodeIndex> = if target_tensors.is_empty() {
+            execution_plan
+                .iter()
+                .copied()
+                .filter(|&node_idx| {
+                    graph
+                        .edges_directed(node_idx, Direction::Outgoing)
+                        .next()
+                        .is_none()
+                })
+                .collect()
+        } else {
+            target_tensors
+                .iter()
+                .map(|tensor| tensor.node_id())
+                .collect()
+        };
 
-            let has_outgoing = graph
-                .edges_directed(node_idx, Direction::Outgoing)
-                .any(|edge| required_nodes.contains(&edge.target()));
-            if !has_outgoing {
-                outputs.push(node_idx);
-            }
-        }
+        // Merging only rewires edges forward, so the plan stays sorted.
+        let mut graph = graph.clone();
+        let merged = match options.eliminate_common_subexpressions {
+            true => eliminate_common_subexpressions(
+                &mut graph,
+                &execution_plan,
+                &outputs,
+            ),
+            false => HashSet::new(),
+        };
+        execution_plan.retain(|node_idx| !merged.contains(node_idx));
+
+        // Variables outside the compiled part can still be read and set.
+        let variables: HashMap<NodeIndex, TensorView> = tensor_storage
+            .iter()
+            .filter(|&(node, _)| {
+                matches!(graph.node_weight(*node), Some(Operation::Variable))
+            })
+            .map(|(&node, storage)| (node, TensorView::from(storage.clone())))
+            .collect();
+
+        let names = names
+            .iter()
+            .filter(|(_, node_idx)| {
+                inputs.contains(node_idx)
+                    || outputs.contains(node_idx)
+                    || variables.contains_key(node_idx)
+            })
+            .map(|(name, &node_idx)| (name.clone(), node_idx))
+            .collect();
 
         // Filter tensor storage to only include required nodes
-        let pruned_tensor_storage: HashMap<NodeIndex, TensorStorage> =
+        let pruned_tensor_storage: HashMap<NodeIndex, TensorView> =
             tensor_storage
                 .into_iter()
                 .filter(|(node, _)| required_nodes.contains(node))
+                .map(|(node, storage)| (node, TensorView::from(storage)))
                 .collect();
 
-        Ok(Self {
-            graph: graph.clone(),
+        let mut executable = Self {
+            graph,
             execution_plan,
             tensor_storage: pruned_tensor_storage,
+            tuple_storage: HashMap::new(),
             inputs,
             outputs,
-        })
+            variables,
+            names,
+            bindings: HashMap::new(),
+            merged: merged.len(),
+            folded: 0,
+            fused: 0,
+            memory: MemoryPlan::default(),
+        };
+        executable.fold_constants();
+        if options.fuse_elementwise {
+            let fused = fuse_elementwise(
+                &mut executable.graph,
+                &executable.execution_plan,
+                &executable.outputs,
+            );
+            executable
+                .execution_plan
+                .retain(|node_idx| !fused.contains(node_idx));
+            executable.fused = fused.len();
+        }
+        executable.memory = MemoryPlan::new(
+            &executable.graph,
+            &executable.execution_plan,
+            &executable.outputs,
+            value_type,
+        );
+
+        Ok(executable)
+    }
+
+    /// Number of operations that repeated another on the same operands,
+    /// which [`GraphExecutable::new`] merged into it.
+    pub fn merged_nodes(&self) -> usize {
+        self.merged
+    }
+
+    /// Number of operations that depend on constants alone, which were
+    /// computed once by [`GraphExecutable::new`] instead of on every
+    /// execution.
+    pub fn folded_nodes(&self) -> usize {
+        self.folded
     }
 
     fn find_required_nodes(
-        graph: &StableGraph<Operation, ()>,
+        graph: &OpGraph,
         target_tensors: &[&GraphTensor],
     ) -> HashSet<NodeIndex> {
         let mut required = HashSet::new();
@@ -123,65 +390,598 @@ impl GraphExecutable {
         required
     }
 
+    /// Number of elementwise operations that run inside the fused kernel
+    /// of another instead of producing a tensor of their own.
+    pub fn fused_nodes(&self) -> usize {
+        self.fused
+    }
+
+    /// Bytes that the tensors computed by one execution take at most at
+    /// once, since each is released after the last operation reading it,
+    /// elementwise operations write over an operand read for the last time,
+    /// and fused kernels, matmuls, reductions, softmaxes, gathers, concats
+    /// and casts write to buffers released before them. Every other
+    /// operation allocates a new buffer for its result, even when a
+    /// released one would fit, and counts in full. Inputs, constants and
+    /// variables are not counted, nor are views, which share the buffer of
+    /// the tensor they look at. `None` when some shape has symbolic
+    /// dimensions.
+    pub fn peak_memory(&self) -> Option<usize> {
+        self.memory.peak_bytes()
+    }
+
+    /// Evaluates the operations whose operands are all constants or folded
+    /// themselves, in plan order. Those still read by the rest of the plan,
+    /// or returned, become constants holding their value; the others leave
+    /// the plan. Inputs, variables, assignments and random samples are
+    /// never folded, and an operation whose kernel fails is left for
+    /// [`GraphExecutable::execute`] to report.
+    fn fold_constants(&mut self) {
+        let mut constant = HashSet::new();
+        let mut assigned = HashMap::new();
+
+        for node_idx in self.execution_plan.clone() {
+            let foldable = match &self.graph[node_idx] {
+                Operation::Constant => {
+                    constant.insert(node_idx);
+                    continue;
+                }
+                Operation::Variable
+                | Operation::Placeholder { .. }
+                | Operation::Uniform { .. }
+                | Operation::Assign
+                | Operation::AssignAdd => false,
+                _ => operands(&self.graph, node_idx)
+                    .iter()
+                    .all(|operand| constant.contains(operand)),
+            };
+
+            if foldable
+                && self
+                    .execute_node(
+                        node_idx,
+                        &mut assigned,
+                        &mut Arena::default(),
+                    )
+                    .is_ok()
+            {
+                constant.insert(node_idx);
+                self.folded += 1;
+            }
+        }
+
+        let in_plan: HashSet<NodeIndex> =
+            self.execution_plan.iter().copied().collect();
+        let needed = |node_idx: NodeIndex| {
+            self.outputs.contains(&node_idx)
+                || self
+                    .graph
+                    .edges_directed(node_idx, Direction::Outgoing)
+                    .any(|edge| {
+                        in_plan.contains(&edge.target())
+                            && !constant.contains(&edge.target())
+                    })
+        };
+        let (kept, dropped): (Vec<NodeIndex>, Vec<NodeIndex>) = self
+            .execution_plan
+            .iter()
+            .copied()
+            .filter(|node_idx| constant.contains(node_idx))
+            .partition(|&node_idx| needed(node_idx));
+
+        for node_idx in kept {
+            self.graph[node_idx] = Operation::Constant;
+            let edges: Vec<_> = self
+                .graph
+                .edges_directed(node_idx, Direction::Incoming)
+                .map(|edge| edge.id())
+                .collect();
+            for edge in edges {
+                self.graph.remove_edge(edge);
+            }
+        }
+        for node_idx in &dropped {
+            self.tensor_storage.remove(node_idx);
+        }
+        self.execution_plan.retain(|node_idx| !dropped.contains(node_idx));
+        self.tuple_storage.clear();
+    }
+
+    /// Runs the graph with `input_data` keyed by placeholder name or tensor,
+    /// returning the outputs in the order they were compiled with. Inputs
+    /// are checked against their placeholders before anything runs.
     pub fn execute(
         &mut self,
-        input_data: HashMap<NodeIndex, TensorStorage>,
-    ) -> Result<HashMap<NodeIndex, TensorStorage>, ExecutionError> {
+        input_data: HashMap<TensorKey, TensorStorage>,
+    ) -> Result<Outputs, ExecutionError> {
+        let mut fed = HashMap::new();
+        for (key, data) in input_data {
+            match self.resolve_key(&key) {
+                Some(node_idx) if self.inputs.contains(&node_idx) => {
+                    fed.insert(node_idx, data);
+                }
+                _ => return Err(ExecutionError::UnexpectedInput(key)),
+            }
+        }
+
+        // Symbols are bound by the first placeholder that uses them, in the
+        // order they were created, and every later one must agree.
+        let mut inputs = self.inputs.clone();
+        inputs.sort_unstable();
+        self.bindings.clear();
+        for node_idx in inputs {
+            let data = fed
+                .get(&node_idx)
+                .ok_or(ExecutionError::MissingInput(node_idx))?;
+            self.check_input(node_idx, data)?;
+        }
+
         // Set input data
-        for &node_idx in &self.inputs {
-            if let Some(data) = input_data.get(&node_idx) {
-                self.tensor_storage.insert(node_idx, data.clone());
-            } else {
-                return Err(ExecutionError::MissingInput(node_idx));
+        for (node_idx, data) in fed {
+            self.tensor_storage.insert(node_idx, TensorView::from(data));
+        }
+        for (&node_idx, value) in &self.variables {
+            if self.tensor_storage.contains_key(&node_idx) {
+                self.tensor_storage.insert(node_idx, value.clone());
             }
         }
 
-        // Execute operations in topological order
+        // Reads see the variables as they were when the execution started
+        let mut assigned = HashMap::new();
+
+        // Execute operations in topological order, releasing every tensor
+        // after its last read. Released buffers donated to a later node are
+        // kept for it until it runs.
+        let mut donated = HashMap::new();
         let execution_plan = self.execution_plan.clone();
-        for node_idx in execution_plan {
-            if let Some(operation) = self.graph.node_weight(node_idx) {
-                match operation {
-                    Operation::Constant | Operation::Variable | Operation::Placeholder => {
-                        // These already have their data in tensor_storage
-                        continue;
-                    }
-                    Operation::Add => {
-                        self.execute_binary_op(node_idx, cpu_add)?;
-                    }
-                    Operation::Sub => {
-                        self.execute_binary_op(node_idx, cpu_sub)?;
-                    }
-                    Operation::Mul => {
-                        self.execute_binary_op(node_idx, cpu_mul)?;
-                    }
-                    Operation::Div => {
-                        self.execute_binary_op(node_idx, cpu_div)?;
-                    }
+        for (step, node_idx) in execution_plan.into_iter().enumerate() {
+            let mut arena = Arena::default();
+            if let Some(storage) = donated
+                .remove(&node_idx)
+                .and_then(TensorView::into_storage)
+            {
+                arena.recycle(storage);
+            }
+            self.execute_node(node_idx, &mut assigned, &mut arena)?;
+
+            for released in self.memory.releases(step) {
+                self.tuple_storage.remove(released);
+                let view = self.tensor_storage.remove(released);
+                if let (Some(view), Some(taker)) =
+                    (view, self.memory.donation(*released))
+                {
+                    donated.insert(taker, view);
                 }
             }
         }
 
+        self.variables.extend(assigned);
+
         // Collect outputs
-        let mut results = HashMap::new();
+        let mut results = Vec::with_capacity(self.outputs.len());
         for &output_idx in &self.outputs {
-            if let Some(data) = self.tensor_storage.get(&output_idx) {
-                results.insert(output_idx, data.clone());
+            let view = self
+                .tensor_storage
+                .get(&output_idx)
+                .ok_or(ExecutionError::InvalidOperation)?;
+            results.push((output_idx, cpu_contiguous(view)));
+        }
+
+        let names = self
+            .names
+            .iter()
+            .filter(|(_, node_idx)| self.outputs.contains(node_idx))
+            .map(|(name, &node_idx)| (name.clone(), node_idx))
+            .collect();
+
+        Ok(Outputs::new(results, names))
+    }
+
+    /// Computes `node_idx` from operands that are already in
+    /// `tensor_storage`. Assignments write to `assigned`, and kernels that
+    /// can take their buffer from `arena` do.
+    fn execute_node(
+        &mut self,
+        node_idx: NodeIndex,
+        assigned: &mut HashMap<NodeIndex, TensorView>,
+        arena: &mut Arena,
+    ) -> Result<(), ExecutionError> {
+        let Some(operation) = self.graph.node_weight(node_idx).cloned() else {
+            return Ok(());
+        };
+
+        // An operand read for the last time holds the result instead
+        if operation.is_elementwise() && self.memory.in_place(node_idx) {
+            return self.execute_in_place(node_idx, &operation);
+        }
+
+        match operation {
+            Operation::Constant
+            | Operation::Variable
+            | Operation::Placeholder { .. } => {
+                // These already have their data in tensor_storage
+            }
+            Operation::Add => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_add,
+                )?;
+            }
+            Operation::Sub => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_sub,
+                )?;
+            }
+            Operation::Mul => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_mul,
+                )?;
+            }
+            Operation::Div => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_div,
+                )?;
+            }
+            Operation::Equal => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_equal,
+                )?;
+            }
+            Operation::NotEqual => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_not_equal,
+                )?;
+            }
+            Operation::Less => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_less,
+                )?;
+            }
+            Operation::LessEqual => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_less_equal,
+                )?;
+            }
+            Operation::Greater => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_greater,
+                )?;
+            }
+            Operation::GreaterEqual => {
+                self.execute_binary_op(
+                    node_idx,
+                    Shape::broadcast_with,
+                    cpu_greater_equal,
+                )?;
+            }
+            Operation::MatMul {
+                transpose_lhs,
+                transpose_rhs,
+            } => {
+                self.execute_binary_op(
+                    node_idx,
+                    |lhs, rhs| {
+                        lhs.matmul_with_transposed(
+                            rhs,
+                            transpose_lhs,
+                            transpose_rhs,
+                        )
+                    },
+                    |lhs, rhs, output_shape| {
+                        cpu_matmul(
+                            lhs,
+                            rhs,
+                            transpose_lhs,
+                            transpose_rhs,
+                            output_shape,
+                            arena,
+                        )
+                    },
+                )?;
+            }
+            Operation::Neg => {
+                self.execute_unary_op(node_idx, cpu_neg)?;
+            }
+            Operation::Abs => {
+                self.execute_unary_op(node_idx, cpu_abs)?;
+            }
+            Operation::Exp => {
+                self.execute_unary_op(node_idx, cpu_exp)?;
+            }
+            Operation::Log => {
+                self.execute_unary_op(node_idx, cpu_log)?;
+            }
+            Operation::Sqrt => {
+                self.execute_unary_op(node_idx, cpu_sqrt)?;
+            }
+            Operation::Rsqrt => {
+                self.execute_unary_op(node_idx, cpu_rsqrt)?;
+            }
+            Operation::Sin => {
+                self.execute_unary_op(node_idx, cpu_sin)?;
+            }
+            Operation::Cos => {
+                self.execute_unary_op(node_idx, cpu_cos)?;
+            }
+            Operation::Tanh => {
+                self.execute_unary_op(node_idx, cpu_tanh)?;
+            }
+            Operation::Sigmoid => {
+                self.execute_unary_op(node_idx, cpu_sigmoid)?;
+            }
+            Operation::Relu => {
+                self.execute_unary_op(node_idx, cpu_relu)?;
+            }
+            Operation::Gelu => {
+                self.execute_unary_op(node_idx, cpu_gelu)?;
+            }
+            Operation::Erf => {
+                self.execute_unary_op(node_idx, cpu_erf)?;
+            }
+            Operation::Floor => {
+                self.execute_unary_op(node_idx, cpu_floor)?;
+            }
+            Operation::Ceil => {
+                self.execute_unary_op(node_idx, cpu_ceil)?;
+            }
+            Operation::Round => {
+                self.execute_unary_op(node_idx, cpu_round)?;
+            }
+            Operation::Sign => {
+                self.execute_unary_op(node_idx, cpu_sign)?;
+            }
+            Operation::Reciprocal => {
+                self.execute_unary_op(node_idx, cpu_reciprocal)?;
+            }
+            Operation::Sum { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_sum,
+                )?;
+            }
+            Operation::Mean { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_mean,
+                )?;
+            }
+            Operation::Max { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_max,
+                )?;
+            }
+            Operation::Min { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_min,
+                )?;
+            }
+            Operation::Prod { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_prod,
+                )?;
+            }
+            Operation::ArgMax { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_argmax,
+                )?;
+            }
+            Operation::ArgMin { axes, keepdims } => {
+                self.execute_reduce_op(
+                    node_idx, &axes, keepdims, arena, cpu_argmin,
+                )?;
+            }
+            Operation::Softmax { axis } => {
+                self.execute_unary_op(node_idx, |input| {
+                    cpu_softmax(input, axis, arena)
+                })?;
+            }
+            Operation::LogSoftmax { axis } => {
+                self.execute_unary_op(node_idx, |input| {
+                    cpu_log_softmax(input, axis, arena)
+                })?;
+            }
+            Operation::Reshape { shape } => {
+                let shape = self.resolve(node_idx, &shape)?;
+                self.execute_view_op(node_idx, |input| {
+                    let layout = input.layout().reshape(&shape);
+                    let view = match layout {
+                        Some(layout) => input.with_layout(layout),
+                        None => TensorView::new(
+                            Rc::new(cpu_contiguous(input)),
+                            Layout::contiguous(&shape),
+                        ),
+                    };
+
+                    Ok(view)
+                })?;
+            }
+            Operation::Permute { axes } => {
+                self.execute_view_op(node_idx, |input| {
+                    Ok(input.with_layout(input.layout().permute(&axes)))
+                })?;
+            }
+            Operation::Squeeze { axes } => {
+                self.execute_view_op(node_idx, |input| {
+                    Ok(input.with_layout(input.layout().squeeze(&axes)))
+                })?;
+            }
+            Operation::Unsqueeze { axes } => {
+                self.execute_view_op(node_idx, |input| {
+                    let layout = input.layout().unsqueeze(&axes);
+
+                    Ok(input.with_layout(layout))
+                })?;
+            }
+            Operation::Expand { shape } => {
+                let shape = self.resolve(node_idx, &shape)?;
+                self.execute_view_op(node_idx, |input| {
+                    let layout =
+                        broadcast_layout("expand", input.layout(), &shape)?;
+
+                    Ok(input.with_layout(layout))
+                })?;
+            }
+            Operation::Slice { slices } => {
+                self.execute_view_op(node_idx, |input| {
+                    let ranges: Vec<_> = slices
+                        .iter()
+                        .zip(input.shape())
+                        .map(|(slice, &dim)| {
+                            let (start, len) = slice.resolve(dim);
+                            (start, len, slice.step as usize)
+                        })
+                        .collect();
+
+                    Ok(input.with_layout(input.layout().slice(&ranges)))
+                })?;
+            }
+            Operation::SliceScatter { slices } => {
+                self.execute_variadic_op(node_idx, 2, |inputs| {
+                    cpu_slice_scatter(inputs[0], inputs[1], &slices)
+                })?;
+            }
+            Operation::IndexSelect { axis } => {
+                self.execute_variadic_op(node_idx, 2, |inputs| {
+                    cpu_index_select(inputs[0], inputs[1], axis, arena)
+                })?;
+            }
+            Operation::Gather { axis } => {
+                self.execute_variadic_op(node_idx, 2, |inputs| {
+                    cpu_gather(inputs[0], inputs[1], axis, arena)
+                })?;
+            }
+            Operation::Scatter { axis, accumulate } => {
+                self.execute_variadic_op(node_idx, 3, |inputs| {
+                    cpu_scatter(
+                        inputs[0], inputs[1], inputs[2], axis, accumulate,
+                    )
+                })?;
+            }
+            Operation::Where => {
+                self.execute_variadic_op(node_idx, 3, |inputs| {
+                    let mut shape = inputs[0].shape().to_vec();
+                    for input in &inputs[1..] {
+                        shape = broadcast_dims(&shape, input.shape())
+                            .ok_or_else(|| KernelError::IncompatibleShapes {
+                                op: "where",
+                                lhs: shape.clone(),
+                                rhs: input.shape().to_vec(),
+                            })?;
+                    }
+
+                    cpu_where(inputs[0], inputs[1], inputs[2], &shape)
+                })?;
+            }
+            Operation::Concat { axis } => {
+                let arity = operands(&self.graph, node_idx).len();
+                self.execute_variadic_op(node_idx, arity, |inputs| {
+                    cpu_concat(inputs, axis, arena)
+                })?;
+            }
+            Operation::Split { axis, sizes } => {
+                let inputs = operands(&self.graph, node_idx);
+                let input = self
+                    .tensor_storage
+                    .get(&inputs[0])
+                    .ok_or(ExecutionError::InvalidOperation)?;
+
+                let mut ranges: Vec<_> =
+                    input.shape().iter().map(|&dim| (0, dim, 1)).collect();
+                let mut start = 0;
+                let pieces = sizes
+                    .iter()
+                    .map(|&size| {
+                        ranges[axis] = (start, size, 1);
+                        start += size;
+                        input.with_layout(input.layout().slice(&ranges))
+                    })
+                    .collect();
+
+                self.tuple_storage.insert(node_idx, pieces);
+            }
+            Operation::Output { index } => {
+                let inputs = operands(&self.graph, node_idx);
+                let piece = self
+                    .tuple_storage
+                    .get(&inputs[0])
+                    .and_then(|pieces| pieces.get(index))
+                    .ok_or(ExecutionError::InvalidOperation)?
+                    .clone();
+
+                self.tensor_storage.insert(node_idx, piece);
+            }
+            Operation::SumTo { shape } => {
+                let shape = self.resolve(node_idx, &shape)?;
+                self.execute_unary_op(node_idx, |input| {
+                    cpu_sum_to(input, &shape)
+                })?;
+            }
+            Operation::OnesLike => {
+                self.execute_unary_op(node_idx, |input| {
+                    Ok(cpu_ones_like(input))
+                })?;
+            }
+            Operation::ZerosLike => {
+                self.execute_unary_op(node_idx, |input| {
+                    Ok(cpu_zeros_like(input))
+                })?;
+            }
+            Operation::FullLike { value } => {
+                self.execute_unary_op(node_idx, |input| {
+                    Ok(cpu_full_like(input, value))
+                })?;
+            }
+            Operation::Uniform { seed } => {
+                self.execute_variadic_op(node_idx, 2, |inputs| {
+                    cpu_uniform(inputs[0], inputs[1], seed)
+                })?;
+            }
+            Operation::Cast { dtype } => {
+                self.execute_unary_op(node_idx, |input| {
+                    Ok(cpu_cast(input, dtype, arena))
+                })?;
+            }
+            Operation::Assign => {
+                self.execute_assign(node_idx, false, assigned)?;
+            }
+            Operation::AssignAdd => {
+                self.execute_assign(node_idx, true, assigned)?;
+            }
+            Operation::Fused { steps } => {
+                self.execute_fused(node_idx, &steps, arena)?;
             }
         }
 
-        Ok(results)
+        Ok(())
     }
     
     fn execute_binary_op(
         &mut self,
         node_idx: NodeIndex,
-        op_fn: fn(&TensorStorage, &TensorStorage, &Shape) -> TensorStorage,
+        infer_shape: impl Fn(&Shape, &Shape) -> Result<Shape, BroadcastError>,
+        op_fn: impl FnOnce(
+            &TensorView,
+            &TensorView,
+            &[usize],
+        ) -> Result<TensorStorage, KernelError>,
     ) -> Result<(), ExecutionError> {
         // Get input nodes (assumes binary operation has exactly 2 inputs)
-        let inputs: Vec<NodeIndex> = self.graph
-            .edges_directed(node_idx, Direction::Incoming)
-            .map(|edge| edge.source())
-            .collect();
+        let inputs = operands(&self.graph, node_idx);
         
         if inputs.len() != 2 {
             return Err(ExecutionError::InvalidOperation);
@@ -192,21 +992,384 @@ impl GraphExecutable {
         let rhs_data = self.tensor_storage.get(&inputs[1])
             .ok_or(ExecutionError::InvalidOperation)?;
         
-        // Compute output shape (broadcast)
-        let lhs_shape = Shape::from(lhs_data.shape().to_vec());
-        let rhs_shape = Shape::from(rhs_data.shape().to_vec());
-        let output_shape = lhs_shape.broadcast_with(&rhs_shape)
-            .map_err(|_| ExecutionError::InvalidOperation)?;
+        // Compute output shape
+        let lhs_shape = Shape::from(lhs_data.shape());
+        let rhs_shape = Shape::from(rhs_data.shape());
+        let output_shape = infer_shape(&lhs_shape, &rhs_shape)
+            .ok()
+            .and_then(|shape| shape.fixed_dims())
+            .ok_or(ExecutionError::InvalidOperation)?;
         
         // Execute operation
-        let result = op_fn(lhs_data, rhs_data, &output_shape);
+        let result = op_fn(lhs_data, rhs_data, &output_shape)
+            .map_err(|source| ExecutionError::Kernel {
+                node: node_idx,
+                source,
+            })?;
         
         // Store result
-        self.tensor_storage.insert(node_idx, result);
+        self.tensor_storage.insert(node_idx, TensorView::from(result));
         
         Ok(())
     }
 
+    fn execute_unary_op(
+        &mut self,
+        node_idx: NodeIndex,
+        op_fn: impl FnOnce(&TensorView) -> Result<TensorStorage, KernelError>,
+    ) -> Result<(), ExecutionError> {
+        self.execute_view_op(node_idx, |input| {
+            op_fn(input).map(TensorView::from)
+        })
+    }
+
+    /// Runs an operation that may return a view sharing its input's storage.
+    fn execute_view_op(
+        &mut self,
+        node_idx: NodeIndex,
+        op_fn: impl FnOnce(&TensorView) -> Result<TensorView, KernelError>,
+    ) -> Result<(), ExecutionError> {
+        let inputs = operands(&self.graph, node_idx);
+
+        if inputs.len() != 1 {
+            return Err(ExecutionError::InvalidOperation);
+        }
+
+        let input_data = self
+            .tensor_storage
+            .get(&inputs[0])
+            .ok_or(ExecutionError::InvalidOperation)?;
+
+        let result =
+            op_fn(input_data).map_err(|source| ExecutionError::Kernel {
+                node: node_idx,
+                source,
+            })?;
+
+        self.tensor_storage.insert(node_idx, result);
+
+        Ok(())
+    }
+
+    /// Runs an operation on exactly `arity` operands, in order.
+    fn execute_variadic_op(
+        &mut self,
+        node_idx: NodeIndex,
+        arity: usize,
+        op_fn: impl FnOnce(
+            &[&TensorView],
+        ) -> Result<TensorStorage, KernelError>,
+    ) -> Result<(), ExecutionError> {
+        let inputs = operands(&self.graph, node_idx);
+
+        if inputs.len() != arity {
+            return Err(ExecutionError::InvalidOperation);
+        }
+
+        let input_data = inputs
+            .iter()
+            .map(|input| self.tensor_storage.get(input))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ExecutionError::InvalidOperation)?;
+
+        let result =
+            op_fn(&input_data).map_err(|source| ExecutionError::Kernel {
+                node: node_idx,
+                source,
+            })?;
+
+        self.tensor_storage.insert(node_idx, TensorView::from(result));
+
+        Ok(())
+    }
+
+    /// Runs the elementwise operation at `node_idx` with its in-place
+    /// kernel. The operand whose buffer is donated to `node_idx` leaves
+    /// `tensor_storage` first, so that the kernel can write over it.
+    fn execute_in_place(
+        &mut self,
+        node_idx: NodeIndex,
+        operation: &Operation,
+    ) -> Result<(), ExecutionError> {
+        let inputs = operands(&self.graph, node_idx);
+
+        let mut views = Vec::with_capacity(inputs.len());
+        for input in &inputs {
+            let view = match self.memory.donation(*input) == Some(node_idx) {
+                true => self.tensor_storage.remove(input),
+                false => self.tensor_storage.get(input).cloned(),
+            };
+            views.push(view.ok_or(ExecutionError::InvalidOperation)?);
+        }
+        let mut views = views.into_iter();
+
+        let result = match *operation {
+            Operation::FullLike { value } => {
+                let input =
+                    views.next().ok_or(ExecutionError::InvalidOperation)?;
+
+                Ok(cpu_full_like_in_place(input, value))
+            }
+            Operation::Add
+            | Operation::Sub
+            | Operation::Mul
+            | Operation::Div => {
+                let (Some(lhs), Some(rhs)) = (views.next(), views.next())
+                else {
+                    return Err(ExecutionError::InvalidOperation);
+                };
+                let output_shape = broadcast_dims(lhs.shape(), rhs.shape())
+                    .ok_or(ExecutionError::InvalidOperation)?;
+                let kernel = match operation {
+                    Operation::Add => cpu_add_in_place,
+                    Operation::Sub => cpu_sub_in_place,
+                    Operation::Mul => cpu_mul_in_place,
+                    _ => cpu_div_in_place,
+                };
+
+                kernel(lhs, rhs, &output_shape)
+            }
+            _ => {
+                let input =
+                    views.next().ok_or(ExecutionError::InvalidOperation)?;
+                let kernel = unary_in_place(operation)
+                    .ok_or(ExecutionError::InvalidOperation)?;
+
+                kernel(input)
+            }
+        }
+        .map_err(|source| ExecutionError::Kernel {
+            node: node_idx,
+            source,
+        })?;
+
+        self.tensor_storage.insert(node_idx, TensorView::from(result));
+
+        Ok(())
+    }
+
+    /// Runs `steps` over the operands of `node_idx`. Operands whose buffer
+    /// is donated to `node_idx` leave `tensor_storage` first, so that the
+    /// kernel can write over them.
+    fn execute_fused(
+        &mut self,
+        node_idx: NodeIndex,
+        steps: &[FusedStep],
+        arena: &mut Arena,
+    ) -> Result<(), ExecutionError> {
+        let inputs = operands(&self.graph, node_idx);
+
+        let input_data = inputs
+            .iter()
+            .map(|input| self.tensor_storage.get(input).cloned())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ExecutionError::InvalidOperation)?;
+        for input in &inputs {
+            if self.memory.donation(*input) == Some(node_idx) {
+                self.tensor_storage.remove(input);
+            }
+        }
+
+        let result = cpu_fused(input_data, steps, arena).map_err(|source| {
+            ExecutionError::Kernel {
+                node: node_idx,
+                source,
+            }
+        })?;
+
+        self.tensor_storage.insert(node_idx, TensorView::from(result));
+
+        Ok(())
+    }
+
+    fn execute_reduce_op(
+        &mut self,
+        node_idx: NodeIndex,
+        axes: &[usize],
+        keepdims: bool,
+        arena: &mut Arena,
+        op_fn: fn(
+            &TensorView,
+            &[usize],
+            bool,
+            &mut Arena,
+        ) -> Result<TensorStorage, KernelError>,
+    ) -> Result<(), ExecutionError> {
+        self.execute_unary_op(node_idx, |input| {
+            op_fn(input, axes, keepdims, arena)
+        })
+    }
+
+    /// Writes the second operand of the assignment at `node_idx` into the
+    /// variable that is the first, or adds it when `accumulate` is set. The
+    /// new value goes to `assigned`, where later assignments in the same
+    /// execution build on it.
+    fn execute_assign(
+        &mut self,
+        node_idx: NodeIndex,
+        accumulate: bool,
+        assigned: &mut HashMap<NodeIndex, TensorView>,
+    ) -> Result<(), ExecutionError> {
+        let inputs = operands(&self.graph, node_idx);
+        let [variable, value] = inputs[..] else {
+            return Err(ExecutionError::InvalidOperation);
+        };
+
+        let current = assigned
+            .get(&variable)
+            .or_else(|| self.variables.get(&variable))
+            .ok_or(ExecutionError::InvalidOperation)?;
+        let value = self
+            .tensor_storage
+            .get(&value)
+            .ok_or(ExecutionError::InvalidOperation)?;
+
+        let shape = current.shape();
+        let result = match accumulate {
+            true => cpu_add(current, value, shape),
+            false => broadcast_layout("assign", value.layout(), shape)
+                .map(|layout| cpu_contiguous(&value.with_layout(layout))),
+        }
+        .map_err(|source| ExecutionError::Kernel {
+            node: node_idx,
+            source,
+        })?;
+
+        let result = TensorView::from(result);
+        assigned.insert(variable, result.clone());
+        self.tensor_storage.insert(node_idx, result);
+
+        Ok(())
+    }
+
+    /// The current value of the variable named or given by `key`.
+    pub fn get_variable(
+        &self,
+        key: impl Into<TensorKey>,
+    ) -> Option<&TensorStorage> {
+        let node_idx = self.resolve_key(&key.into())?;
+
+        self.variables.get(&node_idx).map(TensorView::storage)
+    }
+
+    /// Replaces the value of the variable named or given by `key`, which
+    /// must keep its shape and dtype. Later executions read the new value.
+    pub fn set_variable(
+        &mut self,
+        key: impl Into<TensorKey>,
+        data: TensorStorage,
+    ) -> Result<(), ExecutionError> {
+        let key = key.into();
+        let Some((node_idx, current)) = self
+            .resolve_key(&key)
+            .and_then(|node_idx| self.variables.get_key_value(&node_idx))
+        else {
+            return Err(ExecutionError::UnknownVariable(key));
+        };
+
+        let (node_idx, shape) = (*node_idx, Shape::from(current.shape()));
+        let dtype = current.storage().dtype();
+        self.check_data(node_idx, &shape, dtype, &data)?;
+        self.variables.insert(node_idx, TensorView::from(data));
+
+        Ok(())
+    }
+
+    /// The node named or given by `key`, if it may be in this executable.
+    fn resolve_key(&self, key: &TensorKey) -> Option<NodeIndex> {
+        match key {
+            TensorKey::Name(name) => self.names.get(name).copied(),
+            TensorKey::Node(node_idx) => Some(*node_idx),
+        }
+    }
+
+    /// Checks `data` against the placeholder at `node_idx`.
+    fn check_input(
+        &mut self,
+        node_idx: NodeIndex,
+        data: &TensorStorage,
+    ) -> Result<(), ExecutionError> {
+        let Some(Operation::Placeholder { shape, dtype }) =
+            self.graph.node_weight(node_idx)
+        else {
+            return Err(ExecutionError::InvalidOperation);
+        };
+
+        self.check_data(node_idx, &shape.clone(), *dtype, data)
+    }
+
+    /// Checks `data` fed to `node_idx` against the `shape` and `dtype` the
+    /// node was declared with, binding the symbols in `shape` that are not
+    /// bound yet.
+    fn check_data(
+        &mut self,
+        node_idx: NodeIndex,
+        shape: &Shape,
+        dtype: DType,
+        data: &TensorStorage,
+    ) -> Result<(), ExecutionError> {
+        if data.dtype() != dtype {
+            return Err(ExecutionError::DtypeMismatch {
+                node: node_idx,
+                expected: dtype,
+                actual: data.dtype(),
+            });
+        }
+        let fits = data.shape().len() == shape.rank()
+            && shape
+                .dims()
+                .iter()
+                .zip(data.shape())
+                .all(|(dim, &size)| dim.fixed().is_none_or(|f| f == size));
+        if !fits {
+            return Err(ExecutionError::ShapeMismatch {
+                node: node_idx,
+                expected: shape.clone(),
+                actual: data.shape().to_vec(),
+            });
+        }
+        for (dim, &size) in shape.dims().iter().zip(data.shape()) {
+            let Dim::Sym(symbol) = *dim else { continue };
+            match self.bindings.get(symbol) {
+                Some(&bound) if bound != size => {
+                    return Err(ExecutionError::SymbolMismatch {
+                        node: node_idx,
+                        symbol,
+                        bound,
+                        actual: size,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.bindings.insert(symbol, size);
+                }
+            }
+        }
+        if data.len() != data.shape().iter().product::<usize>() {
+            return Err(ExecutionError::MalformedInput {
+                node: node_idx,
+                len: data.len(),
+                shape: data.shape().to_vec(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The sizes of `shape` under the current bindings.
+    fn resolve(
+        &self,
+        node_idx: NodeIndex,
+        shape: &Shape,
+    ) -> Result<Vec<usize>, ExecutionError> {
+        shape
+            .resolve(&self.bindings)
+            .map_err(|symbol| ExecutionError::UnboundSymbol {
+                node: node_idx,
+                symbol,
+            })
+    }
+
     pub fn inputs(&self) -> &[NodeIndex] {
         &self.inputs
     }
@@ -215,3 +1378,41 @@ impl GraphExecutable {
         &self.outputs
     }
 }
+
+/// The broadcast of two concrete shapes, if they are compatible.
+fn broadcast_dims(lhs: &[usize], rhs: &[usize]) -> Option<Vec<usize>> {
+    Shape::from(lhs)
+        .broadcast_with(&Shape::from(rhs))
+        .ok()
+        .and_then(|shape| shape.fixed_dims())
+}
+
+/// The in-place kernel of a unary elementwise operation.
+fn unary_in_place(
+    operation: &Operation,
+) -> Option<fn(TensorView) -> Result<TensorStorage, KernelError>> {
+    let kernel: fn(TensorView) -> Result<TensorStorage, KernelError> =
+        match operation {
+            Operation::Neg => cpu_neg_in_place,
+            Operation::Abs => cpu_abs_in_place,
+            Operation::Exp => cpu_exp_in_place,
+            Operation::Log => cpu_log_in_place,
+            Operation::Sqrt => cpu_sqrt_in_place,
+            Operation::Rsqrt => cpu_rsqrt_in_place,
+            Operation::Sin => cpu_sin_in_place,
+            Operation::Cos => cpu_cos_in_place,
+            Operation::Tanh => cpu_tanh_in_place,
+            Operation::Sigmoid => cpu_sigmoid_in_place,
+            Operation::Relu => cpu_relu_in_place,
+            Operation::Gelu => cpu_gelu_in_place,
+            Operation::Erf => cpu_erf_in_place,
+            Operation::Floor => cpu_floor_in_place,
+            Operation::Ceil => cpu_ceil_in_place,
+            Operation::Round => cpu_round_in_place,
+            Operation::Sign => cpu_sign_in_place,
+            Operation::Reciprocal => cpu_reciprocal_in_place,
+            _ => return None,
+        };
+
+    Some(kernel)
+}
@@ -0,0 +1,103 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use petgraph::{Direction, graph::NodeIndex, visit::EdgeRef};
+
+use crate::op::{FusedStep, Operation};
+
+use super::inner::{OpGraph, operands};
+
+/// Turns every group of elementwise nodes of `plan`, which must be
+/// topologically sorted, into one [`Operation::Fused`] node. A group grows
+/// from its last node through operands that are elementwise too and read
+/// only by the group within `plan`; such an operand never needs a tensor.
+/// The last node becomes the fused node and the others are removed, except
+/// for `outputs`, whose values are returned. Returns the removed nodes.
+pub(crate) fn fuse_elementwise(
+    graph: &mut OpGraph,
+    plan: &[NodeIndex],
+    outputs: &[NodeIndex],
+) -> HashSet<NodeIndex> {
+    let position: HashMap<NodeIndex, usize> = plan
+        .iter()
+        .enumerate()
+        .map(|(i, &node)| (node, i))
+        .collect();
+    let mut removed = HashSet::new();
+
+    for &root in plan.iter().rev() {
+        if removed.contains(&root) || !graph[root].is_elementwise() {
+            continue;
+        }
+
+        // Candidates are taken latest first, so every consumer of one has
+        // been settled by the time it is looked at.
+        let mut group = Vec::new();
+        let mut candidates = BTreeSet::from([(position[&root], root)]);
+        while let Some((_, candidate)) = candidates.pop_last() {
+            let consumed_inside = candidate == root
+                || graph
+                    .edges_directed(candidate, Direction::Outgoing)
+                    .filter(|edge| position.contains_key(&edge.target()))
+                    .all(|edge| group.contains(&edge.target()));
+            if !consumed_inside {
+                continue;
+            }
+            group.push(candidate);
+            for operand in operands(graph, candidate) {
+                if !outputs.contains(&operand)
+                    && graph[operand].is_elementwise()
+                {
+                    candidates.insert((position[&operand], operand));
+                }
+            }
+        }
+        if group.len() == 1 {
+            continue;
+        }
+        group.reverse();
+
+        // Values are numbered inputs first, then one per node of the group.
+        let mut inputs = Vec::new();
+        for &node_idx in &group {
+            for operand in operands(graph, node_idx) {
+                if !group.contains(&operand) && !inputs.contains(&operand) {
+                    inputs.push(operand);
+                }
+            }
+        }
+        let value =
+            |node: NodeIndex| match group.iter().position(|&n| n == node) {
+                Some(step) => inputs.len() + step,
+                None => inputs.iter().position(|&n| n == node).unwrap(),
+            };
+        let steps = group
+            .iter()
+            .map(|&node_idx| FusedStep {
+                op: graph[node_idx].clone(),
+                operands: operands(graph, node_idx)
+                    .into_iter()
+                    .map(value)
+                    .collect(),
+            })
+            .collect();
+
+        let edges: Vec<_> = graph
+            .edges_directed(root, Direction::Incoming)
+            .map(|edge| edge.id())
+            .collect();
+        for edge in edges {
+            graph.remove_edge(edge);
+        }
+        for (slot, &input) in inputs.iter().enumerate() {
+            graph.add_edge(input, root, slot);
+        }
+        graph[root] = Operation::Fused { steps };
+
+        for &node_idx in &group[..group.len() - 1] {
+            graph.remove_node(node_idx);
+            removed.insert(node_idx);
+        }
+    }
+
+    removed
+}
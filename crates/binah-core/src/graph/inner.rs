@@ -1,13 +1,33 @@
 use std::collections::HashMap;
 
-use petgraph::{graph::NodeIndex, prelude::StableGraph};
+use petgraph::{
+    Direction, graph::NodeIndex, prelude::StableGraph, visit::EdgeRef,
+};
 
-use crate::{op::Operation, tensor::storage::TensorStorage};
+use crate::{
+    graph::GraphError,
+    op::Operation,
+    tensor::{
+        shape::Shape,
+        storage::{DType, TensorStorage},
+    },
+};
+
+use super::TypePromotion;
+
+/// Each edge carries the operand slot it feeds, so that operands are read
+/// back in order no matter how petgraph iterates the edges, and a node that
+/// feeds two slots of the same operation gets one edge per slot.
+pub(crate) type OpGraph = StableGraph<Operation, usize>;
 
 #[derive(Clone, Debug)]
 pub(crate) struct GraphInner {
-    graph: StableGraph<Operation, ()>,
+    graph: OpGraph,
     tensor_map: HashMap<NodeIndex, TensorStorage>,
+    shape_map: HashMap<NodeIndex, Shape>,
+    dtype_map: HashMap<NodeIndex, DType>,
+    names: HashMap<String, NodeIndex>,
+    type_promotion: TypePromotion,
 }
 
 impl GraphInner {
@@ -15,6 +35,10 @@ impl GraphInner {
         Self {
             graph: StableGraph::new(),
             tensor_map: HashMap::new(),
+            shape_map: HashMap::new(),
+            dtype_map: HashMap::new(),
+            names: HashMap::new(),
+            type_promotion: TypePromotion::default(),
         }
     }
 
@@ -23,10 +47,14 @@ impl GraphInner {
         Self {
             graph: StableGraph::with_capacity(capacity, 0),
             tensor_map: HashMap::with_capacity(capacity),
+            shape_map: HashMap::with_capacity(capacity),
+            dtype_map: HashMap::with_capacity(capacity),
+            names: HashMap::new(),
+            type_promotion: TypePromotion::default(),
         }
     }
 
-    pub(crate) fn graph(&self) -> &StableGraph<Operation, ()> {
+    pub(crate) fn graph(&self) -> &OpGraph {
         &self.graph
     }
 
@@ -34,8 +62,86 @@ impl GraphInner {
         &self.tensor_map
     }
 
-    pub fn add_op(&mut self, op: Operation) -> NodeIndex {
-        self.graph.add_node(op)
+    pub(crate) fn names(&self) -> &HashMap<String, NodeIndex> {
+        &self.names
+    }
+
+    /// Names `node_id`, failing when another node already has the name.
+    pub(crate) fn add_name(
+        &mut self,
+        node_id: NodeIndex,
+        name: &str,
+    ) -> Result<(), GraphError> {
+        match self.names.get(name) {
+            Some(&owner) if owner != node_id => {
+                Err(GraphError::DuplicateName {
+                    name: name.to_string(),
+                    node: owner,
+                })
+            }
+            _ => {
+                self.names.insert(name.to_string(), node_id);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn shape(&self, node_id: NodeIndex) -> &Shape {
+        &self.shape_map[&node_id]
+    }
+
+    pub(crate) fn dtype(&self, node_id: NodeIndex) -> DType {
+        self.dtype_map[&node_id]
+    }
+
+    /// The shape and dtype of `node_id`, unless it produces no tensor.
+    pub(crate) fn value_type(
+        &self,
+        node_id: NodeIndex,
+    ) -> Option<(Shape, DType)> {
+        let shape = self.shape_map.get(&node_id)?;
+
+        Some((shape.clone(), *self.dtype_map.get(&node_id)?))
+    }
+
+    pub(crate) fn type_promotion(&self) -> TypePromotion {
+        self.type_promotion
+    }
+
+    pub(crate) fn set_type_promotion(&mut self, promotion: TypePromotion) {
+        self.type_promotion = promotion;
+    }
+
+    pub(crate) fn operands(&self, node_id: NodeIndex) -> Vec<NodeIndex> {
+        operands(&self.graph, node_id)
+    }
+
+    /// Adds an operation without operands, such as a constant.
+    pub fn add_op(
+        &mut self,
+        op: Operation,
+        shape: Shape,
+        dtype: DType,
+    ) -> NodeIndex {
+        let node_id = self.graph.add_node(op);
+        self.shape_map.insert(node_id, shape);
+        self.dtype_map.insert(node_id, dtype);
+
+        node_id
+    }
+
+    pub fn add_unary_op(
+        &mut self,
+        input: NodeIndex,
+        op: Operation,
+        shape: Shape,
+    ) -> NodeIndex {
+        let dtype = op.output_dtype(&[self.dtype(input)]);
+        let node_id = self.add_op(op, shape, dtype);
+
+        self.graph.add_edge(input, node_id, 0);
+
+        node_id
     }
 
     pub fn add_binary_op(
@@ -43,11 +149,32 @@ impl GraphInner {
         lhs: NodeIndex,
         rhs: NodeIndex,
         op: Operation,
+        shape: Shape,
     ) -> NodeIndex {
-        let node_id = self.add_op(op);
+        let dtype = op.output_dtype(&[self.dtype(lhs), self.dtype(rhs)]);
+        let node_id = self.add_op(op, shape, dtype);
 
-        self.graph.add_edge(lhs, node_id, ());
-        self.graph.add_edge(rhs, node_id, ());
+        self.graph.add_edge(lhs, node_id, 0);
+        self.graph.add_edge(rhs, node_id, 1);
+
+        node_id
+    }
+
+    /// Adds an operation whose operands are `inputs`, in order.
+    pub fn add_variadic_op(
+        &mut self,
+        inputs: &[NodeIndex],
+        op: Operation,
+        shape: Shape,
+    ) -> NodeIndex {
+        let dtypes: Vec<DType> =
+            inputs.iter().map(|&input| self.dtype(input)).collect();
+        let dtype = op.output_dtype(&dtypes);
+        let node_id = self.add_op(op, shape, dtype);
+
+        for (slot, &input) in inputs.iter().enumerate() {
+            self.graph.add_edge(input, node_id, slot);
+        }
 
         node_id
     }
@@ -60,3 +187,14 @@ impl GraphInner {
         self.tensor_map.insert(node_id, storage);
     }
 }
+
+/// Returns the inputs of `node_id` ordered by operand slot.
+pub(crate) fn operands(graph: &OpGraph, node_id: NodeIndex) -> Vec<NodeIndex> {
+    let mut inputs: Vec<(usize, NodeIndex)> = graph
+        .edges_directed(node_id, Direction::Incoming)
+        .map(|edge| (*edge.weight(), edge.source()))
+        .collect();
+    inputs.sort_unstable_by_key(|&(slot, _)| slot);
+
+    inputs.into_iter().map(|(_, input)| input).collect()
+}
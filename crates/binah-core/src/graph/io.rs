@@ -0,0 +1,118 @@
+use std::{collections::HashMap, ops::Index};
+
+use petgraph::graph::NodeIndex;
+
+use crate::tensor::storage::TensorStorage;
+
+use super::tensor::GraphTensor;
+
+/// Identifies a tensor fed to or returned from
+/// [`GraphExecutable::execute`](super::GraphExecutable::execute): either its
+/// name or its node.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TensorKey {
+    Name(String),
+    Node(NodeIndex),
+}
+
+impl From<&str> for TensorKey {
+    fn from(name: &str) -> Self {
+        TensorKey::Name(name.to_string())
+    }
+}
+
+impl From<String> for TensorKey {
+    fn from(name: String) -> Self {
+        TensorKey::Name(name)
+    }
+}
+
+impl From<&GraphTensor> for TensorKey {
+    fn from(tensor: &GraphTensor) -> Self {
+        TensorKey::Node(tensor.node_id())
+    }
+}
+
+impl From<NodeIndex> for TensorKey {
+    fn from(node_id: NodeIndex) -> Self {
+        TensorKey::Node(node_id)
+    }
+}
+
+/// The results of one execution, in the order the targets were passed to
+/// [`Graph::compile`](super::Graph::compile).
+#[derive(Clone, Debug)]
+pub struct Outputs {
+    entries: Vec<(NodeIndex, TensorStorage)>,
+    names: HashMap<String, NodeIndex>,
+}
+
+impl Outputs {
+    pub(crate) fn new(
+        entries: Vec<(NodeIndex, TensorStorage)>,
+        names: HashMap<String, NodeIndex>,
+    ) -> Self {
+        Self { entries, names }
+    }
+
+    /// The result for a target, looked up by name or by tensor.
+    pub fn get(&self, key: impl Into<TensorKey>) -> Option<&TensorStorage> {
+        let node_id = match key.into() {
+            TensorKey::Name(name) => *self.names.get(&name)?,
+            TensorKey::Node(node_id) => node_id,
+        };
+
+        self.entries
+            .iter()
+            .find(|(target, _)| *target == node_id)
+            .map(|(_, storage)| storage)
+    }
+
+    /// The results in target order.
+    pub fn iter(&self) -> impl Iterator<Item = &TensorStorage> {
+        self.entries.iter().map(|(_, storage)| storage)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The results in target order.
+    pub fn into_vec(self) -> Vec<TensorStorage> {
+        self.entries
+            .into_iter()
+            .map(|(_, storage)| storage)
+            .collect()
+    }
+}
+
+impl Index<&GraphTensor> for Outputs {
+    type Output = TensorStorage;
+
+    /// Panics when `tensor` is not a target.
+    fn index(&self, tensor: &GraphTensor) -> &TensorStorage {
+        self.get(tensor).expect("Tensor is not a target")
+    }
+}
+
+impl Index<&str> for Outputs {
+    type Output = TensorStorage;
+
+    /// Panics when no target has the name.
+    fn index(&self, name: &str) -> &TensorStorage {
+        self.get(name).expect("No target has this name")
+    }
+}
+
+impl Index<&NodeIndex> for Outputs {
+    type Output = TensorStorage;
+
+    /// Panics when the node is not a target.
+    fn index(&self, node_id: &NodeIndex) -> &TensorStorage {
+        self.get(*node_id).expect("Node is not a target")
+    }
+}
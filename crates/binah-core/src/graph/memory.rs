@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::{Direction, graph::NodeIndex, visit::EdgeRef};
+
+use crate::{
+    op::Operation,
+    tensor::{shape::Shape, storage::DType},
+};
+
+use super::inner::{OpGraph, operands};
+
+/// When each tensor of an execution plan is read for the last time, so
+/// that it can be released right after, which released buffers hold later
+/// results instead, and how many bytes the tensors held take at most.
+///
+/// An elementwise result overwrites an operand that is read for the last
+/// time by it and has its shape and dtype. Kernels that take their output
+/// from an [`Arena`](crate::cpu::Arena), such as fused kernels, matmuls and
+/// reductions, are otherwise handed the smallest buffer of their dtype
+/// released before them that fits. Elementwise kernels that cannot
+/// overwrite an operand, and the few kernels that always allocate, count
+/// in full.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryPlan {
+    /// For each step of the plan, the nodes it reads for the last time, or
+    /// computes when nothing reads them at all.
+    releases: Vec<Vec<NodeIndex>>,
+    /// The node whose result is written to the buffer of each node, once
+    /// the buffer is no longer read.
+    donations: HashMap<NodeIndex, NodeIndex>,
+    /// Nodes whose result is written over one of their operands.
+    in_place: HashSet<NodeIndex>,
+    peak_bytes: Option<usize>,
+}
+
+impl MemoryPlan {
+    /// Plans the tensors of `plan`, which must be topologically sorted.
+    /// `outputs` are held until the end, and constants and variables for
+    /// good. `value_type` gives the shape and dtype of a node's value.
+    pub(crate) fn new(
+        graph: &OpGraph,
+        plan: &[NodeIndex],
+        outputs: &[NodeIndex],
+        value_type: impl Fn(NodeIndex) -> Option<(Shape, DType)>,
+    ) -> Self {
+        let position: HashMap<NodeIndex, usize> = plan
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (node, i))
+            .collect();
+
+        // The step after which each node is no longer read, if ever
+        let mut last_read = HashMap::new();
+        let mut releases = vec![Vec::new(); plan.len()];
+        for (step, &node_idx) in plan.iter().enumerate() {
+            let held = outputs.contains(&node_idx)
+                || matches!(
+                    graph[node_idx],
+                    Operation::Constant | Operation::Variable
+                );
+            let last = graph
+                .edges_directed(node_idx, Direction::Outgoing)
+                .filter_map(|edge| position.get(&edge.target()).copied())
+                .max()
+                .unwrap_or(step);
+            if !held {
+                releases[last].push(node_idx);
+            }
+            last_read.insert(node_idx, (!held).then_some(last));
+        }
+
+        // The node owning the buffer each node reads through, and the step
+        // after which each buffer is free
+        let mut owner = HashMap::new();
+        let mut freed: HashMap<NodeIndex, Option<usize>> = HashMap::new();
+        for &node_idx in plan {
+            let buffer = match graph[node_idx].is_view() {
+                true => owner[&operands(graph, node_idx)[0]],
+                false => node_idx,
+            };
+            owner.insert(node_idx, buffer);
+            let free = freed.entry(buffer).or_insert(Some(0));
+            *free = free.zip(last_read[&node_idx]).map(|(a, b)| a.max(b));
+        }
+
+        let mut donations = HashMap::new();
+        let mut in_place = HashSet::new();
+        for (step, &node_idx) in plan.iter().enumerate() {
+            let op = &graph[node_idx];
+            let writes_over =
+                matches!(op, Operation::Fused { .. }) || op.is_elementwise();
+            if !writes_over && !op.takes_buffer() {
+                continue;
+            }
+            let Some((shape, dtype)) = value_type(node_idx) else {
+                continue;
+            };
+
+            // An operand read twice is shared by both reads
+            let inputs = operands(graph, node_idx);
+            let overwritten = inputs.iter().copied().find(|&operand| {
+                writes_over
+                    && owner[&operand] == operand
+                    && freed[&operand] == Some(step)
+                    && inputs.iter().filter(|&&input| input == operand).count()
+                        == 1
+                    && value_type(operand)
+                        .is_some_and(|value| value == (shape.clone(), dtype))
+            });
+            if let Some(operand) = overwritten {
+                donations.insert(operand, node_idx);
+                in_place.insert(node_idx);
+                continue;
+            }
+            if !op.takes_buffer() {
+                continue;
+            }
+            let donor = plan[..step]
+                .iter()
+                .filter(|&&buffer| {
+                    freed
+                        .get(&buffer)
+                        .is_some_and(|free| free.is_some_and(|f| f < step))
+                        && !donations.contains_key(&buffer)
+                })
+                .filter_map(|&buffer| {
+                    let (donor_shape, donor_dtype) = value_type(buffer)?;
+                    let fits = donor_dtype == dtype
+                        && match (
+                            donor_shape.num_elements(),
+                            shape.num_elements(),
+                        ) {
+                            (Some(donor_len), Some(len)) => donor_len >= len,
+                            _ => donor_shape == shape,
+                        };
+
+                    fits.then_some((donor_shape.num_elements(), buffer))
+                })
+                .min();
+            if let Some((_, buffer)) = donor {
+                donations.insert(buffer, node_idx);
+            }
+        }
+
+        let peak_bytes =
+            peak_bytes(graph, plan, &owner, &freed, &donations, |node_idx| {
+                let (shape, dtype) = value_type(node_idx)?;
+
+                Some(shape.num_elements()? * dtype.size_of())
+            });
+
+        Self {
+            releases,
+            donations,
+            in_place,
+            peak_bytes,
+        }
+    }
+
+    /// The nodes whose tensors can be dropped once step `step` has run.
+    pub(crate) fn releases(&self, step: usize) -> &[NodeIndex] {
+        &self.releases[step]
+    }
+
+    /// The node that writes its result to the buffer of `node_idx` after
+    /// the buffer is released, if any.
+    pub(crate) fn donation(&self, node_idx: NodeIndex) -> Option<NodeIndex> {
+        self.donations.get(&node_idx).copied()
+    }
+
+    /// Whether `node_idx` writes its result over one of its operands.
+    pub(crate) fn in_place(&self, node_idx: NodeIndex) -> bool {
+        self.in_place.contains(&node_idx)
+    }
+
+    pub(crate) fn peak_bytes(&self) -> Option<usize> {
+        self.peak_bytes
+    }
+}
+
+/// The most bytes that buffers computed by `plan` take at once. A buffer
+/// is freed after the step in `freed`, unless it is donated, in which case
+/// its bytes pass on to the node it is donated to. Inputs, constants and
+/// variables are not computed and do not count.
+fn peak_bytes(
+    graph: &OpGraph,
+    plan: &[NodeIndex],
+    owner: &HashMap<NodeIndex, NodeIndex>,
+    freed: &HashMap<NodeIndex, Option<usize>>,
+    donations: &HashMap<NodeIndex, NodeIndex>,
+    byte_size: impl Fn(NodeIndex) -> Option<usize>,
+) -> Option<usize> {
+    let donors: HashMap<NodeIndex, NodeIndex> = donations
+        .iter()
+        .map(|(&donor, &taker)| (taker, donor))
+        .collect();
+
+    let mut counted = HashMap::new();
+    let mut live = 0;
+    let mut peak = 0;
+    let mut frees = vec![0; plan.len()];
+    for (step, &node_idx) in plan.iter().enumerate() {
+        let computed = owner[&node_idx] == node_idx
+            && !matches!(
+                graph[node_idx],
+                Operation::Constant
+                    | Operation::Variable
+                    | Operation::Placeholder { .. }
+            );
+        if computed {
+            let bytes = byte_size(node_idx)?;
+            let bytes = match donors.get(&node_idx) {
+                Some(donor) => counted.get(donor).copied().unwrap_or(0),
+                None => {
+                    live += bytes;
+                    bytes
+                }
+            };
+            counted.insert(node_idx, bytes);
+            if let Some(free) = freed[&node_idx]
+                && !donations.contains_key(&node_idx)
+            {
+                frees[free] += bytes;
+            }
+        }
+        peak = peak.max(live);
+        live -= frees[step];
+    }
+
+    Some(peak)
+}
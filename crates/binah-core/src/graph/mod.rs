@@ -1,22 +1,53 @@
 use std::{cell::RefCell, rc::Rc};
 
 use inner::GraphInner;
-use num_traits::{One, Zero};
 
 use crate::{
     op::Operation,
-    tensor::{Tensor, shape::Shape, storage::IntoStorage},
+    tensor::{
+        Tensor,
+        shape::Shape,
+        storage::{DType, IntoStorage, TensorStorage},
+    },
 };
+mod autodiff;
+mod cse;
+mod error;
 pub mod execute;
+mod fuse;
 pub(crate) mod inner;
+mod io;
+mod memory;
 pub mod tensor;
 
-pub use execute::{ExecutionError, GraphExecutable};
+pub use error::GraphError;
+pub use execute::{CompileOptions, ExecutionError, GraphExecutable};
+pub use io::{Outputs, TensorKey};
 pub use tensor::GraphTensor;
 
+/// How a graph treats operands of different dtypes meeting in one
+/// operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypePromotion {
+    /// Casts the operands to the type given by [`DType::promote`], and
+    /// integer operands of floating-point operations such as `exp` to a
+    /// float type.
+    #[default]
+    Numpy,
+    /// Rejects every operation that would need an implicit cast; use
+    /// [`GraphTensor::cast`] instead.
+    Strict,
+}
+
 #[derive(Clone, Debug)]
 pub struct Graph {
-    inner: Rc<RefCell<GraphInner>>,
+    pub(crate) inner: Rc<RefCell<GraphInner>>,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Graph {
@@ -34,48 +65,123 @@ impl Graph {
 
     pub fn constant<T>(&mut self, data: Vec<T>, shape: Shape) -> GraphTensor
     where
-        T: IntoStorage + Zero + One,
+        T: IntoStorage,
     {
-        let node_id = self.inner.borrow_mut().add_op(Operation::Constant);
-        let tensor = Tensor::from_data(data, shape.clone());
-
-        self.inner
-            .borrow_mut()
-            .add_storage(node_id, tensor.into_storage());
-
-        GraphTensor::new(self.inner.clone(), node_id, shape)
+        self.add_data(Operation::Constant, data, shape)
     }
 
     pub fn variable<T>(&mut self, data: Vec<T>, shape: Shape) -> GraphTensor
     where
-        T: IntoStorage + Zero + One,
+        T: IntoStorage,
     {
-        let node_id = self.inner.borrow_mut().add_op(Operation::Variable);
-        let tensor = Tensor::from_data(data, shape.clone());
+        self.add_data(Operation::Variable, data, shape)
+    }
 
-        self.inner
-            .borrow_mut()
-            .add_storage(node_id, tensor.into_storage());
+    /// A variable of `shape` and `dtype` that starts out as zeros. Panics if
+    /// `shape` is symbolic.
+    pub fn zeros_variable(
+        &mut self,
+        shape: Shape,
+        dtype: DType,
+    ) -> GraphTensor {
+        let dims = shape
+            .fixed_dims()
+            .expect("A tensor holding data must have a fixed shape");
+
+        self.add_storage(
+            Operation::Variable,
+            TensorStorage::zeros(dtype, dims),
+            shape,
+        )
+    }
+
+    pub fn type_promotion(&self) -> TypePromotion {
+        self.inner.borrow().type_promotion()
+    }
 
-        GraphTensor::new(self.inner.clone(), node_id, shape)
+    /// Sets how operations added from now on combine dtypes. Defaults to
+    /// [`TypePromotion::Numpy`].
+    pub fn set_type_promotion(&mut self, promotion: TypePromotion) {
+        self.inner.borrow_mut().set_type_promotion(promotion);
     }
 
-    pub fn placeholder(&mut self, shape: Shape) -> GraphTensor {
-        let node_id = self.inner.borrow_mut().add_op(Operation::Placeholder);
+    /// An input of `shape` and `dtype` fed at execution time under `name`,
+    /// which must be unique within the graph.
+    pub fn placeholder(
+        &mut self,
+        name: &str,
+        shape: Shape,
+        dtype: DType,
+    ) -> Result<GraphTensor, GraphError> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(&node) = inner.names().get(name) {
+            return Err(GraphError::DuplicateName {
+                name: name.to_string(),
+                node,
+            });
+        }
+
+        let op = Operation::Placeholder {
+            shape: shape.clone(),
+            dtype,
+        };
+        let node_id = inner.add_op(op, shape.clone(), dtype);
+        inner.add_name(node_id, name)?;
 
-        GraphTensor::new(self.inner.clone(), node_id, shape)
+        Ok(GraphTensor::new(self.inner.clone(), node_id, shape, dtype))
     }
 
     pub fn compile(
         &mut self,
         target_tensors: &[&GraphTensor],
+    ) -> Result<GraphExecutable, ExecutionError> {
+        self.compile_with(target_tensors, CompileOptions::default())
+    }
+
+    /// Like [`Graph::compile`], with the optimizations in `options`.
+    pub fn compile_with(
+        &mut self,
+        target_tensors: &[&GraphTensor],
+        options: CompileOptions,
     ) -> Result<GraphExecutable, ExecutionError> {
         let graph_inner = self.inner.borrow();
 
         GraphExecutable::new(
             graph_inner.graph(),
             graph_inner.tensor_storage().clone(),
+            graph_inner.names(),
             target_tensors,
+            options,
+            |node_id| graph_inner.value_type(node_id),
         )
     }
+
+    fn add_data<T>(
+        &mut self,
+        op: Operation,
+        data: Vec<T>,
+        shape: Shape,
+    ) -> GraphTensor
+    where
+        T: IntoStorage,
+    {
+        let storage = Tensor::from_data(data, shape.clone()).into_storage();
+
+        self.add_storage(op, storage, shape)
+    }
+
+    fn add_storage(
+        &mut self,
+        op: Operation,
+        storage: TensorStorage,
+        shape: Shape,
+    ) -> GraphTensor {
+        let dtype = storage.dtype();
+
+        let mut inner = self.inner.borrow_mut();
+        let node_id = inner.add_op(op, shape.clone(), dtype);
+        inner.add_storage(node_id, storage);
+
+        GraphTensor::new(self.inner.clone(), node_id, shape, dtype)
+    }
 }
@@ -5,15 +5,22 @@ use std::{
 
 use petgraph::graph::NodeIndex;
 
-use crate::tensor::shape::Shape;
+use crate::tensor::{
+    shape::{Shape, ShapeError},
+    storage::DType,
+};
 
-use super::GraphInner;
+use super::{Graph, GraphError, GraphInner};
 
-#[derive(Debug)]
+/// A handle to a node of a [`Graph`](super::Graph). Handles are cheap to
+/// clone, and the arithmetic operators also take them by reference, so one
+/// tensor can feed any number of operations.
+#[derive(Clone, Debug)]
 pub struct GraphTensor {
     graph: Weak<RefCell<GraphInner>>,
     node_id: NodeIndex,
     shape: Shape,
+    dtype: DType,
 }
 
 impl GraphTensor {
@@ -21,16 +28,29 @@ impl GraphTensor {
         graph: Rc<RefCell<GraphInner>>,
         node_id: NodeIndex,
         shape: Shape,
+        dtype: DType,
     ) -> Self {
         Self {
             graph: Rc::downgrade(&graph),
             node_id,
             shape,
+            dtype,
         }
     }
 
-    pub fn graph(&self) -> Rc<RefCell<GraphInner>> {
-        self.graph.upgrade().expect("Graph dropped")
+    /// The graph this tensor belongs to, or an error once it was dropped.
+    pub(crate) fn graph(&self) -> Result<Rc<RefCell<GraphInner>>, GraphError> {
+        self.graph
+            .upgrade()
+            .ok_or(GraphError::GraphDropped { node: self.node_id })
+    }
+
+    /// The graph this tensor was built in, for builders that add nodes
+    /// through [`Graph`] methods.
+    pub(crate) fn owner(&self) -> Result<Graph, GraphError> {
+        Ok(Graph {
+            inner: self.graph()?,
+        })
     }
 
     pub fn node_id(&self) -> NodeIndex {
@@ -40,4 +60,53 @@ impl GraphTensor {
     pub fn shape(&self) -> Shape {
         self.shape.clone()
     }
+
+    pub fn dtype(&self) -> DType {
+        self.dtype
+    }
+
+    /// Names this tensor so that it can be fed or read by name when the
+    /// graph is executed. Names are unique within a graph.
+    pub fn set_name(&self, name: &str) -> Result<(), GraphError> {
+        self.graph()?.borrow_mut().add_name(self.node_id, name)
+    }
+
+    /// The name given by [`GraphTensor::set_name`] or
+    /// [`Graph::placeholder`](super::Graph::placeholder), if any.
+    pub fn name(&self) -> Option<String> {
+        let graph_rc = self.graph().ok()?;
+        let inner = graph_rc.borrow();
+
+        inner
+            .names()
+            .iter()
+            .find(|&(_, &node_id)| node_id == self.node_id)
+            .map(|(name, _)| name.clone())
+    }
+
+    /// The error for `op` rejecting this tensor's shape.
+    pub(crate) fn shape_error(
+        &self,
+        op: &'static str,
+        source: ShapeError,
+    ) -> GraphError {
+        GraphError::InvalidShape {
+            op,
+            node: self.node_id,
+            source,
+        }
+    }
+
+    /// The size of `axis`, which `op` needs to be fixed.
+    pub(crate) fn fixed_dim(
+        &self,
+        axis: usize,
+        op: &'static str,
+    ) -> Result<usize, GraphError> {
+        let dim = self.shape.dims()[axis];
+
+        dim.fixed().ok_or_else(|| {
+            self.shape_error(op, ShapeError::SymbolicDim(axis, dim))
+        })
+    }
 }
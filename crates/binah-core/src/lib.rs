@@ -1,7 +1,15 @@
 pub mod cpu;
 pub mod graph;
+pub mod nn;
 pub mod op;
+pub mod optim;
 pub mod tensor;
 
-pub use graph::{Graph, GraphExecutable, GraphTensor, ExecutionError};
-pub use tensor::shape::Shape;
\ No newline at end of file
+pub use graph::{
+    CompileOptions, ExecutionError, Graph, GraphError, GraphExecutable,
+    GraphTensor, Outputs, TensorKey, TypePromotion,
+};
+pub use tensor::{
+    shape::{Dim, Shape, Slice},
+    storage::{DType, TensorStorage},
+};
@@ -0,0 +1,123 @@
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    tensor::shape::Dim,
+};
+
+use super::{Init, Linear, Module, Rng};
+
+/// Scaled dot-product attention over `num_heads` heads, for inputs of shape
+/// `[..., sequence, embed_dim]`. The forward pass is self-attention.
+#[derive(Clone, Debug)]
+pub struct MultiHeadAttention {
+    pub query: Linear,
+    pub key: Linear,
+    pub value: Linear,
+    pub output: Linear,
+    num_heads: usize,
+}
+
+impl MultiHeadAttention {
+    /// Projections with Xavier-uniform weights and zero biases. Panics
+    /// unless `num_heads` divides `embed_dim`.
+    pub fn new(
+        graph: &mut Graph,
+        rng: &mut Rng,
+        embed_dim: usize,
+        num_heads: usize,
+    ) -> Self {
+        assert!(
+            num_heads > 0 && embed_dim.is_multiple_of(num_heads),
+            "{num_heads} heads do not divide an embedding of {embed_dim}"
+        );
+        let mut projection = || {
+            Linear::with_init(
+                graph,
+                rng,
+                embed_dim,
+                embed_dim,
+                Init::XavierUniform,
+                true,
+            )
+        };
+
+        Self {
+            query: projection(),
+            key: projection(),
+            value: projection(),
+            output: projection(),
+            num_heads,
+        }
+    }
+
+    /// Attends from each position of `query` to the positions of `key` and
+    /// `value`, which share a sequence length. `mask`, a bool tensor that
+    /// broadcasts to `[..., num_heads, query_len, key_len]`, is true where
+    /// attention is not allowed.
+    pub fn attend(
+        &self,
+        query: &GraphTensor,
+        key: &GraphTensor,
+        value: &GraphTensor,
+        mask: Option<&GraphTensor>,
+    ) -> GraphTensor {
+        self.try_attend(query, key, value, mask)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_attend(
+        &self,
+        query: &GraphTensor,
+        key: &GraphTensor,
+        value: &GraphTensor,
+        mask: Option<&GraphTensor>,
+    ) -> Result<GraphTensor, GraphError> {
+        let query = self.split_heads(&self.query.forward(query))?;
+        let key = self.split_heads(&self.key.forward(key))?;
+        let value = self.split_heads(&self.value.forward(value))?;
+
+        let head_dim =
+            query.fixed_dim(query.shape().dims().len() - 1, "attention")?;
+        let scores = query.try_matmul(&key.transpose(-1, -2)?)?
+            * (1.0 / (head_dim as f64).sqrt());
+        let scores = match mask {
+            Some(mask) => scores.masked_fill(mask, f64::NEG_INFINITY)?,
+            None => scores,
+        };
+        let weights = scores.try_softmax(-1)?;
+
+        let context = weights.try_matmul(&value)?.transpose(-3, -2)?;
+        let mut dims = context.shape().dims().to_vec();
+        dims.truncate(dims.len() - 2);
+        dims.push(Dim::Fixed(self.num_heads * head_dim));
+
+        Ok(self.output.forward(&context.reshape(dims)?))
+    }
+
+    /// `[..., sequence, embed_dim]` to `[..., num_heads, sequence,
+    /// head_dim]`.
+    fn split_heads(
+        &self,
+        input: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let mut dims = input.shape().dims().to_vec();
+        let rank = dims.len();
+        let embed_dim = input.fixed_dim(rank - 1, "attention")?;
+        dims[rank - 1] = Dim::Fixed(self.num_heads);
+        dims.push(Dim::Fixed(embed_dim / self.num_heads));
+
+        input.reshape(dims)?.transpose(-3, -2)
+    }
+}
+
+impl Module for MultiHeadAttention {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        self.attend(input, input, input, None)
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        [&self.query, &self.key, &self.value, &self.output]
+            .into_iter()
+            .flat_map(Linear::parameters)
+            .collect()
+    }
+}
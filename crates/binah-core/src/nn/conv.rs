@@ -0,0 +1,190 @@
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    tensor::shape::{Dim, ShapeError, Slice},
+};
+
+use super::{Init, Module, Rng};
+
+/// 2-D convolution over `[batch, channels, height, width]` inputs, with a
+/// `[out_channels, in_channels, kernel_height, kernel_width]` weight. The
+/// kernel reads zero padding around the input and moves by `stride`.
+#[derive(Clone, Debug)]
+pub struct Conv2d {
+    pub weight: GraphTensor,
+    pub bias: Option<GraphTensor>,
+    in_channels: usize,
+    out_channels: usize,
+    kernel_size: (usize, usize),
+    stride: (usize, usize),
+    padding: (usize, usize),
+}
+
+impl Conv2d {
+    /// A convolution with Kaiming-uniform weights, a zero bias, stride 1
+    /// and no padding.
+    pub fn new(
+        graph: &mut Graph,
+        rng: &mut Rng,
+        in_channels: usize,
+        out_channels: usize,
+        kernel_size: (usize, usize),
+    ) -> Self {
+        let (height, width) = kernel_size;
+        let shape = [out_channels, in_channels, height, width];
+        let area = height * width;
+        let weight = Init::KaimingUniform.variable(
+            graph,
+            rng,
+            &shape,
+            in_channels * area,
+            out_channels * area,
+        );
+        let bias = Init::Zeros.variable(graph, rng, &[out_channels], 0, 0);
+
+        Self {
+            weight,
+            bias: Some(bias),
+            in_channels,
+            out_channels,
+            kernel_size,
+            stride: (1, 1),
+            padding: (0, 0),
+        }
+    }
+
+    /// The step between kernel positions along height and width. Both must
+    /// be positive; a zero stride fails when the layer is applied.
+    pub fn with_stride(mut self, stride: (usize, usize)) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// The zeros added to each side along height and width.
+    pub fn with_padding(mut self, padding: (usize, usize)) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Stacks every kernel position's view of the input into columns and
+    /// multiplies them by the flattened weight.
+    fn try_forward(
+        &self,
+        input: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let rank = input.shape().dims().len();
+        if rank != 4 {
+            let source = ShapeError::RankMismatch(4, rank);
+            return Err(input.shape_error("conv2d", source));
+        }
+        let channels = input.fixed_dim(1, "conv2d")?;
+        if channels != self.in_channels {
+            return Err(GraphError::IncompatibleShapes {
+                op: "conv2d",
+                node: input.node_id(),
+                lhs: self.weight.shape(),
+                rhs: input.shape(),
+            });
+        }
+
+        let mut graph = input.owner()?;
+        let padded = pad(&mut graph, input, 2, self.padding.0)?;
+        let padded = pad(&mut graph, &padded, 3, self.padding.1)?;
+
+        let (kernel_height, kernel_width) = self.kernel_size;
+        let (stride_height, stride_width) = self.stride;
+        if stride_height == 0 || stride_width == 0 {
+            return Err(input.shape_error(
+                "conv2d",
+                ShapeError::InvalidStride(stride_height, stride_width),
+            ));
+        }
+        let out_size = |axis, kernel, stride| {
+            let size = padded.fixed_dim(axis, "conv2d")?;
+            match size.checked_sub(kernel) {
+                Some(room) => Ok(room / stride + 1),
+                None => Err(GraphError::IncompatibleShapes {
+                    op: "conv2d",
+                    node: input.node_id(),
+                    lhs: self.weight.shape(),
+                    rhs: input.shape(),
+                }),
+            }
+        };
+        let out_height = out_size(2, kernel_height, stride_height)?;
+        let out_width = out_size(3, kernel_width, stride_width)?;
+
+        let window = |offset: usize, stride: usize, len: usize| {
+            let stop = offset + stride * (len - 1) + 1;
+            Slice::new(
+                Some(offset as isize),
+                Some(stop as isize),
+                stride as isize,
+            )
+        };
+        let mut patches = Vec::with_capacity(kernel_height * kernel_width);
+        for row in 0..kernel_height {
+            for col in 0..kernel_width {
+                patches.push(padded.slice(&[
+                    Slice::full(),
+                    Slice::full(),
+                    window(row, stride_height, out_height),
+                    window(col, stride_width, out_width),
+                ])?);
+            }
+        }
+        let patches: Vec<&GraphTensor> = patches.iter().collect();
+
+        let batch = input.shape().dims()[0];
+        let patch_len = channels * kernel_height * kernel_width;
+        let columns = graph.stack(&patches, 2)?.reshape(vec![
+            batch,
+            Dim::Fixed(patch_len),
+            Dim::Fixed(out_height * out_width),
+        ])?;
+        let weight = self.weight.reshape([self.out_channels, patch_len])?;
+        let output = weight.try_matmul(&columns)?.reshape(vec![
+            batch,
+            Dim::Fixed(self.out_channels),
+            Dim::Fixed(out_height),
+            Dim::Fixed(out_width),
+        ])?;
+
+        match &self.bias {
+            Some(bias) => {
+                output.try_add(&bias.reshape([self.out_channels, 1, 1])?)
+            }
+            None => Ok(output),
+        }
+    }
+}
+
+impl Module for Conv2d {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        self.try_forward(input)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        std::iter::once(&self.weight).chain(&self.bias).collect()
+    }
+}
+
+/// `input` with `amount` zeros added on both sides of `axis`.
+fn pad(
+    graph: &mut Graph,
+    input: &GraphTensor,
+    axis: usize,
+    amount: usize,
+) -> Result<GraphTensor, GraphError> {
+    if amount == 0 {
+        return Ok(input.clone());
+    }
+
+    let mut edge = vec![Slice::full(); axis];
+    edge.push(Slice::from(0..1));
+    let mut dims = input.shape().dims().to_vec();
+    dims[axis] = Dim::Fixed(amount);
+    let zeros = input.slice(&edge)?.zeros_like().expand(dims)?;
+
+    graph.concat(&[&zeros, input, &zeros], axis as isize)
+}
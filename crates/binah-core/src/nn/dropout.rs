@@ -0,0 +1,67 @@
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    tensor::{shape::Shape, storage::DType},
+};
+
+use super::{Module, Rng};
+
+/// Zeroes each element with probability `p` in training mode and scales
+/// the rest by `1 / (1 - p)`; the identity in evaluation mode. A counter
+/// variable advanced by every execution draws a new mask each time.
+#[derive(Clone, Debug)]
+pub struct Dropout {
+    p: f64,
+    seed: u64,
+    counter: GraphTensor,
+    training: bool,
+}
+
+impl Dropout {
+    /// A layer in training mode with a seed drawn from `rng`. Panics unless
+    /// `0 <= p < 1`.
+    pub fn new(graph: &mut Graph, rng: &mut Rng, p: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&p),
+            "Dropout probability {p} not in [0, 1)"
+        );
+
+        Self {
+            p,
+            seed: rng.next_u64(),
+            counter: graph.zeros_variable(Shape::new([]), DType::U64),
+            training: true,
+        }
+    }
+
+    /// Switches between dropping and passing through for the forward
+    /// passes added from now on.
+    pub fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    fn try_forward(
+        &self,
+        input: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        if !self.training || self.p == 0.0 {
+            return Ok(input.clone());
+        }
+
+        let key = self.counter.assign_add(&self.counter.ones_like())?;
+        let samples = input.uniform_like(self.seed, &key)?;
+        let keep = samples.greater_equal(&samples.full_like(self.p));
+
+        keep.select(&(input / (1.0 - self.p)), &input.zeros_like())
+    }
+}
+
+impl Module for Dropout {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        self.try_forward(input)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,63 @@
+use crate::graph::{Graph, GraphError, tensor::GraphTensor};
+
+use super::{Init, Module, Rng};
+
+/// A lookup table mapping integer indices to rows of a `[num_embeddings,
+/// embedding_dim]` weight. Indices of any fixed shape give an output of that
+/// shape with the embedding dimension appended.
+#[derive(Clone, Debug)]
+pub struct Embedding {
+    pub weight: GraphTensor,
+    embedding_dim: usize,
+}
+
+impl Embedding {
+    /// A table initialized from the standard normal distribution.
+    pub fn new(
+        graph: &mut Graph,
+        rng: &mut Rng,
+        num_embeddings: usize,
+        embedding_dim: usize,
+    ) -> Self {
+        let init = Init::Normal {
+            mean: 0.0,
+            std: 1.0,
+        };
+        let shape = [num_embeddings, embedding_dim];
+        let weight = init.variable(graph, rng, &shape, 0, 0);
+
+        Self {
+            weight,
+            embedding_dim,
+        }
+    }
+
+    fn try_forward(
+        &self,
+        indices: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let mut dims = indices.shape().dims().to_vec();
+        if dims.len() == 1 {
+            return self.weight.index_select(indices, 0);
+        }
+
+        let count = (0..dims.len())
+            .map(|axis| indices.fixed_dim(axis, "embedding"))
+            .product::<Result<usize, _>>()?;
+        let rows = self.weight.index_select(&indices.reshape([count])?, 0)?;
+        dims.push(self.embedding_dim.into());
+
+        rows.reshape(dims)
+    }
+}
+
+impl Module for Embedding {
+    fn forward(&self, indices: &GraphTensor) -> GraphTensor {
+        self.try_forward(indices)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        vec![&self.weight]
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    cpu::splitmix64,
+    graph::{Graph, tensor::GraphTensor},
+    tensor::shape::Shape,
+};
+
+/// A small seeded generator for reproducible parameter initialization,
+/// based on SplitMix64. Not suitable for cryptography.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let value = splitmix64(self.state);
+        self.state = self.state.wrapping_add(1);
+        value
+    }
+
+    /// A sample from `[0, 1)`.
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A sample from the standard normal distribution, by the Box-Muller
+    /// transform.
+    pub fn normal(&mut self) -> f64 {
+        let radius = (-2.0 * (1.0 - self.uniform()).ln()).sqrt();
+        let angle = 2.0 * std::f64::consts::PI * self.uniform();
+        radius * angle.cos()
+    }
+}
+
+/// How a new parameter is filled. `fan_in` and `fan_out` count the inputs
+/// feeding each output and the outputs fed by each input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Init {
+    Zeros,
+    Ones,
+    Constant(f64),
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Normal {
+        mean: f64,
+        std: f64,
+    },
+    /// Glorot: uniform in `±sqrt(6 / (fan_in + fan_out))`.
+    XavierUniform,
+    /// Glorot: normal with deviation `sqrt(2 / (fan_in + fan_out))`.
+    XavierNormal,
+    /// He, for ReLU networks: uniform in `±sqrt(6 / fan_in)`.
+    KaimingUniform,
+    /// He, for ReLU networks: normal with deviation `sqrt(2 / fan_in)`.
+    KaimingNormal,
+}
+
+impl Init {
+    /// `len` values drawn from `rng`.
+    pub fn sample(
+        self,
+        rng: &mut Rng,
+        len: usize,
+        fan_in: usize,
+        fan_out: usize,
+    ) -> Vec<f32> {
+        let (fan_in, fan_sum) = (fan_in as f64, (fan_in + fan_out) as f64);
+        let uniform = |rng: &mut Rng, bound: f64| {
+            (0..len)
+                .map(|_| ((2.0 * rng.uniform() - 1.0) * bound) as f32)
+                .collect()
+        };
+        let normal = |rng: &mut Rng, mean: f64, std: f64| {
+            (0..len)
+                .map(|_| (mean + std * rng.normal()) as f32)
+                .collect()
+        };
+
+        match self {
+            Init::Zeros => vec![0.0; len],
+            Init::Ones => vec![1.0; len],
+            Init::Constant(value) => vec![value as f32; len],
+            Init::Uniform { low, high } => (0..len)
+                .map(|_| (low + (high - low) * rng.uniform()) as f32)
+                .collect(),
+            Init::Normal { mean, std } => normal(rng, mean, std),
+            Init::XavierUniform => uniform(rng, (6.0 / fan_sum).sqrt()),
+            Init::XavierNormal => normal(rng, 0.0, (2.0 / fan_sum).sqrt()),
+            Init::KaimingUniform => uniform(rng, (6.0 / fan_in).sqrt()),
+            Init::KaimingNormal => normal(rng, 0.0, (2.0 / fan_in).sqrt()),
+        }
+    }
+
+    /// A new f32 variable of `shape` filled from `rng`.
+    pub fn variable(
+        self,
+        graph: &mut Graph,
+        rng: &mut Rng,
+        shape: &[usize],
+        fan_in: usize,
+        fan_out: usize,
+    ) -> GraphTensor {
+        let len = shape.iter().product();
+        let data = self.sample(rng, len, fan_in, fan_out);
+
+        graph.variable(data, Shape::from(shape))
+    }
+}
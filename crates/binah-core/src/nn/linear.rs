@@ -0,0 +1,64 @@
+use crate::graph::{Graph, tensor::GraphTensor};
+
+use super::{Init, Module, Rng};
+
+/// `input @ weight + bias` over the last axis of the input. Unlike
+/// PyTorch's `Linear`, the weight is stored as `[in_features,
+/// out_features]`.
+#[derive(Clone, Debug)]
+pub struct Linear {
+    pub weight: GraphTensor,
+    pub bias: Option<GraphTensor>,
+}
+
+impl Linear {
+    /// A layer with Kaiming-uniform weights and a zero bias.
+    pub fn new(
+        graph: &mut Graph,
+        rng: &mut Rng,
+        in_features: usize,
+        out_features: usize,
+    ) -> Self {
+        Self::with_init(
+            graph,
+            rng,
+            in_features,
+            out_features,
+            Init::KaimingUniform,
+            true,
+        )
+    }
+
+    /// A layer with weights from `init` and a zero bias if `bias` is set.
+    pub fn with_init(
+        graph: &mut Graph,
+        rng: &mut Rng,
+        in_features: usize,
+        out_features: usize,
+        init: Init,
+        bias: bool,
+    ) -> Self {
+        let shape = [in_features, out_features];
+        let weight =
+            init.variable(graph, rng, &shape, in_features, out_features);
+        let bias = bias
+            .then(|| Init::Zeros.variable(graph, rng, &[out_features], 0, 0));
+
+        Self { weight, bias }
+    }
+}
+
+impl Module for Linear {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        let output = input.matmul(&self.weight);
+
+        match &self.bias {
+            Some(bias) => output + bias,
+            None => output,
+        }
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        std::iter::once(&self.weight).chain(&self.bias).collect()
+    }
+}
@@ -0,0 +1,29 @@
+use crate::graph::tensor::GraphTensor;
+
+mod attention;
+mod conv;
+mod dropout;
+mod embedding;
+mod init;
+mod linear;
+mod norm;
+
+pub use attention::MultiHeadAttention;
+pub use conv::Conv2d;
+pub use dropout::Dropout;
+pub use embedding::Embedding;
+pub use init::{Init, Rng};
+pub use linear::Linear;
+pub use norm::{BatchNorm, LayerNorm};
+
+/// A layer whose parameters are variables of the graph it was built in.
+pub trait Module {
+    /// Adds the layer applied to `input` to the graph. Panics on inputs of
+    /// a shape the layer cannot take.
+    fn forward(&self, input: &GraphTensor) -> GraphTensor;
+
+    /// The trainable variables, to be differentiated with
+    /// [`Graph::gradients`](crate::Graph::gradients) and updated by an
+    /// [`Optimizer`](crate::optim::Optimizer).
+    fn parameters(&self) -> Vec<&GraphTensor>;
+}
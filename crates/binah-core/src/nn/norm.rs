@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    tensor::{
+        shape::{Shape, ShapeError},
+        storage::DType,
+    },
+};
+
+use super::Module;
+
+/// Normalizes over the trailing `normalized_shape` axes of the input to
+/// zero mean and unit variance, then scales by `weight` and shifts by
+/// `bias`, both of that shape.
+#[derive(Clone, Debug)]
+pub struct LayerNorm {
+    pub weight: GraphTensor,
+    pub bias: GraphTensor,
+    normalized_shape: Vec<usize>,
+    eps: f64,
+}
+
+impl LayerNorm {
+    /// A layer starting as the identity on normalized inputs, with epsilon
+    /// `1e-5`.
+    pub fn new(graph: &mut Graph, normalized_shape: &[usize]) -> Self {
+        Self {
+            weight: ones(graph, normalized_shape),
+            bias: graph.zeros_variable(normalized_shape.into(), DType::F32),
+            normalized_shape: normalized_shape.to_vec(),
+            eps: 1e-5,
+        }
+    }
+
+    /// The term added to the variance for stability.
+    pub fn with_eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    fn try_forward(
+        &self,
+        input: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let dims = input.shape().dims().to_vec();
+        let count = self.normalized_shape.len();
+        let trailing =
+            dims.len().checked_sub(count).map(|start| &dims[start..]);
+        if trailing != Some(Shape::from(&self.normalized_shape).dims()) {
+            return Err(GraphError::IncompatibleShapes {
+                op: "layer_norm",
+                node: input.node_id(),
+                lhs: self.weight.shape(),
+                rhs: input.shape(),
+            });
+        }
+
+        let axes: Vec<isize> = (-(count as isize)..0).collect();
+        let normalized = normalize(input, &axes, self.eps);
+
+        Ok(normalized * &self.weight + &self.bias)
+    }
+}
+
+impl Module for LayerNorm {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        self.try_forward(input)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        vec![&self.weight, &self.bias]
+    }
+}
+
+/// Normalizes each channel, axis 1 of a `[batch, channels, ...]` input,
+/// over every other axis, then scales and shifts it by the per-channel
+/// `weight` and `bias`.
+///
+/// In training mode the statistics of the batch are used, and every
+/// forward pass also adds updates of the running statistics, which
+/// [`BatchNorm::updates`] returns. In evaluation mode the running
+/// statistics are used instead.
+#[derive(Clone, Debug)]
+pub struct BatchNorm {
+    pub weight: GraphTensor,
+    pub bias: GraphTensor,
+    pub running_mean: GraphTensor,
+    pub running_var: GraphTensor,
+    num_features: usize,
+    momentum: f64,
+    eps: f64,
+    training: bool,
+    updates: RefCell<Vec<GraphTensor>>,
+}
+
+impl BatchNorm {
+    /// A layer in training mode with momentum `0.1` and epsilon `1e-5`.
+    pub fn new(graph: &mut Graph, num_features: usize) -> Self {
+        let shape = Shape::from([num_features]);
+
+        Self {
+            weight: ones(graph, &[num_features]),
+            bias: graph.zeros_variable(shape.clone(), DType::F32),
+            running_mean: graph.zeros_variable(shape, DType::F32),
+            running_var: ones(graph, &[num_features]),
+            num_features,
+            momentum: 0.1,
+            eps: 1e-5,
+            training: true,
+            updates: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The weight of each batch's statistics in the running statistics.
+    pub fn with_momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// The term added to the variance for stability.
+    pub fn with_eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Switches between batch statistics and running statistics for the
+    /// forward passes added from now on.
+    pub fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    /// The new running statistics of every training-mode forward pass so
+    /// far. Compile them along with the training step for the statistics
+    /// to be updated.
+    pub fn updates(&self) -> Vec<GraphTensor> {
+        self.updates.borrow().clone()
+    }
+
+    fn try_forward(
+        &self,
+        input: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let rank = input.shape().dims().len();
+        if rank < 2 {
+            let source = ShapeError::RankMismatch(2, rank);
+            return Err(input.shape_error("batch_norm", source));
+        }
+        if input.fixed_dim(1, "batch_norm")? != self.num_features {
+            return Err(GraphError::IncompatibleShapes {
+                op: "batch_norm",
+                node: input.node_id(),
+                lhs: self.weight.shape(),
+                rhs: input.shape(),
+            });
+        }
+
+        // Per-channel tensors broadcast against axis 1.
+        let mut channel_dims = vec![1; rank - 1];
+        channel_dims[0] = self.num_features;
+        let per_channel = |tensor: &GraphTensor| tensor.reshape(&channel_dims);
+        let axes: Vec<isize> =
+            (0..rank as isize).filter(|&axis| axis != 1).collect();
+
+        let normalized = if self.training {
+            let mean = input.mean(&axes, true);
+            let centered = input - &mean;
+            let var = (&centered * &centered).mean(&axes, true);
+            self.update_running(input, &axes, &mean, &var)?;
+
+            centered * (var + self.eps).rsqrt()
+        } else {
+            let mean = per_channel(&self.running_mean)?;
+            let var = per_channel(&self.running_var)?;
+
+            (input - &mean) * (var + self.eps).rsqrt()
+        };
+
+        Ok(normalized * per_channel(&self.weight)? + per_channel(&self.bias)?)
+    }
+
+    /// Moves the running statistics towards the batch's, using the unbiased
+    /// variance as PyTorch does.
+    fn update_running(
+        &self,
+        input: &GraphTensor,
+        axes: &[isize],
+        mean: &GraphTensor,
+        var: &GraphTensor,
+    ) -> Result<(), GraphError> {
+        let shape = [self.num_features];
+        let count = input.ones_like().sum(axes, false);
+        let unbiased = var.reshape(shape)? * &count / (&count - 1.0);
+
+        let keep = 1.0 - self.momentum;
+        let running_mean =
+            &self.running_mean * keep + mean.reshape(shape)? * self.momentum;
+        let running_var = &self.running_var * keep + unbiased * self.momentum;
+
+        let mut updates = self.updates.borrow_mut();
+        updates.push(self.running_mean.assign(&running_mean)?);
+        updates.push(self.running_var.assign(&running_var)?);
+
+        Ok(())
+    }
+}
+
+impl Module for BatchNorm {
+    fn forward(&self, input: &GraphTensor) -> GraphTensor {
+        self.try_forward(input)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn parameters(&self) -> Vec<&GraphTensor> {
+        vec![&self.weight, &self.bias]
+    }
+}
+
+/// `input` shifted and scaled to zero mean and unit variance over `axes`.
+fn normalize(input: &GraphTensor, axes: &[isize], eps: f64) -> GraphTensor {
+    let centered = input - &input.mean(axes, true);
+    let var = (&centered * &centered).mean(axes, true);
+
+    centered * (var + eps).rsqrt()
+}
+
+fn ones(graph: &mut Graph, dims: &[usize]) -> GraphTensor {
+    graph.variable(vec![1.0f32; dims.iter().product()], Shape::from(dims))
+}
@@ -1,82 +1,306 @@
-use crate::{graph::tensor::GraphTensor, op::Operation};
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::storage::DType,
+};
 use std::ops::{Add, Div, Mul, Sub};
 
-impl Add for GraphTensor {
-    type Output = GraphTensor;
+/// A plain number used as an operand of tensor arithmetic. It becomes a
+/// `FullLike` node of the tensor operand, so it takes on the tensor's shape
+/// and element type. A float scalar first converts an integer or bool
+/// tensor to floats as NumPy would, which strict graphs reject instead.
+pub trait Scalar: Copy {
+    /// The dtype of a tensor holding this type.
+    const DTYPE: DType;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        let graph_rc = self.graph();
-        
-        let result_shape = self.shape()
-            .broadcast_with(&rhs.shape())
-            .expect("Incompatible shapes for addition");
+    fn to_f64(self) -> f64;
+}
 
-        let node_id = graph_rc.borrow_mut().add_binary_op(
-            self.node_id(),
-            rhs.node_id(),
-            Operation::Add,
-        );
+macro_rules! impl_scalar {
+    ($($ty:ty => $dtype:ident),*) => {
+        $(
+            impl Scalar for $ty {
+                const DTYPE: DType = DType::$dtype;
 
-        GraphTensor::new(graph_rc, node_id, result_shape)
-    }
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
 }
 
-impl Sub for GraphTensor {
-    type Output = GraphTensor;
+impl_scalar!(
+    f32 => F32, f64 => F64, i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64
+);
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        let graph_rc = self.graph();
-        
-        let result_shape = self.shape()
-            .broadcast_with(&rhs.shape())
-            .expect("Incompatible shapes for subtraction");
+/// Implements an arithmetic operator for owned and borrowed tensors and
+/// scalars, panicking where the fallible builder would return an error.
+macro_rules! tensor_ops {
+    ($($trait:ident, $method:ident => $try_method:ident;)*) => {
+        $(
+            impl $trait<&GraphTensor> for &GraphTensor {
+                type Output = GraphTensor;
 
-        let node_id = graph_rc.borrow_mut().add_binary_op(
-            self.node_id(),
-            rhs.node_id(),
-            Operation::Sub,
-        );
+                #[doc = concat!(
+                    "Panics on incompatible shapes; see [`GraphTensor::",
+                    stringify!($try_method),
+                    "`]."
+                )]
+                fn $method(self, rhs: &GraphTensor) -> GraphTensor {
+                    self.$try_method(rhs).unwrap_or_else(|err| panic!("{err}"))
+                }
+            }
 
-        GraphTensor::new(graph_rc, node_id, result_shape)
-    }
+            impl $trait<GraphTensor> for GraphTensor {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: GraphTensor) -> GraphTensor {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl $trait<&GraphTensor> for GraphTensor {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: &GraphTensor) -> GraphTensor {
+                    (&self).$method(rhs)
+                }
+            }
+
+            impl $trait<GraphTensor> for &GraphTensor {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: GraphTensor) -> GraphTensor {
+                    self.$method(&rhs)
+                }
+            }
+
+            impl<S: Scalar> $trait<S> for &GraphTensor {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: S) -> GraphTensor {
+                    let (lhs, rhs) = self
+                        .with_scalar(rhs, stringify!($method))
+                        .unwrap_or_else(|err| panic!("{err}"));
+
+                    lhs.$method(&rhs)
+                }
+            }
+
+            impl<S: Scalar> $trait<S> for GraphTensor {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: S) -> GraphTensor {
+                    (&self).$method(rhs)
+                }
+            }
+
+            scalar_ops!($trait, $method; f64, i64);
+        )*
+    };
 }
 
-impl Mul for GraphTensor {
-    type Output = GraphTensor;
+/// Implements an arithmetic operator with a scalar left-hand side. Only one
+/// float and one integer type get these impls, so that untyped literals
+/// such as `1.0 - &t` resolve without annotations.
+macro_rules! scalar_ops {
+    ($trait:ident, $method:ident; $($scalar:ty),*) => {
+        $(
+            impl $trait<&GraphTensor> for $scalar {
+                type Output = GraphTensor;
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        let graph_rc = self.graph();
-        
-        let result_shape = self.shape()
-            .broadcast_with(&rhs.shape())
-            .expect("Incompatible shapes for multiplication");
+                fn $method(self, rhs: &GraphTensor) -> GraphTensor {
+                    let (rhs, lhs) = rhs
+                        .with_scalar(self, stringify!($method))
+                        .unwrap_or_else(|err| panic!("{err}"));
 
-        let node_id = graph_rc.borrow_mut().add_binary_op(
-            self.node_id(),
-            rhs.node_id(),
-            Operation::Mul,
-        );
+                    lhs.$method(&rhs)
+                }
+            }
 
-        GraphTensor::new(graph_rc, node_id, result_shape)
-    }
+            impl $trait<GraphTensor> for $scalar {
+                type Output = GraphTensor;
+
+                fn $method(self, rhs: GraphTensor) -> GraphTensor {
+                    self.$method(&rhs)
+                }
+            }
+        )*
+    };
 }
 
-impl Div for GraphTensor {
-    type Output = GraphTensor;
+tensor_ops! {
+    Add, add => try_add;
+    Sub, sub => try_sub;
+    Mul, mul => try_mul;
+    Div, div => try_div;
+}
+
+impl GraphTensor {
+    /// Elementwise `self + rhs`, broadcasting both operands.
+    pub fn try_add(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Add, "add")
+    }
+
+    /// Elementwise `self - rhs`; see [`GraphTensor::try_add`].
+    pub fn try_sub(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Sub, "sub")
+    }
+
+    /// Elementwise `self * rhs`; see [`GraphTensor::try_add`].
+    pub fn try_mul(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Mul, "mul")
+    }
+
+    /// Elementwise `self / rhs`; see [`GraphTensor::try_add`].
+    pub fn try_div(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Div, "div")
+    }
+
+    /// Elementwise `self == rhs` as a bool tensor, broadcasting like the
+    /// arithmetic operators.
+    pub fn try_equal(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Equal, "equal")
+    }
+
+    /// Elementwise `self != rhs`; see [`GraphTensor::try_equal`].
+    pub fn try_not_equal(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::NotEqual, "not_equal")
+    }
+
+    /// Elementwise `self < rhs`; see [`GraphTensor::try_equal`].
+    pub fn try_less(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Less, "less")
+    }
+
+    /// Elementwise `self <= rhs`; see [`GraphTensor::try_equal`].
+    pub fn try_less_equal(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::LessEqual, "less_equal")
+    }
+
+    /// Elementwise `self > rhs`; see [`GraphTensor::try_equal`].
+    pub fn try_greater(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::Greater, "greater")
+    }
+
+    /// Elementwise `self >= rhs`; see [`GraphTensor::try_equal`].
+    pub fn try_greater_equal(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.broadcast_binary(rhs, Operation::GreaterEqual, "greater_equal")
+    }
+
+    /// Panics where [`GraphTensor::try_equal`] fails.
+    pub fn equal(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_equal(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_not_equal`] fails.
+    pub fn not_equal(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_not_equal(rhs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_less`] fails.
+    pub fn less(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_less(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_less_equal`] fails.
+    pub fn less_equal(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_less_equal(rhs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_greater`] fails.
+    pub fn greater(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_greater(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_greater_equal`] fails.
+    pub fn greater_equal(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_greater_equal(rhs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// `self` and a tensor of its shape filled with `value`, in one dtype
+    /// for `op`; see [`Scalar`].
+    fn with_scalar<S: Scalar>(
+        &self,
+        value: S,
+        op: &'static str,
+    ) -> Result<(GraphTensor, GraphTensor), GraphError> {
+        let tensor = match S::DTYPE.is_float() {
+            true => self.float_operand(op)?,
+            false => self.clone(),
+        };
+        let scalar = tensor.full_like(value.to_f64());
+
+        Ok((tensor, scalar))
+    }
+
+    fn broadcast_binary(
+        &self,
+        rhs: &GraphTensor,
+        op: Operation,
+        name: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let result_shape =
+            self.shape().broadcast_with(&rhs.shape()).map_err(|_| {
+                GraphError::IncompatibleShapes {
+                    op: name,
+                    node: rhs.node_id(),
+                    lhs: self.shape(),
+                    rhs: rhs.shape(),
+                }
+            })?;
 
-    fn div(self, rhs: Self) -> Self::Output {
-        let graph_rc = self.graph();
-        
-        let result_shape = self.shape()
-            .broadcast_with(&rhs.shape())
-            .expect("Incompatible shapes for division");
+        let operands = GraphTensor::promote(name, &[self, rhs])?;
+        // Only addition and multiplication have bool kernels.
+        if matches!(op, Operation::Sub | Operation::Div) {
+            operands[0].numeric_operand(name)?;
+        }
 
         let node_id = graph_rc.borrow_mut().add_binary_op(
-            self.node_id(),
-            rhs.node_id(),
-            Operation::Div,
+            operands[0].node_id(),
+            operands[1].node_id(),
+            op,
+            result_shape.clone(),
         );
+        let dtype = graph_rc.borrow().dtype(node_id);
 
-        GraphTensor::new(graph_rc, node_id, result_shape)
+        Ok(GraphTensor::new(graph_rc, node_id, result_shape, dtype))
     }
 }
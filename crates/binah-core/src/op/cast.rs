@@ -0,0 +1,146 @@
+use crate::{
+    graph::{GraphError, TypePromotion, tensor::GraphTensor},
+    op::Operation,
+    tensor::storage::DType,
+};
+
+impl GraphTensor {
+    /// Panics where [`GraphTensor::try_cast`] fails.
+    pub fn cast(&self, dtype: DType) -> GraphTensor {
+        self.try_cast(dtype).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Converts every element to `dtype` as Rust's `as` does. Bools become
+    /// 0 or 1, and numbers become bools by testing for non-zero. Fails once
+    /// the graph was dropped.
+    pub fn try_cast(&self, dtype: DType) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            self.node_id(),
+            Operation::Cast { dtype },
+            self.shape(),
+        );
+
+        Ok(GraphTensor::new(graph_rc, node_id, self.shape(), dtype))
+    }
+
+    /// `tensors` converted to one dtype for `op`. Graphs in strict mode
+    /// reject operands whose dtypes differ instead.
+    pub(crate) fn promote(
+        op: &'static str,
+        tensors: &[&GraphTensor],
+    ) -> Result<Vec<GraphTensor>, GraphError> {
+        let first = tensors.first().ok_or(GraphError::NoOperands { op })?;
+        let promotion = first.graph()?.borrow().type_promotion();
+
+        let mut dtype = first.dtype();
+        for tensor in &tensors[1..] {
+            dtype = match promotion {
+                TypePromotion::Numpy => dtype.promote(tensor.dtype()),
+                TypePromotion::Strict if dtype == tensor.dtype() => dtype,
+                TypePromotion::Strict => {
+                    return Err(GraphError::MixedDtypes {
+                        op,
+                        node: tensor.node_id(),
+                        lhs: dtype,
+                        rhs: tensor.dtype(),
+                    });
+                }
+            };
+        }
+
+        tensors
+            .iter()
+            .map(|tensor| tensor.converted(dtype))
+            .collect()
+    }
+
+    /// `self` converted to `dtype`, the type `op` writes it into. Graphs in
+    /// strict mode reject a tensor of any other type instead.
+    pub(crate) fn assignable(
+        &self,
+        op: &'static str,
+        dtype: DType,
+    ) -> Result<GraphTensor, GraphError> {
+        let promotion = self.graph()?.borrow().type_promotion();
+        if promotion == TypePromotion::Strict && self.dtype() != dtype {
+            return Err(GraphError::MixedDtypes {
+                op,
+                node: self.node_id(),
+                lhs: dtype,
+                rhs: self.dtype(),
+            });
+        }
+
+        self.converted(dtype)
+    }
+
+    /// `self` as a float for `op`, which has no integer kernels. Integers
+    /// become the float type NumPy would pick, unless the graph is strict.
+    pub(crate) fn float_operand(
+        &self,
+        op: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        if self.dtype().is_float() {
+            return Ok(self.clone());
+        }
+
+        let promotion = self.graph()?.borrow().type_promotion();
+        match promotion {
+            TypePromotion::Numpy => {
+                self.converted(self.dtype().promote(DType::F32))
+            }
+            TypePromotion::Strict => Err(self.dtype_error(op)),
+        }
+    }
+
+    /// Fails when `self` is a bool tensor, which `op` has no kernel for.
+    pub(crate) fn numeric_operand(
+        &self,
+        op: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        match self.dtype() {
+            DType::Bool => Err(self.dtype_error(op)),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Fails unless `self` holds integers that `op` can index with.
+    pub(crate) fn index_operand(
+        &self,
+        op: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        match self.dtype().is_integer() {
+            true => Ok(self.clone()),
+            false => Err(self.dtype_error(op)),
+        }
+    }
+
+    /// Fails unless `self` is a bool tensor, as `op` needs.
+    pub(crate) fn bool_operand(
+        &self,
+        op: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        match self.dtype() {
+            DType::Bool => Ok(self.clone()),
+            _ => Err(self.dtype_error(op)),
+        }
+    }
+
+    /// `self` as `dtype`, without a new node when it already is.
+    fn converted(&self, dtype: DType) -> Result<GraphTensor, GraphError> {
+        match self.dtype() == dtype {
+            true => Ok(self.clone()),
+            false => self.try_cast(dtype),
+        }
+    }
+
+    fn dtype_error(&self, op: &'static str) -> GraphError {
+        GraphError::UnsupportedDtype {
+            op,
+            node: self.node_id(),
+            dtype: self.dtype(),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::shape::{Shape, ShapeError},
+};
+
+use super::shape::normalize_axis;
+
+impl Graph {
+    /// Joins `tensors` along `axis`. They must have the same rank and agree
+    /// on every other axis. Negative axes count from the end.
+    pub fn concat(
+        &mut self,
+        tensors: &[&GraphTensor],
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        let first = tensors
+            .first()
+            .ok_or(GraphError::NoOperands { op: "concat" })?;
+        let rank = first.shape().dims().len();
+        let axis = normalize_axis(axis, rank)
+            .map_err(|err| first.shape_error("concat", err))?;
+
+        let mut dims = first.shape().dims().to_vec();
+        let mut size = 0;
+        for tensor in tensors {
+            let shape = tensor.shape();
+            if shape.dims().len() != rank {
+                let source = ShapeError::RankMismatch(rank, shape.dims().len());
+                return Err(tensor.shape_error("concat", source));
+            }
+            let fits = (0..rank).all(|other| {
+                other == axis || shape.dims()[other] == dims[other]
+            });
+            if !fits {
+                return Err(GraphError::IncompatibleShapes {
+                    op: "concat",
+                    node: tensor.node_id(),
+                    lhs: first.shape(),
+                    rhs: shape,
+                });
+            }
+            size += tensor.fixed_dim(axis, "concat")?;
+        }
+        dims[axis] = size.into();
+
+        let operands = GraphTensor::promote("concat", tensors)?;
+        let inputs: Vec<_> =
+            operands.iter().map(|tensor| tensor.node_id()).collect();
+        let shape = Shape::from(dims);
+        let node_id = self.inner.borrow_mut().add_variadic_op(
+            &inputs,
+            Operation::Concat { axis },
+            shape.clone(),
+        );
+
+        Ok(GraphTensor::new(
+            self.inner.clone(),
+            node_id,
+            shape,
+            operands[0].dtype(),
+        ))
+    }
+
+    /// Joins `tensors`, which must all have the same shape, along a new axis
+    /// inserted at `axis`. Negative axes count from the end of the result.
+    pub fn stack(
+        &mut self,
+        tensors: &[&GraphTensor],
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        let first = tensors
+            .first()
+            .ok_or(GraphError::NoOperands { op: "stack" })?;
+        if let Some(other) = tensors.iter().find(|t| t.shape() != first.shape())
+        {
+            return Err(GraphError::IncompatibleShapes {
+                op: "stack",
+                node: other.node_id(),
+                lhs: first.shape(),
+                rhs: other.shape(),
+            });
+        }
+        let axis = normalize_axis(axis, first.shape().dims().len() + 1)
+            .map_err(|err| first.shape_error("stack", err))?
+            as isize;
+
+        let expanded = tensors
+            .iter()
+            .map(|tensor| tensor.unsqueeze(&[axis]))
+            .collect::<Result<Vec<_>, _>>()?;
+        let expanded: Vec<&GraphTensor> = expanded.iter().collect();
+
+        self.concat(&expanded, axis)
+    }
+}
+
+impl GraphTensor {
+    /// Splits along `axis` into consecutive pieces of `sizes`, which must
+    /// add up to the size of the axis. The pieces are views of `self`.
+    pub fn split(
+        &self,
+        sizes: &[usize],
+        axis: isize,
+    ) -> Result<Vec<GraphTensor>, GraphError> {
+        let shape = self.shape();
+        let axis = normalize_axis(axis, shape.dims().len())
+            .map_err(|err| self.shape_error("split", err))?;
+        let dim = self.fixed_dim(axis, "split")?;
+        if sizes.iter().sum::<usize>() != dim {
+            let source = ShapeError::InvalidSplit(sizes.to_vec(), dim);
+            return Err(self.shape_error("split", source));
+        }
+
+        let graph_rc = self.graph()?;
+        let mut inner = graph_rc.borrow_mut();
+
+        let split = inner.add_unary_op(
+            self.node_id(),
+            Operation::Split {
+                axis,
+                sizes: sizes.to_vec(),
+            },
+            shape.clone(),
+        );
+
+        let pieces = sizes
+            .iter()
+            .enumerate()
+            .map(|(index, &size)| {
+                let mut dims = shape.dims().to_vec();
+                dims[axis] = size.into();
+                let piece = Shape::from(dims);
+
+                let node_id = inner.add_unary_op(
+                    split,
+                    Operation::Output { index },
+                    piece.clone(),
+                );
+                GraphTensor::new(graph_rc.clone(), node_id, piece, self.dtype())
+            })
+            .collect();
+
+        Ok(pieces)
+    }
+
+    /// Splits along `axis` into `chunks` pieces of equal size, the last one
+    /// smaller when the size does not divide evenly. There are fewer pieces
+    /// when the axis is too short to make `chunks` of them.
+    pub fn chunk(
+        &self,
+        chunks: usize,
+        axis: isize,
+    ) -> Result<Vec<GraphTensor>, GraphError> {
+        let shape = self.shape();
+        let invalid = |source| self.shape_error("chunk", source);
+        let resolved =
+            normalize_axis(axis, shape.dims().len()).map_err(invalid)?;
+        let dim = self.fixed_dim(resolved, "chunk")?;
+        if chunks == 0 {
+            return Err(invalid(ShapeError::InvalidSplit(Vec::new(), dim)));
+        }
+
+        let size = dim.div_ceil(chunks).max(1);
+        let mut sizes: Vec<usize> = (0..dim)
+            .step_by(size)
+            .map(|start| size.min(dim - start))
+            .collect();
+        if sizes.is_empty() {
+            sizes.push(0);
+        }
+
+        self.split(&sizes, axis)
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::shape::{Dim, Shape, ShapeError, Slice},
+};
+
+use super::shape::normalize_axis;
+
+impl GraphTensor {
+    /// Slices the leading axes, one [`Slice`] per axis; the remaining axes
+    /// are kept whole. No data is copied. Fails on a step that is not
+    /// positive.
+    pub fn slice(&self, slices: &[Slice]) -> Result<GraphTensor, GraphError> {
+        let shape = self
+            .sliced_shape(slices)
+            .map_err(|err| self.shape_error("slice", err))?;
+
+        self.indexed(
+            &[],
+            Operation::Slice {
+                slices: slices.to_vec(),
+            },
+            shape,
+        )
+    }
+
+    /// A copy of `self` with the region selected by `slices` replaced by
+    /// `src`, which must have the shape of that region.
+    pub fn slice_scatter(
+        &self,
+        src: &GraphTensor,
+        slices: &[Slice],
+    ) -> Result<GraphTensor, GraphError> {
+        let region = self
+            .sliced_shape(slices)
+            .map_err(|err| self.shape_error("slice_scatter", err))?;
+        if src.shape() != region {
+            return Err(GraphError::IncompatibleShapes {
+                op: "slice_scatter",
+                node: src.node_id(),
+                lhs: region,
+                rhs: src.shape(),
+            });
+        }
+        let src = src.assignable("slice_scatter", self.dtype())?;
+
+        self.indexed(
+            &[&src],
+            Operation::SliceScatter {
+                slices: slices.to_vec(),
+            },
+            self.shape(),
+        )
+    }
+
+    /// Entries along `axis` at the positions in the 1-D integer tensor
+    /// `indices`. Negative indices count from the end of the axis.
+    pub fn index_select(
+        &self,
+        indices: &GraphTensor,
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        let mut dims = self.shape().dims().to_vec();
+        let axis = normalize_axis(axis, dims.len())
+            .map_err(|err| self.shape_error("index_select", err))?;
+
+        let index_dims = indices.shape().dims().to_vec();
+        if index_dims.len() != 1 {
+            let source = ShapeError::RankMismatch(1, index_dims.len());
+            return Err(indices.shape_error("index_select", source));
+        }
+        dims[axis] = index_dims[0];
+        indices.index_operand("index_select")?;
+
+        self.indexed(&[indices], Operation::IndexSelect { axis }, dims.into())
+    }
+
+    /// `out[i][j][k] = self[i][indices[i][j][k]][k]` for `axis == 1`, and
+    /// likewise for other axes. `indices` has the same rank as `self` and
+    /// no larger a size on any other axis; the result has its shape.
+    pub fn gather(
+        &self,
+        indices: &GraphTensor,
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        let axis = self.check_indices(axis, indices, "gather")?;
+
+        self.indexed(&[indices], Operation::Gather { axis }, indices.shape())
+    }
+
+    /// A copy of `self` with `self[i][indices[i][j][k]][k] = src[i][j][k]`
+    /// for `axis == 1`, and likewise for other axes. `src` has the shape of
+    /// `indices`. Which write wins for repeated indices is unspecified.
+    pub fn scatter(
+        &self,
+        indices: &GraphTensor,
+        src: &GraphTensor,
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        self.scatter_with(indices, src, axis, false, "scatter")
+    }
+
+    /// Like [`GraphTensor::scatter`], but adds `src` to the entries instead
+    /// of overwriting them, accumulating over repeated indices.
+    pub fn scatter_add(
+        &self,
+        indices: &GraphTensor,
+        src: &GraphTensor,
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        self.scatter_with(indices, src, axis, true, "scatter_add")
+    }
+
+    /// `on_true` where this bool tensor is true and `on_false` elsewhere.
+    /// All three broadcast together.
+    pub fn select(
+        &self,
+        on_true: &GraphTensor,
+        on_false: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let incompatible = |node, lhs, rhs| GraphError::IncompatibleShapes {
+            op: "select",
+            node,
+            lhs,
+            rhs,
+        };
+        let values = on_true
+            .shape()
+            .broadcast_with(&on_false.shape())
+            .map_err(|_| {
+                incompatible(
+                    on_false.node_id(),
+                    on_true.shape(),
+                    on_false.shape(),
+                )
+            })?;
+        let shape = self
+            .shape()
+            .broadcast_with(&values)
+            .map_err(|_| incompatible(self.node_id(), values, self.shape()))?;
+
+        self.bool_operand("select")?;
+        let values = GraphTensor::promote("select", &[on_true, on_false])?;
+
+        self.indexed(&[&values[0], &values[1]], Operation::Where, shape)
+    }
+
+    /// `value` wherever the bool tensor `mask` is true, `self` elsewhere.
+    /// `mask` must broadcast to the shape of `self`.
+    pub fn masked_fill(
+        &self,
+        mask: &GraphTensor,
+        value: f64,
+    ) -> Result<GraphTensor, GraphError> {
+        match mask.shape().broadcast_with(&self.shape()) {
+            Ok(shape) if shape == self.shape() => {}
+            _ => {
+                let source =
+                    ShapeError::NotExpandable(mask.shape(), self.shape());
+                return Err(mask.shape_error("masked_fill", source));
+            }
+        }
+
+        let graph_rc = self.graph()?;
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            self.node_id(),
+            Operation::FullLike { value },
+            self.shape(),
+        );
+        let fill =
+            GraphTensor::new(graph_rc, node_id, self.shape(), self.dtype());
+
+        mask.select(&fill, self)
+    }
+
+    fn scatter_with(
+        &self,
+        indices: &GraphTensor,
+        src: &GraphTensor,
+        axis: isize,
+        accumulate: bool,
+        name: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        let axis = self.check_indices(axis, indices, name)?;
+        if src.shape() != indices.shape() {
+            return Err(GraphError::IncompatibleShapes {
+                op: name,
+                node: src.node_id(),
+                lhs: indices.shape(),
+                rhs: src.shape(),
+            });
+        }
+        let src = src.assignable(name, self.dtype())?;
+
+        self.indexed(
+            &[indices, &src],
+            Operation::Scatter { axis, accumulate },
+            self.shape(),
+        )
+    }
+
+    fn sliced_shape(&self, slices: &[Slice]) -> Result<Shape, ShapeError> {
+        let mut dims = self.shape().dims().to_vec();
+        if slices.len() > dims.len() {
+            return Err(ShapeError::AxisOutOfRange(
+                slices.len() as isize - 1,
+                dims.len(),
+            ));
+        }
+
+        for (axis, (dim, slice)) in dims.iter_mut().zip(slices).enumerate() {
+            if slice.step <= 0 {
+                return Err(ShapeError::InvalidStep(slice.step));
+            }
+            // A symbolic axis can only be kept whole.
+            match *dim {
+                Dim::Fixed(size) => *dim = slice.resolve(size).1.into(),
+                Dim::Sym(_) if *slice == Slice::full() => {}
+                Dim::Sym(_) => return Err(ShapeError::SymbolicDim(axis, *dim)),
+            }
+        }
+
+        Ok(dims.into())
+    }
+
+    /// Validates `indices` for gathering from or scattering into `self`
+    /// along `axis`, returning the resolved axis.
+    fn check_indices(
+        &self,
+        axis: isize,
+        indices: &GraphTensor,
+        name: &'static str,
+    ) -> Result<usize, GraphError> {
+        let (shape, index_shape) = (self.shape(), indices.shape());
+        let (dims, index_dims) = (shape.dims(), index_shape.dims());
+        let axis = normalize_axis(axis, dims.len())
+            .map_err(|err| self.shape_error(name, err))?;
+        indices.index_operand(name)?;
+
+        if index_dims.len() != dims.len() {
+            let source = ShapeError::RankMismatch(dims.len(), index_dims.len());
+            return Err(indices.shape_error(name, source));
+        }
+        let fits = (0..dims.len()).all(|other| {
+            let (index_dim, dim) = (index_dims[other], dims[other]);
+            match (index_dim.fixed(), dim.fixed()) {
+                _ if other == axis || index_dim == dim => true,
+                (Some(index_dim), Some(dim)) => index_dim <= dim,
+                _ => false,
+            }
+        });
+        if !fits {
+            return Err(GraphError::IncompatibleShapes {
+                op: name,
+                node: indices.node_id(),
+                lhs: shape,
+                rhs: index_shape,
+            });
+        }
+
+        Ok(axis)
+    }
+
+    fn indexed(
+        &self,
+        operands: &[&GraphTensor],
+        op: Operation,
+        shape: Shape,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let inputs: Vec<_> = std::iter::once(self)
+            .chain(operands.iter().copied())
+            .map(GraphTensor::node_id)
+            .collect();
+        let node_id =
+            graph_rc
+                .borrow_mut()
+                .add_variadic_op(&inputs, op, shape.clone());
+        let dtype = graph_rc.borrow().dtype(node_id);
+
+        Ok(GraphTensor::new(graph_rc, node_id, shape, dtype))
+    }
+}
@@ -0,0 +1,158 @@
+use crate::graph::{GraphError, tensor::GraphTensor};
+
+/// How a loss combines its per-element values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Reduction {
+    /// Keeps one value per element.
+    None,
+    /// Averages over every element.
+    #[default]
+    Mean,
+    /// Adds up every element.
+    Sum,
+}
+
+impl Reduction {
+    fn apply(self, losses: GraphTensor) -> GraphTensor {
+        match self {
+            Reduction::None => losses,
+            Reduction::Mean => losses.mean(&[], false),
+            Reduction::Sum => losses.sum(&[], false),
+        }
+    }
+}
+
+impl GraphTensor {
+    /// Squared error between `self` and `target`, which must have the same
+    /// shape.
+    pub fn mse_loss(
+        &self,
+        target: &GraphTensor,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let (input, target) = self.loss_operands(target, "mse_loss")?;
+        let error = input - target;
+
+        Ok(reduction.apply(&error * &error))
+    }
+
+    /// Absolute error between `self` and `target`, which must have the same
+    /// shape.
+    pub fn l1_loss(
+        &self,
+        target: &GraphTensor,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let (input, target) = self.loss_operands(target, "l1_loss")?;
+
+        Ok(reduction.apply((input - target).abs()))
+    }
+
+    /// Squared error halved where the error is at most `delta`, and linear
+    /// in the error beyond, with matching slopes at `delta`.
+    pub fn huber_loss(
+        &self,
+        target: &GraphTensor,
+        delta: f64,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let (input, target) = self.loss_operands(target, "huber_loss")?;
+        let error = (input - target).abs();
+
+        let quadratic = &error * &error * 0.5;
+        let linear = (&error - 0.5 * delta) * delta;
+        let losses = error
+            .less_equal(&error.full_like(delta))
+            .select(&quadratic, &linear)?;
+
+        Ok(reduction.apply(losses))
+    }
+
+    /// Binary cross-entropy between `sigmoid(self)` and probabilities
+    /// `target`, computed from the logits as `max(x, 0) - x * target +
+    /// log(1 + exp(-|x|))` so that large logits cannot overflow.
+    pub fn binary_cross_entropy_with_logits(
+        &self,
+        target: &GraphTensor,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let (logits, target) =
+            self.loss_operands(target, "binary_cross_entropy_with_logits")?;
+
+        let softplus = ((-logits.abs()).exp() + 1.0).log();
+        let losses = logits.relu() - &logits * &target + softplus;
+
+        Ok(reduction.apply(losses))
+    }
+
+    /// Cross-entropy between `softmax(self)` over the last axis and the
+    /// classes in the integer tensor `labels`, which has the shape of
+    /// `self` without its last axis. The log-probabilities come from
+    /// [`GraphTensor::log_softmax`], so no logit overflows `exp`.
+    pub fn cross_entropy_loss(
+        &self,
+        labels: &GraphTensor,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let dims = self.shape().dims().to_vec();
+        let batch_dims = dims.split_last().map(|(_, batch)| batch);
+        if batch_dims != Some(labels.shape().dims()) {
+            return Err(GraphError::IncompatibleShapes {
+                op: "cross_entropy_loss",
+                node: labels.node_id(),
+                lhs: self.shape(),
+                rhs: labels.shape(),
+            });
+        }
+        let labels = labels.index_operand("cross_entropy_loss")?;
+        let logits = self.float_operand("cross_entropy_loss")?;
+
+        let picked = logits
+            .try_log_softmax(-1)?
+            .gather(&labels.unsqueeze(&[-1])?, -1)?
+            .squeeze(&[-1])?;
+
+        Ok(reduction.apply(-picked))
+    }
+
+    /// Kullback-Leibler divergence of `self`, log-probabilities, from the
+    /// probabilities `target`: `target * (log(target) - self)`, taken as 0
+    /// where `target` is 0. As in PyTorch, [`Reduction::Mean`] averages
+    /// over every element rather than over the batch.
+    pub fn kl_div_loss(
+        &self,
+        target: &GraphTensor,
+        reduction: Reduction,
+    ) -> Result<GraphTensor, GraphError> {
+        let (log_probs, target) = self.loss_operands(target, "kl_div_loss")?;
+
+        let terms = &target * &(target.log() - log_probs);
+        let zeros = target.zeros_like();
+        let losses = target.greater(&zeros).select(&terms, &zeros)?;
+
+        Ok(reduction.apply(losses))
+    }
+
+    /// `self` and `target` as float tensors of one dtype, which `op` needs
+    /// to be of the same shape.
+    fn loss_operands(
+        &self,
+        target: &GraphTensor,
+        op: &'static str,
+    ) -> Result<(GraphTensor, GraphTensor), GraphError> {
+        if self.shape() != target.shape() {
+            return Err(GraphError::IncompatibleShapes {
+                op,
+                node: target.node_id(),
+                lhs: self.shape(),
+                rhs: target.shape(),
+            });
+        }
+
+        let operands = GraphTensor::promote(op, &[self, target])?;
+        Ok((
+            operands[0].float_operand(op)?,
+            operands[1].float_operand(op)?,
+        ))
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+};
+
+impl GraphTensor {
+    /// Matrix product following NumPy's `matmul` rules: 1-D operands are
+    /// vectors, and leading batch dimensions broadcast. Panics on
+    /// incompatible shapes; see [`GraphTensor::try_matmul`].
+    pub fn matmul(&self, rhs: &GraphTensor) -> GraphTensor {
+        self.try_matmul(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible [`GraphTensor::matmul`].
+    pub fn try_matmul(
+        &self,
+        rhs: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let result_shape =
+            self.shape().matmul_with(&rhs.shape()).map_err(|_| {
+                GraphError::IncompatibleShapes {
+                    op: "matmul",
+                    node: rhs.node_id(),
+                    lhs: self.shape(),
+                    rhs: rhs.shape(),
+                }
+            })?;
+
+        let operands = GraphTensor::promote("matmul", &[self, rhs])?;
+        let dtype = operands[0].numeric_operand("matmul")?.dtype();
+
+        let node_id = graph_rc.borrow_mut().add_binary_op(
+            operands[0].node_id(),
+            operands[1].node_id(),
+            Operation::MatMul {
+                transpose_lhs: false,
+                transpose_rhs: false,
+            },
+            result_shape.clone(),
+        );
+
+        Ok(GraphTensor::new(graph_rc, node_id, result_shape, dtype))
+    }
+}
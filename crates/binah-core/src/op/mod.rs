@@ -1,12 +1,259 @@
+use crate::tensor::{
+    shape::{Shape, Slice},
+    storage::DType,
+};
+
 mod binary;
+mod cast;
+mod concat;
+mod index;
+mod loss;
+mod matmul;
+mod random;
+mod reduce;
+mod shape;
+mod softmax;
+mod unary;
+mod variable;
+
+pub use binary::Scalar;
+pub use loss::Reduction;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Operation {
     Constant,
     Variable,
-    Placeholder,
+    /// An input fed at execution time, which must have `shape` and `dtype`.
+    Placeholder { shape: Shape, dtype: DType },
     Add,
     Sub,
     Mul,
     Div,
+    /// Elementwise comparisons producing bool tensors.
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    /// Batched matrix product; the flags read an operand with its last two
+    /// dimensions swapped.
+    MatMul {
+        transpose_lhs: bool,
+        transpose_rhs: bool,
+    },
+    /// Elementwise unary operations.
+    Neg,
+    Abs,
+    Exp,
+    Log,
+    Sqrt,
+    Rsqrt,
+    Sin,
+    Cos,
+    Tanh,
+    Sigmoid,
+    Relu,
+    Gelu,
+    Erf,
+    Floor,
+    Ceil,
+    Round,
+    Sign,
+    Reciprocal,
+    /// Reductions over sorted, unique `axes`.
+    Sum {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    Mean {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    Max {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    Min {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    Prod {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    ArgMax {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    ArgMin {
+        axes: Vec<usize>,
+        keepdims: bool,
+    },
+    /// Normalizes the exponentials of the input along `axis` to sum to 1.
+    Softmax { axis: usize },
+    /// The logarithm of [`Operation::Softmax`], computed without forming
+    /// the probabilities.
+    LogSoftmax { axis: usize },
+    /// Reinterprets the input's elements, in order, as `shape`.
+    Reshape { shape: Shape },
+    /// Reorders dimensions; output axis `i` is input axis `axes[i]`.
+    Permute { axes: Vec<usize> },
+    /// Removes the size-1 dimensions at sorted `axes`.
+    Squeeze { axes: Vec<usize> },
+    /// Inserts size-1 dimensions at sorted `axes` of the output.
+    Unsqueeze { axes: Vec<usize> },
+    /// Broadcasts the input to `shape` without copying it.
+    Expand { shape: Shape },
+    /// Restricts the leading axes to `slices` without copying.
+    Slice { slices: Vec<Slice> },
+    /// The first operand with the region selected by `slices` replaced by
+    /// the second.
+    SliceScatter { slices: Vec<Slice> },
+    /// Entries of the first operand along `axis` at the 1-D integer indices
+    /// in the second.
+    IndexSelect { axis: usize },
+    /// Entries of the first operand along `axis` at the indices in the
+    /// second, which has the output's shape.
+    Gather { axis: usize },
+    /// The first operand with the third written into it along `axis` at the
+    /// indices in the second; added to instead when `accumulate` is set.
+    Scatter { axis: usize, accumulate: bool },
+    /// Joins every operand along `axis`.
+    Concat { axis: usize },
+    /// Splits the input along `axis` into pieces of `sizes`. This node has
+    /// one output per piece, each read through an [`Operation::Output`].
+    Split { axis: usize, sizes: Vec<usize> },
+    /// Output `index` of a multi-output operand.
+    Output { index: usize },
+    /// Elementwise choice of the second operand where the bool first operand
+    /// is true and the third elsewhere.
+    Where,
+    /// Sums broadcast dimensions away until the input matches `shape`.
+    SumTo { shape: Shape },
+    OnesLike,
+    ZerosLike,
+    /// A tensor shaped and typed like the input, filled with `value`.
+    FullLike { value: f64 },
+    /// Uniform samples in `[0, 1)` shaped and typed like the float first
+    /// operand. They depend only on `seed`, the integer scalar second
+    /// operand and each element's position.
+    Uniform { seed: u64 },
+    /// Converts every element of the input to `dtype`.
+    Cast { dtype: DType },
+    /// Overwrites the variable that is the first operand with the second,
+    /// broadcast to its shape, and yields the new value. The write outlives
+    /// the execution.
+    Assign,
+    /// Like [`Operation::Assign`], but adds the second operand to the
+    /// variable instead.
+    AssignAdd,
+    /// Elementwise operations evaluated together in one pass over the
+    /// output, which is the result of the last step. Compiling creates
+    /// these from chains of elementwise nodes; graphs never hold them.
+    Fused { steps: Vec<FusedStep> },
+}
+
+/// One operation of an [`Operation::Fused`] kernel. `operands` index the
+/// kernel's values: its inputs, followed by the result of every step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FusedStep {
+    pub op: Operation,
+    pub operands: Vec<usize>,
+}
+
+impl Operation {
+    /// The dtype of the result given the dtypes of the operands, in order.
+    /// Builders cast the operands to types the operation accepts first, so
+    /// most operations keep the type of their first operand.
+    pub(crate) fn output_dtype(&self, operands: &[DType]) -> DType {
+        match self {
+            Operation::Placeholder { dtype, .. }
+            | Operation::Cast { dtype } => *dtype,
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::Less
+            | Operation::LessEqual
+            | Operation::Greater
+            | Operation::GreaterEqual => DType::Bool,
+            Operation::ArgMax { .. } | Operation::ArgMin { .. } => DType::I64,
+            // The first operand is the bool condition.
+            Operation::Where => operands[1],
+            _ => operands[0],
+        }
+    }
+
+    /// Whether the result is a view sharing the storage of the first
+    /// operand rather than a tensor of its own. Reshapes copy when the
+    /// operand's layout cannot be reshaped in place.
+    pub(crate) fn is_view(&self) -> bool {
+        matches!(
+            self,
+            Operation::Reshape { .. }
+                | Operation::Permute { .. }
+                | Operation::Squeeze { .. }
+                | Operation::Unsqueeze { .. }
+                | Operation::Expand { .. }
+                | Operation::Slice { .. }
+                | Operation::Split { .. }
+                | Operation::Output { .. }
+        )
+    }
+
+    /// Whether the operation maps the elements at each position of its
+    /// broadcast operands to one element of the same dtype, so that it can
+    /// run inside an [`Operation::Fused`] kernel. A fill maps every
+    /// position to its value.
+    pub(crate) fn is_elementwise(&self) -> bool {
+        matches!(
+            self,
+            Operation::Add
+                | Operation::Sub
+                | Operation::Mul
+                | Operation::Div
+                | Operation::Neg
+                | Operation::Abs
+                | Operation::Exp
+                | Operation::Log
+                | Operation::Sqrt
+                | Operation::Rsqrt
+                | Operation::Sin
+                | Operation::Cos
+                | Operation::Tanh
+                | Operation::Sigmoid
+                | Operation::Relu
+                | Operation::Gelu
+                | Operation::Erf
+                | Operation::Floor
+                | Operation::Ceil
+                | Operation::Round
+                | Operation::Sign
+                | Operation::Reciprocal
+                | Operation::FullLike { .. }
+        )
+    }
+
+    /// Whether the CPU kernel of the operation takes the buffer of its
+    /// result from an [`Arena`](crate::cpu::Arena), so that a buffer
+    /// released before it can be handed to it.
+    pub(crate) fn takes_buffer(&self) -> bool {
+        matches!(
+            self,
+            Operation::Fused { .. }
+                | Operation::MatMul { .. }
+                | Operation::Sum { .. }
+                | Operation::Mean { .. }
+                | Operation::Max { .. }
+                | Operation::Min { .. }
+                | Operation::Prod { .. }
+                | Operation::ArgMax { .. }
+                | Operation::ArgMin { .. }
+                | Operation::Softmax { .. }
+                | Operation::LogSoftmax { .. }
+                | Operation::IndexSelect { .. }
+                | Operation::Gather { .. }
+                | Operation::Concat { .. }
+                | Operation::Cast { .. }
+        )
+    }
 }
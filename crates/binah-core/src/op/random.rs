@@ -0,0 +1,40 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::shape::ShapeError,
+};
+
+impl GraphTensor {
+    /// Uniform samples in `[0, 1)` with the shape of `self` and its float
+    /// type. The samples are a pure function of `seed` and the integer
+    /// scalar `key`, so passing a counter variable that is incremented by
+    /// [`GraphTensor::assign_add`] draws new samples on every execution.
+    pub fn uniform_like(
+        &self,
+        seed: u64,
+        key: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        let rank = key.shape().dims().len();
+        if rank != 0 {
+            let source = ShapeError::RankMismatch(0, rank);
+            return Err(key.shape_error("uniform_like", source));
+        }
+        let key = key.index_operand("uniform_like")?;
+        let like = self.float_operand("uniform_like")?;
+
+        let graph_rc = self.graph()?;
+        let node_id = graph_rc.borrow_mut().add_binary_op(
+            like.node_id(),
+            key.node_id(),
+            Operation::Uniform { seed },
+            self.shape(),
+        );
+
+        Ok(GraphTensor::new(
+            graph_rc,
+            node_id,
+            self.shape(),
+            like.dtype(),
+        ))
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::shape::ShapeError,
+};
+
+use super::shape::normalize_axis;
+
+impl GraphTensor {
+    /// Sums over `axes`, or over every axis when `axes` is empty. Negative
+    /// axes count from the end. Reduced axes are kept with size 1 when
+    /// `keepdims` is set. Fails on bool inputs and axes out of range.
+    pub fn try_sum(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("sum", axes, keepdims, |axes, keepdims| Operation::Sum {
+            axes,
+            keepdims,
+        })
+    }
+
+    /// Arithmetic mean over `axes`; see [`GraphTensor::try_sum`].
+    pub fn try_mean(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("mean", axes, keepdims, |axes, keepdims| Operation::Mean {
+            axes,
+            keepdims,
+        })
+    }
+
+    /// Maximum over `axes`; see [`GraphTensor::try_sum`].
+    pub fn try_max(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("max", axes, keepdims, |axes, keepdims| Operation::Max {
+            axes,
+            keepdims,
+        })
+    }
+
+    /// Minimum over `axes`; see [`GraphTensor::try_sum`].
+    pub fn try_min(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("min", axes, keepdims, |axes, keepdims| Operation::Min {
+            axes,
+            keepdims,
+        })
+    }
+
+    /// Product over `axes`; see [`GraphTensor::try_sum`].
+    pub fn try_prod(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("prod", axes, keepdims, |axes, keepdims| Operation::Prod {
+            axes,
+            keepdims,
+        })
+    }
+
+    /// `i64` index of the first maximum over `axes`, flattened row-major
+    /// when several axes are reduced; see [`GraphTensor::try_sum`].
+    pub fn try_argmax(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("argmax", axes, keepdims, |axes, keepdims| {
+            Operation::ArgMax { axes, keepdims }
+        })
+    }
+
+    /// `i64` index of the first minimum over `axes`; see
+    /// [`GraphTensor::try_argmax`].
+    pub fn try_argmin(
+        &self,
+        axes: &[isize],
+        keepdims: bool,
+    ) -> Result<GraphTensor, GraphError> {
+        self.reduce("argmin", axes, keepdims, |axes, keepdims| {
+            Operation::ArgMin { axes, keepdims }
+        })
+    }
+
+    /// Panics where [`GraphTensor::try_sum`] fails.
+    pub fn sum(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_sum(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_mean`] fails.
+    pub fn mean(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_mean(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_max`] fails.
+    pub fn max(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_max(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_min`] fails.
+    pub fn min(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_min(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_prod`] fails.
+    pub fn prod(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_prod(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_argmax`] fails.
+    pub fn argmax(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_argmax(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_argmin`] fails.
+    pub fn argmin(&self, axes: &[isize], keepdims: bool) -> GraphTensor {
+        self.try_argmin(axes, keepdims)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fails on bool inputs, which no reduction has a kernel for.
+    fn reduce(
+        &self,
+        name: &'static str,
+        axes: &[isize],
+        keepdims: bool,
+        op: impl FnOnce(Vec<usize>, bool) -> Operation,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+        self.numeric_operand(name)?;
+
+        let axes = normalize_axes(axes, self.shape().dims().len())
+            .map_err(|err| self.shape_error(name, err))?;
+        let result_shape = self.shape().reduced(&axes, keepdims);
+
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            self.node_id(),
+            op(axes, keepdims),
+            result_shape.clone(),
+        );
+
+        let dtype = graph_rc.borrow().dtype(node_id);
+
+        Ok(GraphTensor::new(graph_rc, node_id, result_shape, dtype))
+    }
+}
+
+/// Resolves negative axes and sorts and deduplicates them. An empty list
+/// selects every axis.
+fn normalize_axes(
+    axes: &[isize],
+    rank: usize,
+) -> Result<Vec<usize>, ShapeError> {
+    if axes.is_empty() {
+        return Ok((0..rank).collect());
+    }
+
+    let mut normalized = axes
+        .iter()
+        .map(|&axis| normalize_axis(axis, rank))
+        .collect::<Result<Vec<_>, _>>()?;
+    normalized.sort_unstable();
+    normalized.dedup();
+
+    Ok(normalized)
+}
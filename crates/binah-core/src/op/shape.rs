@@ -0,0 +1,193 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+    tensor::shape::{Dim, Shape, ShapeError},
+};
+
+impl GraphTensor {
+    /// Reinterprets the elements, in row-major order, as `shape`. No data
+    /// is copied unless the input is a view whose strides cannot express
+    /// the new shape.
+    pub fn reshape(
+        &self,
+        shape: impl Into<Shape>,
+    ) -> Result<GraphTensor, GraphError> {
+        let shape = shape.into();
+        if !shape.same_num_elements(&self.shape()) {
+            let source = ShapeError::ElementCountMismatch(self.shape(), shape);
+            return Err(self.shape_error("reshape", source));
+        }
+
+        self.view(
+            Operation::Reshape {
+                shape: shape.clone(),
+            },
+            shape,
+        )
+    }
+
+    /// Swaps two axes. Negative axes count from the end.
+    pub fn transpose(
+        &self,
+        axis0: isize,
+        axis1: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        let rank = self.shape().dims().len();
+        let resolve = |axis| {
+            normalize_axis(axis, rank)
+                .map_err(|err| self.shape_error("transpose", err))
+        };
+        let (axis0, axis1) = (resolve(axis0)?, resolve(axis1)?);
+
+        let mut axes: Vec<usize> = (0..rank).collect();
+        axes.swap(axis0, axis1);
+
+        self.permuted(axes)
+    }
+
+    /// Reorders the axes so that output axis `i` is input axis `axes[i]`.
+    /// Negative axes count from the end.
+    pub fn permute(&self, axes: &[isize]) -> Result<GraphTensor, GraphError> {
+        let rank = self.shape().dims().len();
+        let invalid = |source| self.shape_error("permute", source);
+        let not_permutation = ShapeError::InvalidPermutation(axes.to_vec());
+        if axes.len() != rank {
+            return Err(invalid(not_permutation));
+        }
+
+        let resolved = axes
+            .iter()
+            .map(|&axis| normalize_axis(axis, rank))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+
+        let mut seen = vec![false; rank];
+        for &axis in &resolved {
+            if std::mem::replace(&mut seen[axis], true) {
+                return Err(invalid(not_permutation));
+            }
+        }
+
+        self.permuted(resolved)
+    }
+
+    /// Removes the size-1 dimensions at `axes`, or every size-1 dimension
+    /// when `axes` is empty. Negative axes count from the end.
+    pub fn squeeze(&self, axes: &[isize]) -> Result<GraphTensor, GraphError> {
+        let shape = self.shape();
+        let dims = shape.dims();
+
+        let mut resolved = if axes.is_empty() {
+            (0..dims.len()).filter(|&axis| dims[axis] == 1).collect()
+        } else {
+            axes.iter()
+                .map(|&axis| normalize_axis(axis, dims.len()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| self.shape_error("squeeze", err))?
+        };
+        resolved.sort_unstable();
+        resolved.dedup();
+
+        if let Some(&axis) = resolved.iter().find(|&&axis| dims[axis] != 1) {
+            let source = ShapeError::NotSqueezable(axis, dims[axis]);
+            return Err(self.shape_error("squeeze", source));
+        }
+
+        let squeezed = dims
+            .iter()
+            .enumerate()
+            .filter(|(axis, _)| !resolved.contains(axis))
+            .map(|(_, &dim)| dim)
+            .collect::<Vec<_>>();
+
+        self.view(Operation::Squeeze { axes: resolved }, squeezed.into())
+    }
+
+    /// Inserts size-1 dimensions at `axes`, which index the result.
+    /// Negative axes count from the end of the result.
+    pub fn unsqueeze(&self, axes: &[isize]) -> Result<GraphTensor, GraphError> {
+        let shape = self.shape();
+        let rank = shape.dims().len() + axes.len();
+
+        let invalid = |source| self.shape_error("unsqueeze", source);
+
+        let mut resolved = axes
+            .iter()
+            .map(|&axis| normalize_axis(axis, rank))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+        resolved.sort_unstable();
+        if let Some(pair) = resolved.windows(2).find(|pair| pair[0] == pair[1])
+        {
+            return Err(invalid(ShapeError::DuplicateAxis(pair[0])));
+        }
+
+        let mut existing = shape.dims().iter();
+        let unsqueezed = (0..rank)
+            .map(|axis| match resolved.contains(&axis) {
+                true => Dim::Fixed(1),
+                false => *existing.next().unwrap(),
+            })
+            .collect::<Vec<_>>();
+
+        self.view(Operation::Unsqueeze { axes: resolved }, unsqueezed.into())
+    }
+
+    /// Broadcasts to `shape` without copying: size-1 dimensions repeat and
+    /// missing leading dimensions are added.
+    pub fn expand(
+        &self,
+        shape: impl Into<Shape>,
+    ) -> Result<GraphTensor, GraphError> {
+        let shape = shape.into();
+
+        match self.shape().broadcast_with(&shape) {
+            Ok(broadcast) if broadcast == shape => self.view(
+                Operation::Expand {
+                    shape: shape.clone(),
+                },
+                shape,
+            ),
+            _ => Err(self.shape_error(
+                "expand",
+                ShapeError::NotExpandable(self.shape(), shape),
+            )),
+        }
+    }
+
+    fn permuted(&self, axes: Vec<usize>) -> Result<GraphTensor, GraphError> {
+        let dims = self.shape().dims().to_vec();
+        let shape = axes.iter().map(|&axis| dims[axis]).collect::<Vec<_>>();
+
+        self.view(Operation::Permute { axes }, shape.into())
+    }
+
+    fn view(
+        &self,
+        op: Operation,
+        shape: Shape,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            self.node_id(),
+            op,
+            shape.clone(),
+        );
+
+        Ok(GraphTensor::new(graph_rc, node_id, shape, self.dtype()))
+    }
+}
+
+/// Resolves a negative axis against `rank`.
+pub(super) fn normalize_axis(
+    axis: isize,
+    rank: usize,
+) -> Result<usize, ShapeError> {
+    let resolved = if axis < 0 { axis + rank as isize } else { axis };
+
+    match (0..rank as isize).contains(&resolved) {
+        true => Ok(resolved as usize),
+        false => Err(ShapeError::AxisOutOfRange(axis, rank)),
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+};
+
+use super::shape::normalize_axis;
+
+impl GraphTensor {
+    /// `exp(x) / sum(exp(x))` along `axis`, which may count from the end.
+    /// Large inputs do not overflow, as every lane is shifted by its maximum
+    /// first. Integer inputs are converted to floats. Fails on inputs the
+    /// graph cannot convert to floats and on axes out of range.
+    pub fn try_softmax(&self, axis: isize) -> Result<GraphTensor, GraphError> {
+        self.normalize("softmax", axis, |axis| Operation::Softmax { axis })
+    }
+
+    /// `log(softmax(x))` along `axis`, computed as `x - log(sum(exp(x)))`
+    /// so that it stays finite where the probabilities underflow; see
+    /// [`GraphTensor::try_softmax`].
+    pub fn try_log_softmax(
+        &self,
+        axis: isize,
+    ) -> Result<GraphTensor, GraphError> {
+        self.normalize("log_softmax", axis, |axis| Operation::LogSoftmax {
+            axis,
+        })
+    }
+
+    /// Panics where [`GraphTensor::try_softmax`] fails.
+    pub fn softmax(&self, axis: isize) -> GraphTensor {
+        self.try_softmax(axis).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_log_softmax`] fails.
+    pub fn log_softmax(&self, axis: isize) -> GraphTensor {
+        self.try_log_softmax(axis)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn normalize(
+        &self,
+        name: &'static str,
+        axis: isize,
+        op: impl FnOnce(usize) -> Operation,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+        let input = self.float_operand(name)?;
+
+        let axis = normalize_axis(axis, input.shape().dims().len())
+            .map_err(|err| self.shape_error(name, err))?;
+        let result_shape = input.shape();
+
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            input.node_id(),
+            op(axis),
+            result_shape.clone(),
+        );
+
+        Ok(GraphTensor::new(
+            graph_rc,
+            node_id,
+            result_shape,
+            input.dtype(),
+        ))
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::Neg;
+
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+};
+
+impl GraphTensor {
+    /// Elementwise negation.
+    pub fn try_neg(&self) -> Result<GraphTensor, GraphError> {
+        self.numeric_unary(Operation::Neg, "neg")
+    }
+
+    /// Elementwise absolute value.
+    pub fn try_abs(&self) -> Result<GraphTensor, GraphError> {
+        self.numeric_unary(Operation::Abs, "abs")
+    }
+
+    /// Elementwise `e^x`.
+    pub fn try_exp(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Exp, "exp")
+    }
+
+    /// Elementwise natural logarithm.
+    pub fn try_log(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Log, "log")
+    }
+
+    /// Elementwise square root.
+    pub fn try_sqrt(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Sqrt, "sqrt")
+    }
+
+    /// Elementwise `1 / sqrt(x)`.
+    pub fn try_rsqrt(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Rsqrt, "rsqrt")
+    }
+
+    /// Elementwise sine.
+    pub fn try_sin(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Sin, "sin")
+    }
+
+    /// Elementwise cosine.
+    pub fn try_cos(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Cos, "cos")
+    }
+
+    /// Elementwise hyperbolic tangent.
+    pub fn try_tanh(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Tanh, "tanh")
+    }
+
+    /// Elementwise logistic function `1 / (1 + e^-x)`.
+    pub fn try_sigmoid(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Sigmoid, "sigmoid")
+    }
+
+    /// Elementwise `max(x, 0)`.
+    pub fn try_relu(&self) -> Result<GraphTensor, GraphError> {
+        self.numeric_unary(Operation::Relu, "relu")
+    }
+
+    /// Elementwise exact GELU, `x * Φ(x)`.
+    pub fn try_gelu(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Gelu, "gelu")
+    }
+
+    /// Elementwise error function.
+    pub fn try_erf(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Erf, "erf")
+    }
+
+    /// Elementwise floor.
+    pub fn try_floor(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Floor, "floor")
+    }
+
+    /// Elementwise ceiling.
+    pub fn try_ceil(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Ceil, "ceil")
+    }
+
+    /// Elementwise rounding, half to even.
+    pub fn try_round(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Round, "round")
+    }
+
+    /// Elementwise sign: -1, 0 or 1.
+    pub fn try_sign(&self) -> Result<GraphTensor, GraphError> {
+        self.numeric_unary(Operation::Sign, "sign")
+    }
+
+    /// Elementwise `1 / x`.
+    pub fn try_reciprocal(&self) -> Result<GraphTensor, GraphError> {
+        self.float_unary(Operation::Reciprocal, "reciprocal")
+    }
+
+    /// Panics where [`GraphTensor::try_neg`] fails.
+    pub fn neg(&self) -> GraphTensor {
+        self.try_neg().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_abs`] fails.
+    pub fn abs(&self) -> GraphTensor {
+        self.try_abs().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_exp`] fails.
+    pub fn exp(&self) -> GraphTensor {
+        self.try_exp().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_log`] fails.
+    pub fn log(&self) -> GraphTensor {
+        self.try_log().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_sqrt`] fails.
+    pub fn sqrt(&self) -> GraphTensor {
+        self.try_sqrt().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_rsqrt`] fails.
+    pub fn rsqrt(&self) -> GraphTensor {
+        self.try_rsqrt().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_sin`] fails.
+    pub fn sin(&self) -> GraphTensor {
+        self.try_sin().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_cos`] fails.
+    pub fn cos(&self) -> GraphTensor {
+        self.try_cos().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_tanh`] fails.
+    pub fn tanh(&self) -> GraphTensor {
+        self.try_tanh().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_sigmoid`] fails.
+    pub fn sigmoid(&self) -> GraphTensor {
+        self.try_sigmoid().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_relu`] fails.
+    pub fn relu(&self) -> GraphTensor {
+        self.try_relu().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_gelu`] fails.
+    pub fn gelu(&self) -> GraphTensor {
+        self.try_gelu().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_erf`] fails.
+    pub fn erf(&self) -> GraphTensor {
+        self.try_erf().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_floor`] fails.
+    pub fn floor(&self) -> GraphTensor {
+        self.try_floor().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_ceil`] fails.
+    pub fn ceil(&self) -> GraphTensor {
+        self.try_ceil().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_round`] fails.
+    pub fn round(&self) -> GraphTensor {
+        self.try_round().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_sign`] fails.
+    pub fn sign(&self) -> GraphTensor {
+        self.try_sign().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Panics where [`GraphTensor::try_reciprocal`] fails.
+    pub fn reciprocal(&self) -> GraphTensor {
+        self.try_reciprocal().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// A tensor of ones with the shape and type of `self`.
+    pub fn ones_like(&self) -> GraphTensor {
+        self.unary(Operation::OnesLike)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// A tensor of zeros with the shape and type of `self`.
+    pub fn zeros_like(&self) -> GraphTensor {
+        self.unary(Operation::ZerosLike)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// A tensor with the shape and type of `self` filled with `value`,
+    /// converted to that type.
+    pub fn full_like(&self, value: f64) -> GraphTensor {
+        self.unary(Operation::FullLike { value })
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Applies an operation that only has float kernels, converting integer
+    /// inputs first. Fails on inputs the graph cannot convert.
+    fn float_unary(
+        &self,
+        op: Operation,
+        name: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        self.float_operand(name)?.unary(op)
+    }
+
+    /// Applies an operation that has no bool kernel. Fails on bool inputs.
+    fn numeric_unary(
+        &self,
+        op: Operation,
+        name: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        self.numeric_operand(name)?.unary(op)
+    }
+
+    fn unary(&self, op: Operation) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let result_shape = self.shape();
+
+        let node_id = graph_rc.borrow_mut().add_unary_op(
+            self.node_id(),
+            op,
+            result_shape.clone(),
+        );
+
+        Ok(GraphTensor::new(
+            graph_rc,
+            node_id,
+            result_shape,
+            self.dtype(),
+        ))
+    }
+}
+
+impl Neg for GraphTensor {
+    type Output = GraphTensor;
+
+    fn neg(self) -> Self::Output {
+        GraphTensor::neg(&self)
+    }
+}
+
+impl Neg for &GraphTensor {
+    type Output = GraphTensor;
+
+    fn neg(self) -> Self::Output {
+        GraphTensor::neg(self)
+    }
+}
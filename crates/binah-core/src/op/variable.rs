@@ -0,0 +1,72 @@
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    op::Operation,
+};
+
+impl GraphTensor {
+    /// Overwrites this variable with `value`, which must broadcast to its
+    /// shape, and returns the new value. The write happens whenever the
+    /// returned tensor is computed and persists across executions of the
+    /// same [`GraphExecutable`](crate::GraphExecutable).
+    pub fn assign(
+        &self,
+        value: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.update(value, Operation::Assign, "assign")
+    }
+
+    /// Adds `value` to this variable; see [`GraphTensor::assign`].
+    pub fn assign_add(
+        &self,
+        value: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.update(value, Operation::AssignAdd, "assign_add")
+    }
+
+    fn update(
+        &self,
+        value: &GraphTensor,
+        op: Operation,
+        name: &'static str,
+    ) -> Result<GraphTensor, GraphError> {
+        let graph_rc = self.graph()?;
+
+        let is_variable = matches!(
+            graph_rc.borrow().graph().node_weight(self.node_id()),
+            Some(Operation::Variable)
+        );
+        if !is_variable {
+            return Err(GraphError::NotAVariable {
+                op: name,
+                node: self.node_id(),
+            });
+        }
+
+        match value.shape().broadcast_with(&self.shape()) {
+            Ok(shape) if shape == self.shape() => {}
+            _ => {
+                return Err(GraphError::IncompatibleShapes {
+                    op: name,
+                    node: value.node_id(),
+                    lhs: self.shape(),
+                    rhs: value.shape(),
+                });
+            }
+        }
+        let value = value.assignable(name, self.dtype())?;
+
+        let node_id = graph_rc.borrow_mut().add_binary_op(
+            self.node_id(),
+            value.node_id(),
+            op,
+            self.shape(),
+        );
+
+        Ok(GraphTensor::new(
+            graph_rc,
+            node_id,
+            self.shape(),
+            self.dtype(),
+        ))
+    }
+}
@@ -0,0 +1,154 @@
+use crate::graph::{Graph, GraphError, tensor::GraphTensor};
+
+use super::{LearningRate, Optimizer, Slot, Step, minimize_with};
+
+/// Adam with bias-corrected moment estimates and optional L2 weight decay
+/// added to the gradient, following PyTorch's `Adam`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Adam {
+    lr: LearningRate,
+    betas: (f64, f64),
+    eps: f64,
+    weight_decay: f64,
+}
+
+/// Adam with weight decay applied to the parameter directly instead of
+/// through the moments, following PyTorch's `AdamW`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdamW {
+    adam: Adam,
+}
+
+impl Adam {
+    /// Adam with betas `(0.9, 0.999)`, epsilon `1e-8` and no weight decay.
+    pub fn new(lr: impl Into<LearningRate>) -> Self {
+        Self {
+            lr: lr.into(),
+            betas: (0.9, 0.999),
+            eps: 1e-8,
+            weight_decay: 0.0,
+        }
+    }
+
+    /// The decay rates of the first and second moment estimates.
+    pub fn with_betas(mut self, beta1: f64, beta2: f64) -> Self {
+        self.betas = (beta1, beta2);
+        self
+    }
+
+    /// The term added to the denominator for stability.
+    pub fn with_eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Adds `weight_decay * param` to every gradient.
+    pub fn with_weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+
+    /// The bias-corrected step direction `m / (sqrt(v) + eps)`.
+    fn direction(
+        &self,
+        graph: &mut Graph,
+        slot: &Slot,
+        grad: &GraphTensor,
+        state: &mut Vec<GraphTensor>,
+    ) -> Result<GraphTensor, GraphError> {
+        let (beta1, beta2) = self.betas;
+        let (shape, dtype) = (slot.param.shape(), slot.param.dtype());
+
+        let m = graph.zeros_variable(shape.clone(), dtype);
+        let v = graph.zeros_variable(shape, dtype);
+        let m_new = m.assign(&(&m * beta1 + &(grad * (1.0 - beta1))))?;
+        let v_new =
+            v.assign(&(&v * beta2 + &(&(grad * grad) * (1.0 - beta2))))?;
+        state.extend([m, v]);
+
+        let correction1 = 1.0 - (&slot.t * beta1.ln()).exp();
+        let correction2 = 1.0 - (&slot.t * beta2.ln()).exp();
+        let denominator = (&v_new / &correction2).sqrt() + self.eps;
+
+        Ok(&m_new / &correction1 / denominator)
+    }
+}
+
+impl AdamW {
+    /// AdamW with betas `(0.9, 0.999)`, epsilon `1e-8` and weight decay
+    /// `0.01`.
+    pub fn new(lr: impl Into<LearningRate>) -> Self {
+        Self {
+            adam: Adam::new(lr).with_weight_decay(0.01),
+        }
+    }
+
+    /// The decay rates of the first and second moment estimates.
+    pub fn with_betas(self, beta1: f64, beta2: f64) -> Self {
+        Self {
+            adam: self.adam.with_betas(beta1, beta2),
+        }
+    }
+
+    /// The term added to the denominator for stability.
+    pub fn with_eps(self, eps: f64) -> Self {
+        Self {
+            adam: self.adam.with_eps(eps),
+        }
+    }
+
+    /// Shrinks every parameter by `lr * weight_decay * param` per step.
+    pub fn with_weight_decay(self, weight_decay: f64) -> Self {
+        Self {
+            adam: self.adam.with_weight_decay(weight_decay),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn minimize(
+        &self,
+        graph: &mut Graph,
+        params: &[&GraphTensor],
+        grads: &[GraphTensor],
+    ) -> Result<Step, GraphError> {
+        minimize_with(
+            graph,
+            "adam",
+            &self.lr,
+            params,
+            grads,
+            |graph, slot, state| {
+                let grad = slot.decayed_grad(self.weight_decay);
+                let direction = self.direction(graph, slot, &grad, state)?;
+                slot.descend(&direction)
+            },
+        )
+    }
+}
+
+impl Optimizer for AdamW {
+    fn minimize(
+        &self,
+        graph: &mut Graph,
+        params: &[&GraphTensor],
+        grads: &[GraphTensor],
+    ) -> Result<Step, GraphError> {
+        let adam = &self.adam;
+        minimize_with(
+            graph,
+            "adamw",
+            &adam.lr,
+            params,
+            grads,
+            |graph, slot, state| {
+                let mut direction =
+                    adam.direction(graph, slot, slot.grad, state)?;
+                if adam.weight_decay != 0.0 {
+                    direction = direction + &(slot.param * adam.weight_decay);
+                }
+                slot.descend(&direction)
+            },
+        )
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    graph::{Graph, GraphError, tensor::GraphTensor},
+    tensor::{shape::Shape, storage::DType},
+};
+
+mod adam;
+mod rmsprop;
+mod schedule;
+mod sgd;
+
+pub use adam::{Adam, AdamW};
+pub use rmsprop::RmsProp;
+pub use schedule::LearningRate;
+pub use sgd::Sgd;
+
+/// An update rule that turns gradients into in-graph writes to variables.
+pub trait Optimizer {
+    /// Adds one update of `params`, which must be float variables, given
+    /// one gradient per parameter in the same order, as returned by
+    /// [`Graph::gradients`]. The update happens each time the returned
+    /// [`Step`] is executed. Fails if the counts differ or the learning
+    /// rate schedule has a negative decay factor.
+    fn minimize(
+        &self,
+        graph: &mut Graph,
+        params: &[&GraphTensor],
+        grads: &[GraphTensor],
+    ) -> Result<Step, GraphError>;
+}
+
+/// The nodes of one optimizer update.
+#[derive(Clone, Debug)]
+pub struct Step {
+    /// The new value of each parameter, in the order given.
+    pub params: Vec<GraphTensor>,
+    /// The number of updates made so far, including this one.
+    pub count: GraphTensor,
+    /// The variables holding the optimizer's state, the step counter first,
+    /// for saving with [`GraphExecutable::get_variable`] and restoring with
+    /// [`GraphExecutable::set_variable`].
+    ///
+    /// [`GraphExecutable::get_variable`]: crate::GraphExecutable::get_variable
+    /// [`GraphExecutable::set_variable`]: crate::GraphExecutable::set_variable
+    pub state: Vec<GraphTensor>,
+}
+
+impl Step {
+    /// Every tensor that has to be computed for the update to happen.
+    pub fn targets(&self) -> Vec<&GraphTensor> {
+        self.params.iter().chain([&self.count]).collect()
+    }
+}
+
+/// What an update rule sees of one parameter.
+struct Slot<'a> {
+    param: &'a GraphTensor,
+    grad: &'a GraphTensor,
+    /// The learning rate of this step, a scalar of the parameter's dtype.
+    lr: GraphTensor,
+    /// The step count starting from 1, a scalar of the parameter's dtype.
+    t: GraphTensor,
+}
+
+impl Slot<'_> {
+    /// The gradient with L2 weight decay folded in.
+    fn decayed_grad(&self, weight_decay: f64) -> GraphTensor {
+        if weight_decay == 0.0 {
+            self.grad.clone()
+        } else {
+            self.grad + &(self.param * weight_decay)
+        }
+    }
+
+    /// Writes `param - lr * direction` back to the parameter.
+    fn descend(
+        &self,
+        direction: &GraphTensor,
+    ) -> Result<GraphTensor, GraphError> {
+        self.param.assign(&(self.param - &(direction * &self.lr)))
+    }
+}
+
+/// Creates the step counter and learning rate, then applies `update` to
+/// each parameter. `update` pushes any state variable it creates.
+fn minimize_with<F>(
+    graph: &mut Graph,
+    op: &'static str,
+    lr: &LearningRate,
+    params: &[&GraphTensor],
+    grads: &[GraphTensor],
+    mut update: F,
+) -> Result<Step, GraphError>
+where
+    F: FnMut(
+        &mut Graph,
+        &Slot,
+        &mut Vec<GraphTensor>,
+    ) -> Result<GraphTensor, GraphError>,
+{
+    if params.len() != grads.len() {
+        return Err(GraphError::GradientCountMismatch {
+            op,
+            params: params.len(),
+            grads: grads.len(),
+        });
+    }
+
+    lr.validate(op)?;
+
+    let counter = graph.zeros_variable(Shape::new([]), DType::I64);
+    let count = counter.assign_add(&counter.ones_like())?;
+    let t = count.cast(DType::F64);
+    let rate = lr.at(&t);
+
+    let mut state = vec![counter];
+    let mut updated = Vec::with_capacity(params.len());
+    for (param, grad) in params.iter().zip(grads) {
+        let dtype = param.dtype();
+        if !dtype.is_float() {
+            return Err(GraphError::UnsupportedDtype {
+                op,
+                node: param.node_id(),
+                dtype,
+            });
+        }
+
+        let slot = Slot {
+            param,
+            grad,
+            lr: rate.cast(dtype),
+            t: t.cast(dtype),
+        };
+        updated.push(update(graph, &slot, &mut state)?);
+    }
+
+    Ok(Step {
+        params: updated,
+        count,
+        state,
+    })
+}
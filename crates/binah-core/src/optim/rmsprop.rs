@@ -0,0 +1,92 @@
+use crate::graph::{Graph, GraphError, tensor::GraphTensor};
+
+use super::{LearningRate, Optimizer, Step, minimize_with};
+
+/// RMSProp, which divides each gradient by a running root mean square of
+/// past gradients, with optional momentum and L2 weight decay, following
+/// PyTorch's `RMSprop`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RmsProp {
+    lr: LearningRate,
+    alpha: f64,
+    eps: f64,
+    momentum: f64,
+    weight_decay: f64,
+}
+
+impl RmsProp {
+    /// RMSProp with smoothing constant `0.99`, epsilon `1e-8`, no momentum
+    /// and no weight decay.
+    pub fn new(lr: impl Into<LearningRate>) -> Self {
+        Self {
+            lr: lr.into(),
+            alpha: 0.99,
+            eps: 1e-8,
+            momentum: 0.0,
+            weight_decay: 0.0,
+        }
+    }
+
+    /// The decay rate of the running mean of squared gradients.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// The term added to the denominator for stability.
+    pub fn with_eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Keeps a velocity of the scaled gradients and steps along it.
+    pub fn with_momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// Adds `weight_decay * param` to every gradient.
+    pub fn with_weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn minimize(
+        &self,
+        graph: &mut Graph,
+        params: &[&GraphTensor],
+        grads: &[GraphTensor],
+    ) -> Result<Step, GraphError> {
+        minimize_with(
+            graph,
+            "rmsprop",
+            &self.lr,
+            params,
+            grads,
+            |graph, slot, state| {
+                let (shape, dtype) = (slot.param.shape(), slot.param.dtype());
+                let grad = slot.decayed_grad(self.weight_decay);
+
+                let square_avg = graph.zeros_variable(shape.clone(), dtype);
+                let updated = square_avg.assign(
+                    &(&square_avg * self.alpha
+                        + &(&(&grad * &grad) * (1.0 - self.alpha))),
+                )?;
+                state.push(square_avg);
+                let scaled = &grad / &(updated.sqrt() + self.eps);
+
+                if self.momentum == 0.0 {
+                    return slot.descend(&scaled);
+                }
+
+                let velocity = graph.zeros_variable(shape, dtype);
+                let updated =
+                    velocity.assign(&(&velocity * self.momentum + &scaled))?;
+                state.push(velocity);
+                slot.descend(&updated)
+            },
+        )
+    }
+}
@@ -0,0 +1,107 @@
+use std::num::NonZeroUsize;
+
+use crate::{
+    graph::{GraphError, tensor::GraphTensor},
+    tensor::storage::DType,
+};
+
+/// How the learning rate changes with the step count `t`, which starts
+/// from 1. Decay factors `gamma` must not be negative, which optimizers
+/// check when they build the schedule.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LearningRate {
+    /// The same rate at every step.
+    Constant(f64),
+    /// `initial * gamma^floor((t - 1) / every)`.
+    StepDecay {
+        initial: f64,
+        gamma: f64,
+        every: NonZeroUsize,
+    },
+    /// `initial * gamma^(t - 1)`.
+    Exponential { initial: f64, gamma: f64 },
+    /// Anneals from `initial` to `min` along half a cosine over `steps`
+    /// steps, then stays at `min`.
+    Cosine {
+        initial: f64,
+        min: f64,
+        steps: NonZeroUsize,
+    },
+    /// `schedule` scaled linearly from `1 / steps` up to 1 over the first
+    /// `steps` steps.
+    Warmup {
+        steps: NonZeroUsize,
+        schedule: Box<LearningRate>,
+    },
+}
+
+impl From<f64> for LearningRate {
+    fn from(lr: f64) -> Self {
+        LearningRate::Constant(lr)
+    }
+}
+
+impl LearningRate {
+    /// Fails when a decay factor is negative or NaN.
+    pub(crate) fn validate(&self, op: &'static str) -> Result<(), GraphError> {
+        match self {
+            LearningRate::StepDecay { gamma, .. }
+            | LearningRate::Exponential { gamma, .. }
+                if *gamma < 0.0 || gamma.is_nan() =>
+            {
+                Err(GraphError::InvalidDecay { op, gamma: *gamma })
+            }
+            LearningRate::Warmup { schedule, .. } => schedule.validate(op),
+            _ => Ok(()),
+        }
+    }
+
+    /// The rate at step `t`, an f64 scalar. Assumes the schedule passed
+    /// [`LearningRate::validate`].
+    pub(crate) fn at(&self, t: &GraphTensor) -> GraphTensor {
+        match self {
+            LearningRate::Constant(lr) => t.full_like(*lr),
+            LearningRate::StepDecay {
+                initial,
+                gamma,
+                every,
+            } => {
+                let decays = ((t - 1.0) / every.get() as f64).floor();
+                power(*gamma, &decays) * *initial
+            }
+            LearningRate::Exponential { initial, gamma } => {
+                power(*gamma, &(t - 1.0)) * *initial
+            }
+            LearningRate::Cosine {
+                initial,
+                min,
+                steps,
+            } => {
+                let elapsed = minimum(&(t - 1.0), steps.get() as f64);
+                let progress =
+                    elapsed * (std::f64::consts::PI / steps.get() as f64);
+                (progress.cos() + 1.0) * ((initial - min) / 2.0) + *min
+            }
+            LearningRate::Warmup { steps, schedule } => {
+                let scale = minimum(&(t / steps.get() as f64), 1.0);
+                schedule.at(t) * scale
+            }
+        }
+    }
+}
+
+/// `gamma.powf(exponent)` for a whole, non-negative `exponent` and a
+/// `gamma` that is not negative.
+fn power(gamma: f64, exponent: &GraphTensor) -> GraphTensor {
+    if gamma == 0.0 {
+        // 0^0 is 1 and every later power is 0
+        return exponent.equal(&exponent.zeros_like()).cast(DType::F64);
+    }
+    (exponent * gamma.ln()).exp()
+}
+
+/// `x.min(bound)` elementwise.
+fn minimum(x: &GraphTensor, bound: f64) -> GraphTensor {
+    let bound = x.full_like(bound);
+    &bound - &(&bound - x).relu()
+}
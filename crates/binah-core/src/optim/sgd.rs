@@ -0,0 +1,78 @@
+use crate::graph::{Graph, GraphError, tensor::GraphTensor};
+
+use super::{LearningRate, Optimizer, Step, minimize_with};
+
+/// Stochastic gradient descent with optional momentum, Nesterov momentum
+/// and L2 weight decay, following PyTorch's `SGD`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sgd {
+    lr: LearningRate,
+    momentum: f64,
+    nesterov: bool,
+    weight_decay: f64,
+}
+
+impl Sgd {
+    pub fn new(lr: impl Into<LearningRate>) -> Self {
+        Self {
+            lr: lr.into(),
+            momentum: 0.0,
+            nesterov: false,
+            weight_decay: 0.0,
+        }
+    }
+
+    /// Keeps a velocity `v = momentum * v + grad` per parameter and steps
+    /// along it.
+    pub fn with_momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// Steps along `grad + momentum * v` instead; needs a momentum.
+    pub fn with_nesterov(mut self, nesterov: bool) -> Self {
+        self.nesterov = nesterov;
+        self
+    }
+
+    /// Adds `weight_decay * param` to every gradient.
+    pub fn with_weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+}
+
+impl Optimizer for Sgd {
+    fn minimize(
+        &self,
+        graph: &mut Graph,
+        params: &[&GraphTensor],
+        grads: &[GraphTensor],
+    ) -> Result<Step, GraphError> {
+        minimize_with(
+            graph,
+            "sgd",
+            &self.lr,
+            params,
+            grads,
+            |graph, slot, state| {
+                let grad = slot.decayed_grad(self.weight_decay);
+                if self.momentum == 0.0 {
+                    return slot.descend(&grad);
+                }
+
+                let velocity = graph
+                    .zeros_variable(slot.param.shape(), slot.param.dtype());
+                let updated =
+                    velocity.assign(&(&velocity * self.momentum + &grad))?;
+                state.push(velocity);
+
+                if self.nesterov {
+                    slot.descend(&(&grad + &(&updated * self.momentum)))
+                } else {
+                    slot.descend(&updated)
+                }
+            },
+        )
+    }
+}
@@ -0,0 +1,278 @@
+use super::shape::BroadcastError;
+
+/// Where the elements of a logical tensor sit in a flat buffer: element
+/// `[i, j, ..]` lives at `offset + i * strides[0] + j * strides[1] + ..`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    offset: usize,
+}
+
+impl Layout {
+    pub fn new(shape: Vec<usize>, strides: Vec<usize>, offset: usize) -> Self {
+        Self {
+            shape,
+            strides,
+            offset,
+        }
+    }
+
+    pub fn contiguous(shape: &[usize]) -> Self {
+        Self {
+            shape: shape.to_vec(),
+            strides: contiguous_strides(shape),
+            offset: 0,
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn num_elements(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Whether the elements are laid out row-major without gaps. Strides of
+    /// size-1 dimensions are irrelevant.
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+
+        for (&dim, &stride) in self.shape.iter().zip(&self.strides).rev() {
+            if dim != 1 && stride != expected {
+                return false;
+            }
+            expected *= dim;
+        }
+
+        true
+    }
+
+    /// Buffer position of the element at row-major position `linear_idx`.
+    pub(crate) fn index(&self, linear_idx: usize) -> usize {
+        self.offset + compute_index(linear_idx, &self.strides, &self.shape)
+    }
+
+    /// Strides that read this view as if it were broadcast to `target`.
+    pub(crate) fn broadcast_strides(
+        &self,
+        target: &[usize],
+    ) -> Result<Vec<usize>, BroadcastError> {
+        if self.shape.len() > target.len() {
+            return Err(BroadcastError::DimensionMismatch);
+        }
+
+        let offset = target.len() - self.shape.len();
+        let mut strides = vec![0; target.len()];
+
+        for (i, (&dim, &stride)) in
+            self.shape.iter().zip(&self.strides).enumerate()
+        {
+            let target_dim = target[offset + i];
+            strides[offset + i] = match dim {
+                _ if dim == target_dim => stride,
+                1 => 0,
+                _ => {
+                    return Err(BroadcastError::IncompatibleShapes(
+                        dim.into(),
+                        target_dim.into(),
+                    ));
+                }
+            };
+        }
+
+        Ok(strides)
+    }
+
+    pub(crate) fn broadcast_to(
+        &self,
+        target: &[usize],
+    ) -> Result<Layout, BroadcastError> {
+        Ok(Self {
+            shape: target.to_vec(),
+            strides: self.broadcast_strides(target)?,
+            offset: self.offset,
+        })
+    }
+
+    /// `axes` must be a permutation of `0..rank`.
+    pub(crate) fn permute(&self, axes: &[usize]) -> Layout {
+        Self {
+            shape: axes.iter().map(|&axis| self.shape[axis]).collect(),
+            strides: axes.iter().map(|&axis| self.strides[axis]).collect(),
+            offset: self.offset,
+        }
+    }
+
+    /// Restricts leading axes to `(start, len, step)` ranges, which must lie
+    /// within the axes.
+    pub(crate) fn slice(&self, ranges: &[(usize, usize, usize)]) -> Layout {
+        let mut layout = self.clone();
+
+        for (axis, &(start, len, step)) in ranges.iter().enumerate() {
+            if len > 0 {
+                layout.offset += start * self.strides[axis];
+            }
+            layout.shape[axis] = len;
+            layout.strides[axis] *= step;
+        }
+
+        layout
+    }
+
+    /// Removes `axes`, which must all have size 1.
+    pub(crate) fn squeeze(&self, axes: &[usize]) -> Layout {
+        let (shape, strides) = self
+            .shape
+            .iter()
+            .zip(&self.strides)
+            .enumerate()
+            .filter(|(axis, _)| !axes.contains(axis))
+            .map(|(_, (&dim, &stride))| (dim, stride))
+            .unzip();
+
+        Self {
+            shape,
+            strides,
+            offset: self.offset,
+        }
+    }
+
+    /// Inserts size-1 dimensions at `axes`, given as positions in the
+    /// result.
+    pub(crate) fn unsqueeze(&self, axes: &[usize]) -> Layout {
+        let rank = self.shape.len() + axes.len();
+        let mut existing = self.shape.iter().zip(&self.strides);
+
+        let (shape, strides) = (0..rank)
+            .map(|axis| match axes.contains(&axis) {
+                true => (1, 0),
+                false => {
+                    let (&dim, &stride) = existing.next().unwrap();
+                    (dim, stride)
+                }
+            })
+            .unzip();
+
+        Self {
+            shape,
+            strides,
+            offset: self.offset,
+        }
+    }
+
+    /// A view of the same elements with `shape`, or `None` when the
+    /// elements cannot be expressed with strides and need copying first.
+    /// `shape` must have as many elements as this layout.
+    pub(crate) fn reshape(&self, shape: &[usize]) -> Option<Layout> {
+        if self.is_contiguous() || self.num_elements() == 0 {
+            return Some(Self {
+                offset: self.offset,
+                ..Self::contiguous(shape)
+            });
+        }
+
+        // Match runs of old dimensions to runs of new dimensions with the
+        // same product; each old run must itself be contiguous.
+        let old: Vec<(usize, usize)> = self
+            .shape
+            .iter()
+            .zip(&self.strides)
+            .filter(|&(&dim, _)| dim != 1)
+            .map(|(&dim, &stride)| (dim, stride))
+            .collect();
+        let mut strides = vec![0; shape.len()];
+
+        let (mut old_start, mut old_end) = (0, 1);
+        let (mut new_start, mut new_end) = (0, 1);
+        while new_start < shape.len() && old_start < old.len() {
+            let mut new_size = shape[new_start];
+            let mut old_size = old[old_start].0;
+            while new_size != old_size {
+                if new_size < old_size {
+                    new_size *= shape[new_end];
+                    new_end += 1;
+                } else {
+                    old_size *= old[old_end].0;
+                    old_end += 1;
+                }
+            }
+
+            for k in old_start..old_end - 1 {
+                if old[k].1 != old[k + 1].0 * old[k + 1].1 {
+                    return None;
+                }
+            }
+
+            strides[new_end - 1] = old[old_end - 1].1;
+            for k in (new_start + 1..new_end).rev() {
+                strides[k - 1] = strides[k] * shape[k];
+            }
+
+            new_start = new_end;
+            new_end += 1;
+            old_start = old_end;
+            old_end += 1;
+        }
+
+        // Trailing size-1 dimensions
+        let last_stride = match new_start {
+            0 => 1,
+            _ => strides[new_start - 1],
+        };
+        for stride in &mut strides[new_start..] {
+            *stride = last_stride;
+        }
+
+        Some(Self {
+            shape: shape.to_vec(),
+            strides,
+            offset: self.offset,
+        })
+    }
+}
+
+/// Row-major strides of a tensor of `shape` without gaps.
+pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![0; shape.len()];
+    let mut stride = 1;
+
+    for i in (0..shape.len()).rev() {
+        strides[i] = stride;
+        stride *= shape[i];
+    }
+
+    strides
+}
+
+pub(crate) fn compute_index(
+    linear_idx: usize,
+    strides: &[usize],
+    shape: &[usize],
+) -> usize {
+    let mut index = 0;
+    let mut remaining = linear_idx;
+
+    for i in 0..shape.len() {
+        let dim_size = shape[i];
+        let stride = strides[i];
+
+        // Calculate size of remaining dimensions
+        let next_size: usize = shape[i + 1..].iter().product();
+
+        let coord = (remaining / next_size) % dim_size;
+        index += coord * stride;
+        remaining %= next_size;
+    }
+
+    index
+}
@@ -2,10 +2,14 @@ use num_traits::{One, Zero};
 use shape::Shape;
 use storage::{IntoStorage, TensorStorage};
 
+pub(crate) mod layout;
 pub mod shape;
 
 pub(crate) mod storage;
+pub(crate) mod view;
 
+/// Data together with its shape, which must be fixed: building or storing a
+/// tensor of a symbolic shape panics.
 #[derive(Debug, Clone)]
 pub struct Tensor<T>
 where
@@ -21,30 +25,46 @@ where
 {
     pub fn with_capacity(shape: Shape) -> Self {
         Self {
-            data: Vec::with_capacity(shape.num_elements()),
+            data: Vec::with_capacity(fixed_num_elements(&shape)),
             shape,
         }
     }
 
     pub fn zeros(shape: Shape) -> Self {
         Self {
-            data: vec![T::zero(); shape.num_elements()],
+            data: vec![T::zero(); fixed_num_elements(&shape)],
             shape,
         }
     }
 
     pub fn ones(shape: Shape) -> Self {
         Self {
-            data: vec![T::one(); shape.num_elements()],
+            data: vec![T::one(); fixed_num_elements(&shape)],
             shape,
         }
     }
+}
 
+impl<T> Tensor<T>
+where
+    T: IntoStorage,
+{
     pub fn from_data(data: Vec<T>, shape: Shape) -> Self {
         Self { data, shape }
     }
 
     pub fn into_storage(self) -> TensorStorage {
-        T::into_storage(self.data, self.shape.into())
+        let shape = self
+            .shape
+            .fixed_dims()
+            .expect("A tensor holding data must have a fixed shape");
+
+        T::into_storage(self.data, shape)
     }
 }
+
+fn fixed_num_elements(shape: &Shape) -> usize {
+    shape
+        .num_elements()
+        .expect("A tensor holding data must have a fixed shape")
+}
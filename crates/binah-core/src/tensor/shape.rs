@@ -1,52 +1,234 @@
-#[derive(Clone, Debug, PartialEq)]
+use std::collections::HashMap;
+
+/// The size of one axis: fixed, or a named size that is bound when the graph
+/// is executed, such as a batch size. Axes sharing a name share a size.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dim {
+    Fixed(usize),
+    Sym(&'static str),
+}
+
+impl Dim {
+    /// The size, unless it is symbolic.
+    pub fn fixed(self) -> Option<usize> {
+        match self {
+            Dim::Fixed(size) => Some(size),
+            Dim::Sym(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for Dim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dim::Fixed(size) => write!(f, "{size}"),
+            Dim::Sym(name) => f.write_str(name),
+        }
+    }
+}
+
+impl From<usize> for Dim {
+    fn from(size: usize) -> Self {
+        Dim::Fixed(size)
+    }
+}
+
+impl PartialEq<usize> for Dim {
+    fn eq(&self, size: &usize) -> bool {
+        *self == Dim::Fixed(*size)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Shape {
-    pub(crate) dims: Vec<usize>,
+    pub(crate) dims: Vec<Dim>,
 }
 
 #[derive(Debug, Clone)]
 pub enum BroadcastError {
-    IncompatibleShapes(usize, usize),
+    IncompatibleShapes(Dim, Dim),
     DimensionMismatch,
 }
 
+/// Why a shape manipulation cannot be applied to a tensor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    /// Reshaping to a shape with a different number of elements.
+    ElementCountMismatch(Shape, Shape),
+    /// An axis outside a tensor of the given rank.
+    AxisOutOfRange(isize, usize),
+    /// Axes that are not a permutation of every axis of the tensor.
+    InvalidPermutation(Vec<isize>),
+    /// Squeezing an axis whose size is not 1.
+    NotSqueezable(usize, Dim),
+    /// The same axis given twice where each must be distinct.
+    DuplicateAxis(usize),
+    /// Expanding to a shape the tensor does not broadcast to.
+    NotExpandable(Shape, Shape),
+    /// A slice step that is not positive.
+    InvalidStep(isize),
+    /// A convolution stride, as height and width, with a zero in it.
+    InvalidStride(usize, usize),
+    /// An operand of the wrong rank; holds the expected and actual rank.
+    RankMismatch(usize, usize),
+    /// Operands whose shapes do not fit together.
+    IncompatibleShapes(Shape, Shape),
+    /// Split sizes that do not add up to the size of the split axis.
+    InvalidSplit(Vec<usize>, usize),
+    /// An axis whose size must be fixed has a symbolic one.
+    SymbolicDim(usize, Dim),
+}
+
+impl std::fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::ElementCountMismatch(from, to) => write!(
+                f,
+                "Cannot reshape {:?} into {:?}",
+                from.dims(),
+                to.dims()
+            ),
+            ShapeError::AxisOutOfRange(axis, rank) => write!(
+                f,
+                "Axis {axis} is out of range for a tensor of rank {rank}"
+            ),
+            ShapeError::InvalidPermutation(axes) => {
+                write!(f, "{axes:?} is not a permutation of the axes")
+            }
+            ShapeError::NotSqueezable(axis, dim) => {
+                write!(f, "Cannot squeeze axis {axis} of size {dim:?}")
+            }
+            ShapeError::DuplicateAxis(axis) => {
+                write!(f, "Axis {axis} is given more than once")
+            }
+            ShapeError::NotExpandable(from, to) => write!(
+                f,
+                "Cannot expand {:?} to {:?}",
+                from.dims(),
+                to.dims()
+            ),
+            ShapeError::InvalidStep(step) => {
+                write!(f, "Slice step must be positive, got {step}")
+            }
+            ShapeError::InvalidStride(height, width) => write!(
+                f,
+                "Convolution stride must be positive, got ({height}, {width})"
+            ),
+            ShapeError::RankMismatch(expected, actual) => {
+                write!(f, "Expected a tensor of rank {expected}, got {actual}")
+            }
+            ShapeError::IncompatibleShapes(lhs, rhs) => write!(
+                f,
+                "Incompatible shapes {:?} and {:?}",
+                lhs.dims(),
+                rhs.dims()
+            ),
+            ShapeError::InvalidSplit(sizes, dim) => write!(
+                f,
+                "Split sizes {sizes:?} do not add up to the axis size {dim}"
+            ),
+            ShapeError::SymbolicDim(axis, dim) => {
+                write!(f, "Axis {axis} has the symbolic size {dim:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
 impl Shape {
     pub fn new<const D: usize>(dims: [usize; D]) -> Self {
         // For backward compat
         Self {
-            dims: dims.to_vec(),
+            dims: dims.into_iter().map(Dim::Fixed).collect(),
         }
     }
 
-    pub fn dims(&self) -> &[usize] {
+    pub fn dims(&self) -> &[Dim] {
         &self.dims
     }
 
+    pub fn rank(&self) -> usize {
+        self.dims.len()
+    }
+
     pub fn is_scalar(&self) -> bool {
         self.dims.is_empty() || (self.dims.len() == 1 && self.dims[0] == 1)
     }
 
-    pub fn num_elements(&self) -> usize {
-        self.dims.iter().product()
+    pub fn is_symbolic(&self) -> bool {
+        self.dims.iter().any(|dim| matches!(dim, Dim::Sym(_)))
     }
 
-    pub fn contiguous_strides(&self) -> Vec<usize> {
-        let n = self.dims.len();
+    /// The sizes of every axis, unless any is symbolic.
+    pub fn fixed_dims(&self) -> Option<Vec<usize>> {
+        self.dims.iter().map(|dim| dim.fixed()).collect()
+    }
 
-        let mut strides = vec![0; n];
-        let mut stride = 1;
+    /// `None` when any axis is symbolic.
+    pub fn num_elements(&self) -> Option<usize> {
+        self.dims.iter().map(|dim| dim.fixed()).product()
+    }
 
-        for i in (0..n).rev() {
-            strides[i] = stride;
-            stride *= self.dims[i];
-        }
+    /// The fixed sizes with every symbol replaced by its size in `bindings`,
+    /// or the first symbol that has none.
+    pub(crate) fn resolve(
+        &self,
+        bindings: &HashMap<&'static str, usize>,
+    ) -> Result<Vec<usize>, &'static str> {
+        self.dims
+            .iter()
+            .map(|dim| match *dim {
+                Dim::Fixed(size) => Ok(size),
+                Dim::Sym(name) => bindings.get(name).copied().ok_or(name),
+            })
+            .collect()
+    }
 
-        strides
+    /// Whether both shapes hold the same number of elements for any sizes
+    /// of their symbols.
+    pub(crate) fn same_num_elements(&self, other: &Shape) -> bool {
+        let count = |shape: &Shape| {
+            let mut symbols = Vec::new();
+            let mut size = 1;
+            for dim in &shape.dims {
+                match *dim {
+                    Dim::Fixed(fixed) => size *= fixed,
+                    Dim::Sym(name) => symbols.push(name),
+                }
+            }
+            symbols.sort_unstable();
+
+            // Nothing is left to count once a fixed size is zero.
+            if size == 0 { (0, Vec::new()) } else { (size, symbols) }
+        };
+
+        count(self) == count(other)
+    }
+
+    /// Shape left after reducing over `axes`, which must be sorted, unique
+    /// and in range. Reduced dimensions become 1 when `keepdims` is set.
+    pub(crate) fn reduced(&self, axes: &[usize], keepdims: bool) -> Shape {
+        let dims = self
+            .dims
+            .iter()
+            .enumerate()
+            .filter_map(|(axis, &dim)| match axes.contains(&axis) {
+                true if keepdims => Some(Dim::Fixed(1)),
+                true => None,
+                false => Some(dim),
+            })
+            .collect();
+
+        Shape { dims }
     }
 
     pub fn can_broadcast_with(&self, other: &Shape) -> bool {
         self.broadcast_with(other).is_ok()
     }
 
+    /// NumPy broadcasting. A symbol only broadcasts with itself and with 1,
+    /// since its size is not known until execution.
     pub fn broadcast_with(&self, other: &Shape) -> Result<Shape, BroadcastError> {
         let self_dims = &self.dims;
         let other_dims = &other.dims;
@@ -58,19 +240,19 @@ impl Shape {
             let self_dim = if i < self_dims.len() {
                 self_dims[self_dims.len() - 1 - i]
             } else {
-                1
+                Dim::Fixed(1)
             };
             
             let other_dim = if i < other_dims.len() {
                 other_dims[other_dims.len() - 1 - i]
             } else {
-                1
+                Dim::Fixed(1)
             };
             
             let result_dim = match (self_dim, other_dim) {
                 (a, b) if a == b => a,
-                (1, b) => b,
-                (a, 1) => a,
+                (Dim::Fixed(1), b) => b,
+                (a, Dim::Fixed(1)) => a,
                 (a, b) => return Err(BroadcastError::IncompatibleShapes(a, b)),
             };
             
@@ -81,26 +263,127 @@ impl Shape {
         Ok(Shape { dims: result_dims })
     }
 
-    pub fn compute_broadcast_strides(&self, target_shape: &Shape) -> Result<Vec<usize>, BroadcastError> {
-        if !self.can_broadcast_with(target_shape) {
+    pub fn matmul_with(&self, other: &Shape) -> Result<Shape, BroadcastError> {
+        self.matmul_with_transposed(other, false, false)
+    }
+
+    /// Output shape of a matrix product where either operand may have its
+    /// last two dimensions swapped. 1-D operands are treated as vectors and
+    /// their dimension is dropped from the result; leading batch dimensions
+    /// broadcast.
+    pub(crate) fn matmul_with_transposed(
+        &self,
+        other: &Shape,
+        transpose_lhs: bool,
+        transpose_rhs: bool,
+    ) -> Result<Shape, BroadcastError> {
+        if self.dims.is_empty() || other.dims.is_empty() {
             return Err(BroadcastError::DimensionMismatch);
         }
-        
-        let self_strides = self.contiguous_strides();
-        let mut broadcast_strides = vec![0; target_shape.dims.len()];
-        
-        let self_offset = target_shape.dims.len().saturating_sub(self.dims.len());
-        
-        for (i, &dim) in self.dims.iter().enumerate() {
-            let target_idx = self_offset + i;
-            if dim == 1 && target_shape.dims[target_idx] != 1 {
-                broadcast_strides[target_idx] = 0;
-            } else {
-                broadcast_strides[target_idx] = self_strides[i];
-            }
+
+        let lhs_dims = self.transposed_matrix(transpose_lhs);
+        let rhs_dims = other.transposed_matrix(transpose_rhs);
+
+        let (lhs_batch, lhs_matrix) =
+            lhs_dims.split_at(lhs_dims.len().saturating_sub(2));
+        let (rhs_batch, rhs_matrix) =
+            rhs_dims.split_at(rhs_dims.len().saturating_sub(2));
+
+        let lhs_inner = lhs_matrix[lhs_matrix.len() - 1];
+        let rhs_inner = rhs_matrix[0];
+        if lhs_inner != rhs_inner {
+            return Err(BroadcastError::IncompatibleShapes(
+                lhs_inner, rhs_inner,
+            ));
         }
-        
-        Ok(broadcast_strides)
+
+        let batch =
+            Shape::from(lhs_batch).broadcast_with(&Shape::from(rhs_batch))?;
+
+        let mut result_dims = batch.dims;
+        if lhs_matrix.len() == 2 {
+            result_dims.push(lhs_matrix[0]);
+        }
+        if rhs_matrix.len() == 2 {
+            result_dims.push(rhs_matrix[1]);
+        }
+
+        Ok(Shape { dims: result_dims })
+    }
+
+    fn transposed_matrix(&self, transpose: bool) -> Vec<Dim> {
+        let mut dims = self.dims.clone();
+        let n = dims.len();
+        if transpose && n >= 2 {
+            dims.swap(n - 2, n - 1);
+        }
+
+        dims
+    }
+}
+
+/// A Python-style slice of one axis. Negative bounds count from the end of
+/// the axis and bounds past either end are clamped.
+///
+/// Unlike Python, the step must be positive: slices are views, and view
+/// strides cannot run backwards, so an axis cannot be reversed by slicing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Slice {
+    pub start: Option<isize>,
+    pub stop: Option<isize>,
+    pub step: isize,
+}
+
+impl Slice {
+    pub fn new(start: Option<isize>, stop: Option<isize>, step: isize) -> Self {
+        Self { start, stop, step }
+    }
+
+    /// Every element of the axis.
+    pub fn full() -> Self {
+        Self::new(None, None, 1)
+    }
+
+    pub fn with_step(self, step: isize) -> Self {
+        Self { step, ..self }
+    }
+
+    /// First index and element count of this slice over an axis of size
+    /// `dim`. The step must be positive.
+    pub(crate) fn resolve(&self, dim: usize) -> (usize, usize) {
+        let clamp = |bound: isize| {
+            let bound = if bound < 0 { bound + dim as isize } else { bound };
+            bound.clamp(0, dim as isize) as usize
+        };
+        let start = self.start.map_or(0, clamp);
+        let stop = self.stop.map_or(dim, clamp);
+        let step = self.step as usize;
+
+        (start, stop.saturating_sub(start).div_ceil(step))
+    }
+}
+
+impl From<std::ops::Range<isize>> for Slice {
+    fn from(range: std::ops::Range<isize>) -> Self {
+        Self::new(Some(range.start), Some(range.end), 1)
+    }
+}
+
+impl From<std::ops::RangeFrom<isize>> for Slice {
+    fn from(range: std::ops::RangeFrom<isize>) -> Self {
+        Self::new(Some(range.start), None, 1)
+    }
+}
+
+impl From<std::ops::RangeTo<isize>> for Slice {
+    fn from(range: std::ops::RangeTo<isize>) -> Self {
+        Self::new(None, Some(range.end), 1)
+    }
+}
+
+impl From<std::ops::RangeFull> for Slice {
+    fn from(_: std::ops::RangeFull) -> Self {
+        Self::full()
     }
 }
 
@@ -110,28 +393,44 @@ impl<const D: usize> From<[usize; D]> for Shape {
     }
 }
 
+impl<const D: usize> From<[Dim; D]> for Shape {
+    fn from(dims: [Dim; D]) -> Self {
+        Self {
+            dims: dims.to_vec(),
+        }
+    }
+}
+
 impl From<&[usize]> for Shape {
     fn from(dims: &[usize]) -> Self {
-        Shape { dims: dims.into() }
+        Shape {
+            dims: dims.iter().copied().map(Dim::Fixed).collect(),
+        }
     }
 }
 
 impl From<Vec<usize>> for Shape {
     fn from(shape: Vec<usize>) -> Self {
-        Self { dims: shape }
+        Shape::from(shape.as_slice())
     }
 }
 
 impl From<&Vec<usize>> for Shape {
     fn from(shape: &Vec<usize>) -> Self {
+        Shape::from(shape.as_slice())
+    }
+}
+
+impl From<&[Dim]> for Shape {
+    fn from(dims: &[Dim]) -> Self {
         Self {
-            dims: shape.clone(),
+            dims: dims.to_vec(),
         }
     }
 }
 
-impl From<Shape> for Vec<usize> {
-    fn from(shape: Shape) -> Self {
-        shape.dims
+impl From<Vec<Dim>> for Shape {
+    fn from(dims: Vec<Dim>) -> Self {
+        Self { dims }
     }
 }
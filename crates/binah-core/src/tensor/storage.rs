@@ -1,3 +1,122 @@
+/// The element type of a tensor, one per [`TensorStorage`] variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DType {
+    Bool,
+
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+
+    F32,
+    F64,
+}
+
+impl DType {
+    pub fn is_float(self) -> bool {
+        matches!(self, DType::F32 | DType::F64)
+    }
+
+    /// Whether this is a signed or unsigned integer type.
+    pub fn is_integer(self) -> bool {
+        !self.is_float() && self != DType::Bool
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(
+            self,
+            DType::I8 | DType::I16 | DType::I32 | DType::I64 | DType::I128
+        )
+    }
+
+    /// Bytes one element takes in memory.
+    pub fn size_of(self) -> usize {
+        match self {
+            DType::Bool => 1,
+            dtype => dtype.bits() as usize / 8,
+        }
+    }
+
+    fn bits(self) -> u32 {
+        match self {
+            DType::Bool => 1,
+            DType::U8 | DType::I8 => 8,
+            DType::U16 | DType::I16 => 16,
+            DType::U32 | DType::I32 | DType::F32 => 32,
+            DType::U64 | DType::I64 | DType::F64 => 64,
+            DType::U128 | DType::I128 => 128,
+        }
+    }
+
+    /// The type both `self` and `other` convert to when they meet in one
+    /// operation, following NumPy: the smallest type that holds every value
+    /// of both, where `f32` holds integers of up to 16 bits and anything
+    /// wider goes to `f64`.
+    pub fn promote(self, other: DType) -> DType {
+        let (narrow, wide) = match self.bits() <= other.bits() {
+            true => (self, other),
+            false => (other, self),
+        };
+
+        match (narrow, wide) {
+            _ if self == other => self,
+            (DType::Bool, _) => wide,
+            (_, DType::F64) => DType::F64,
+            (_, DType::F32) => match narrow.bits() {
+                ..=16 => DType::F32,
+                _ => DType::F64,
+            },
+            (DType::F32, _) => match wide.bits() {
+                ..=16 => DType::F32,
+                _ => DType::F64,
+            },
+            _ if narrow.is_signed() == wide.is_signed() => wide,
+            // Mixed signedness needs a signed type wider than the unsigned
+            // operand.
+            _ if wide.is_signed() && wide.bits() > narrow.bits() => wide,
+            _ => match narrow.bits().max(wide.bits()) {
+                8 => DType::I16,
+                16 => DType::I32,
+                32 => DType::I64,
+                64 => DType::I128,
+                _ => DType::F64,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for DType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DType::Bool => "bool",
+
+            DType::U8 => "u8",
+            DType::U16 => "u16",
+            DType::U32 => "u32",
+            DType::U64 => "u64",
+            DType::U128 => "u128",
+
+            DType::I8 => "i8",
+            DType::I16 => "i16",
+            DType::I32 => "i32",
+            DType::I64 => "i64",
+            DType::I128 => "i128",
+
+            DType::F32 => "f32",
+            DType::F64 => "f64",
+        };
+
+        f.write_str(name)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum TensorStorage {
     Bool { data: Vec<bool>, shape: Vec<usize> },
@@ -39,6 +158,117 @@ impl TensorStorage {
             TensorStorage::F64 { shape, .. } => shape,
         }
     }
+
+    pub fn dtype(&self) -> DType {
+        match self {
+            TensorStorage::Bool { .. } => DType::Bool,
+
+            TensorStorage::U8 { .. } => DType::U8,
+            TensorStorage::U16 { .. } => DType::U16,
+            TensorStorage::U32 { .. } => DType::U32,
+            TensorStorage::U64 { .. } => DType::U64,
+            TensorStorage::U128 { .. } => DType::U128,
+
+            TensorStorage::I8 { .. } => DType::I8,
+            TensorStorage::I16 { .. } => DType::I16,
+            TensorStorage::I32 { .. } => DType::I32,
+            TensorStorage::I64 { .. } => DType::I64,
+            TensorStorage::I128 { .. } => DType::I128,
+
+            TensorStorage::F32 { .. } => DType::F32,
+            TensorStorage::F64 { .. } => DType::F64,
+        }
+    }
+
+    /// The number of elements held, which matches `shape` in a well-formed
+    /// storage.
+    pub fn len(&self) -> usize {
+        match self {
+            TensorStorage::Bool { data, .. } => data.len(),
+
+            TensorStorage::U8 { data, .. } => data.len(),
+            TensorStorage::U16 { data, .. } => data.len(),
+            TensorStorage::U32 { data, .. } => data.len(),
+            TensorStorage::U64 { data, .. } => data.len(),
+            TensorStorage::U128 { data, .. } => data.len(),
+
+            TensorStorage::I8 { data, .. } => data.len(),
+            TensorStorage::I16 { data, .. } => data.len(),
+            TensorStorage::I32 { data, .. } => data.len(),
+            TensorStorage::I64 { data, .. } => data.len(),
+            TensorStorage::I128 { data, .. } => data.len(),
+
+            TensorStorage::F32 { data, .. } => data.len(),
+            TensorStorage::F64 { data, .. } => data.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A storage of `dtype` filled with zeros, or `false` for bools.
+    pub fn zeros(dtype: DType, shape: Vec<usize>) -> Self {
+        let len = shape.iter().product();
+
+        match dtype {
+            DType::Bool => TensorStorage::Bool {
+                data: vec![false; len],
+                shape,
+            },
+
+            DType::U8 => TensorStorage::U8 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::U16 => TensorStorage::U16 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::U32 => TensorStorage::U32 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::U64 => TensorStorage::U64 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::U128 => TensorStorage::U128 {
+                data: vec![0; len],
+                shape,
+            },
+
+            DType::I8 => TensorStorage::I8 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::I16 => TensorStorage::I16 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::I32 => TensorStorage::I32 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::I64 => TensorStorage::I64 {
+                data: vec![0; len],
+                shape,
+            },
+            DType::I128 => TensorStorage::I128 {
+                data: vec![0; len],
+                shape,
+            },
+
+            DType::F32 => TensorStorage::F32 {
+                data: vec![0.0; len],
+                shape,
+            },
+            DType::F64 => TensorStorage::F64 {
+                data: vec![0.0; len],
+                shape,
+            },
+        }
+    }
 }
 
 pub trait IntoStorage: Clone + std::fmt::Debug + 'static {
@@ -0,0 +1,59 @@
+use std::rc::Rc;
+
+use super::{layout::Layout, storage::TensorStorage};
+
+/// A tensor as seen through a [`Layout`] over shared storage. Reshaping,
+/// permuting and broadcasting produce new views of the same buffer instead
+/// of copying it.
+#[derive(Clone, Debug)]
+pub struct TensorView {
+    storage: Rc<TensorStorage>,
+    layout: Layout,
+}
+
+impl TensorView {
+    pub fn new(storage: Rc<TensorStorage>, layout: Layout) -> Self {
+        Self { storage, layout }
+    }
+
+    pub fn storage(&self) -> &TensorStorage {
+        &self.storage
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        self.layout.shape()
+    }
+
+    /// The storage itself, if no other view shares it.
+    pub(crate) fn into_storage(self) -> Option<TensorStorage> {
+        Rc::try_unwrap(self.storage).ok()
+    }
+
+    /// The storage, to write to, if no other view shares it.
+    pub(crate) fn storage_mut(&mut self) -> Option<&mut TensorStorage> {
+        Rc::get_mut(&mut self.storage)
+    }
+
+    /// Another view of the same storage.
+    pub(crate) fn with_layout(&self, layout: Layout) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            layout,
+        }
+    }
+}
+
+impl From<TensorStorage> for TensorView {
+    fn from(storage: TensorStorage) -> Self {
+        let layout = Layout::contiguous(storage.shape());
+
+        Self {
+            storage: Rc::new(storage),
+            layout,
+        }
+    }
+}
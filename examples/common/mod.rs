@@ -0,0 +1,22 @@
+//! Helpers shared by the examples, each of which uses only some of them.
+#![allow(dead_code)]
+
+use binah_core::{Graph, GraphTensor};
+use std::collections::HashMap;
+
+/// Compiles `tensor` alone and checks its printed result.
+pub fn check(
+    graph: &mut Graph,
+    name: &str,
+    tensor: &GraphTensor,
+    expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut executable = graph.compile(&[tensor])?;
+    let results = executable.execute(HashMap::new())?;
+    let actual = format!("{:?}", results[tensor]);
+
+    println!("{name}: {actual}");
+    assert_eq!(actual, expected, "{name}");
+
+    Ok(())
+}
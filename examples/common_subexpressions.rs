@@ -0,0 +1,112 @@
+use binah_core::{
+    CompileOptions, DType, Graph, Shape, TensorKey, TensorStorage,
+};
+use std::collections::HashMap;
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn feed(data: Vec<f32>) -> HashMap<TensorKey, TensorStorage> {
+    HashMap::from([(
+        "x".into(),
+        TensorStorage::F32 {
+            data,
+            shape: vec![3],
+        },
+    )])
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Common Subexpressions Test ===");
+
+    // The same sum built three times is computed once
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([3]), DType::F32)?;
+    let b = graph.variable(vec![1.0f32, 2.0, 3.0], Shape::from([3]));
+    let first = &x + &b;
+    let second = &x + &b;
+    let third = &x + &b;
+    // Each scaling adds a fill and a product, which merge as well
+    let total = &(&first * 0.5) + &(&(&second * 0.5) + &third);
+
+    let mut merged = graph.compile(&[&total])?;
+    let mut unmerged = graph.compile_with(
+        &[&total],
+        CompileOptions {
+            eliminate_common_subexpressions: false,
+            ..CompileOptions::default()
+        },
+    )?;
+    println!("merged {} nodes", merged.merged_nodes());
+    // Two sums, one fill and one product
+    assert_eq!(merged.merged_nodes(), 4);
+    assert_eq!(unmerged.merged_nodes(), 0);
+
+    let data = vec![1.0f32, -1.0, 0.5];
+    let expected = floats(&unmerged.execute(feed(data.clone()))?[&total]);
+    assert_eq!(floats(&merged.execute(feed(data))?[&total]), expected);
+    assert_eq!(expected, [4.0, 2.0, 7.0]);
+
+    // Targets keep their own results even when they repeat each other
+    let mut executable = graph.compile(&[&first, &second])?;
+    assert_eq!(executable.merged_nodes(), 0);
+    let results = executable.execute(feed(vec![0.0, 0.0, 0.0]))?;
+    assert_eq!(floats(&results[&first]), floats(&results[&second]));
+
+    // Fills of 0 and -0 differ, e.g. under a reciprocal
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([3]), DType::F32)?;
+    let positive = x.full_like(0.0).reciprocal();
+    let negative = x.full_like(-0.0).reciprocal();
+    let both = &positive - &negative;
+    let mut executable = graph.compile(&[&both])?;
+    assert_eq!(executable.merged_nodes(), 0);
+    let results = executable.execute(feed(vec![1.0, 2.0, 3.0]))?;
+    assert_eq!(floats(&results[&both]), [f32::INFINITY; 3]);
+
+    // Assignments each take effect
+    let mut graph = Graph::new();
+    let counter = graph.variable(vec![0.0f32], Shape::from([1]));
+    let one = graph.constant(vec![1.0f32], Shape::from([1]));
+    let first = counter.assign_add(&one)?;
+    let second = counter.assign_add(&one)?;
+    let mut executable = graph.compile(&[&first, &second])?;
+    assert_eq!(executable.merged_nodes(), 0);
+    executable.execute(HashMap::new())?;
+    assert_eq!(floats(executable.get_variable(&counter).unwrap()), [2.0]);
+
+    // Gradients, which repeat many subexpressions, are unchanged
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![0.5f32, -1.5, 2.0], Shape::from([3]));
+    let x = graph.placeholder("x", Shape::from([3]), DType::F32)?;
+    let h = (&w * &x).tanh();
+    let loss = (&(&h * &h) + &(&w * &w).tanh()).sum(&[], false);
+    let grads = graph.gradients(&loss, &[&w])?;
+    let mut merged = graph.compile(&[&loss, &grads[0]])?;
+    let mut unmerged = graph.compile_with(
+        &[&loss, &grads[0]],
+        CompileOptions {
+            eliminate_common_subexpressions: false,
+            ..CompileOptions::default()
+        },
+    )?;
+    println!("merged {} gradient nodes", merged.merged_nodes());
+    assert!(merged.merged_nodes() > 0);
+    let data = vec![1.0f32, 0.25, -0.75];
+    let merged_results = merged.execute(feed(data.clone()))?;
+    let unmerged_results = unmerged.execute(feed(data))?;
+    for tensor in [&loss, &grads[0]] {
+        assert_eq!(
+            floats(&merged_results[tensor]),
+            floats(&unmerged_results[tensor])
+        );
+    }
+
+    println!("✓ repeated subexpressions are computed once");
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use binah_core::{Graph, Shape};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Concat Test ===");
+
+    let mut graph = Graph::new();
+    // [[0, 1, 2], [3, 4, 5]]
+    let a = graph.constant((0..6i32).collect::<Vec<_>>(), Shape::from([2, 3]));
+    let b = graph.constant(vec![6i32, 7, 8], Shape::from([1, 3]));
+    let c = graph.constant(vec![10i32, 11], Shape::from([2, 1]));
+    let e =
+        graph.constant((10..16i32).collect::<Vec<_>>(), Shape::from([2, 3]));
+
+    let rows = graph.concat(&[&a, &b], 0)?;
+    check(
+        &mut graph,
+        "concat axis 0",
+        &rows,
+        "I32 { data: [0, 1, 2, 3, 4, 5, 6, 7, 8], shape: [3, 3] }",
+    )?;
+    let columns = graph.concat(&[&a, &c], -1)?;
+    check(
+        &mut graph,
+        "concat axis -1",
+        &columns,
+        "I32 { data: [0, 1, 2, 10, 3, 4, 5, 11], shape: [2, 4] }",
+    )?;
+    let single = graph.concat(&[&b], 0)?;
+    check(
+        &mut graph,
+        "concat one tensor",
+        &single,
+        "I32 { data: [6, 7, 8], shape: [1, 3] }",
+    )?;
+    // Operands are promoted to a common dtype
+    let wide = graph.constant(vec![6i64, 7, 8], Shape::from([1, 3]));
+    let promoted = graph.concat(&[&a, &wide], 0)?;
+    check(
+        &mut graph,
+        "concat i32 and i64",
+        &promoted,
+        "I64 { data: [0, 1, 2, 3, 4, 5, 6, 7, 8], shape: [3, 3] }",
+    )?;
+
+    let stacked = graph.stack(&[&a, &e], 0)?;
+    check(
+        &mut graph,
+        "stack axis 0",
+        &stacked,
+        "I32 { data: [0, 1, 2, 3, 4, 5, 10, 11, 12, 13, 14, 15], \
+         shape: [2, 2, 3] }",
+    )?;
+    let interleaved = graph.stack(&[&a, &e], -1)?;
+    check(
+        &mut graph,
+        "stack axis -1",
+        &interleaved,
+        "I32 { data: [0, 10, 1, 11, 2, 12, 3, 13, 4, 14, 5, 15], \
+         shape: [2, 3, 2] }",
+    )?;
+
+    // Concatenating, stacking and splitting need shapes that line up
+    assert!(graph.concat(&[], 0).is_err());
+    assert!(graph.concat(&[&a, &c], 0).is_err());
+    assert!(graph.concat(&[&a, &a.reshape([6])?], 0).is_err());
+    assert!(graph.concat(&[&a, &b], 2).is_err());
+    assert!(graph.stack(&[&a, &b], 0).is_err());
+    assert!(graph.stack(&[&a, &e], 3).is_err());
+
+    let x = graph.constant((0..10i32).collect::<Vec<_>>(), Shape::from([2, 5]));
+    let pieces = x.split(&[2, 3], 1)?;
+    assert_eq!(pieces.len(), 2);
+    check(
+        &mut graph,
+        "split first",
+        &pieces[0],
+        "I32 { data: [0, 1, 5, 6], shape: [2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "split second",
+        &pieces[1],
+        "I32 { data: [2, 3, 4, 7, 8, 9], shape: [2, 3] }",
+    )?;
+    // Pieces can be empty
+    let pieces = x.split(&[0, 2], 0)?;
+    check(
+        &mut graph,
+        "split empty piece",
+        &pieces[0],
+        "I32 { data: [], shape: [0, 5] }",
+    )?;
+    assert!(x.split(&[2, 2], 1).is_err());
+    assert!(x.split(&[1, 1], 2).is_err());
+
+    let t = graph.constant((0..7i32).collect::<Vec<_>>(), Shape::from([7]));
+    let chunks = t.chunk(3, 0)?;
+    let sizes: Vec<_> = chunks.iter().map(|c| c.shape()).collect();
+    assert_eq!(
+        sizes,
+        [Shape::from([3]), Shape::from([3]), Shape::from([1])]
+    );
+    check(
+        &mut graph,
+        "chunk last",
+        &chunks[2],
+        "I32 { data: [6], shape: [1] }",
+    )?;
+    // Too short an axis gives fewer pieces
+    let chunks = x.chunk(4, 0)?;
+    assert_eq!(chunks.len(), 2);
+    check(
+        &mut graph,
+        "chunk rows",
+        &chunks[1],
+        "I32 { data: [5, 6, 7, 8, 9], shape: [1, 5] }",
+    )?;
+    assert!(t.chunk(0, 0).is_err());
+
+    // Splitting undoes concatenating
+    let halves = rows.split(&[2, 1], 0)?;
+    check(
+        &mut graph,
+        "split of concat",
+        &halves[1],
+        "I32 { data: [6, 7, 8], shape: [1, 3] }",
+    )?;
+
+    println!("✓ concat, stack, split and chunk move the right elements");
+
+    Ok(())
+}
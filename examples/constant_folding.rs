@@ -0,0 +1,124 @@
+use binah_core::{
+    DType, Graph, Shape, TensorStorage,
+    graph::ExecutionError,
+    optim::{Optimizer, Sgd},
+};
+use std::collections::HashMap;
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Constant Folding Test ===");
+
+    // Everything computed from constants alone is evaluated once
+    let mut graph = Graph::new();
+    let a = graph.constant(vec![1.0f32, 2.0], Shape::from([2]));
+    let b = graph.constant(vec![3.0f32, 4.0], Shape::from([2]));
+    // Add, FullLike and Mul, then Exp
+    let scale = ((&a + &b) * 0.5).exp();
+    let x = graph.placeholder("x", Shape::from([2]), DType::F32)?;
+    let y = &x * &scale;
+
+    let mut executable = graph.compile(&[&y, &scale])?;
+    println!("folded {} nodes", executable.folded_nodes());
+    assert_eq!(executable.folded_nodes(), 4);
+
+    let expected_scale = [2.0f32.exp(), 3.0f32.exp()];
+    for feed in [[1.0f32, 1.0], [2.0, -1.0]] {
+        let results = executable.execute(HashMap::from([(
+            "x".into(),
+            TensorStorage::F32 {
+                data: feed.to_vec(),
+                shape: vec![2],
+            },
+        )]))?;
+        assert_eq!(
+            floats(&results[&y]),
+            [feed[0] * expected_scale[0], feed[1] * expected_scale[1]]
+        );
+        assert_eq!(floats(&results[&scale]), expected_scale);
+    }
+
+    // Views and multi-output operations of constants fold too
+    let mut graph = Graph::new();
+    let table = graph.constant(
+        (0..6).map(|i| i as f32).collect::<Vec<_>>(),
+        Shape::from([2, 3]),
+    );
+    let pieces = table.transpose(0, 1)?.split(&[1, 2], 0)?;
+    let x = graph.placeholder("x", Shape::from([1, 2]), DType::F32)?;
+    let sum = &x + &pieces[0];
+    let mut executable = graph.compile(&[&sum, &pieces[1]])?;
+    // Permute, Split and both outputs
+    assert_eq!(executable.folded_nodes(), 4);
+    let results = executable.execute(HashMap::from([(
+        "x".into(),
+        TensorStorage::F32 {
+            data: vec![10.0, 20.0],
+            shape: vec![1, 2],
+        },
+    )]))?;
+    assert_eq!(
+        format!("{:?}", results[&sum]),
+        "F32 { data: [10.0, 23.0], shape: [1, 2] }"
+    );
+    assert_eq!(
+        format!("{:?}", results[&pieces[1]]),
+        "F32 { data: [1.0, 4.0, 2.0, 5.0], shape: [2, 2] }"
+    );
+
+    // Variables change between executions, so nothing that reads them is
+    // folded, and training still converges
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![0.0f32], Shape::from([1]));
+    let target = graph.constant(vec![3.0f32], Shape::from([1]));
+    let two = graph.constant(vec![2.0f32], Shape::from([1]));
+    let error = &(&w - &target) * &two.log();
+    let loss = (&error * &error).sum(&[], false);
+    let grads = graph.gradients(&loss, &[&w])?;
+    let step = Sgd::new(1.0).minimize(&mut graph, &[&w], &grads)?;
+    let mut targets = step.targets();
+    targets.push(&loss);
+    let mut executable = graph.compile(&targets)?;
+    // Only the logarithm of the constant two
+    assert_eq!(executable.folded_nodes(), 1);
+    for _ in 0..50 {
+        executable.execute(HashMap::new())?;
+    }
+    let trained = floats(executable.get_variable(&w).unwrap());
+    println!("trained: {trained:?}");
+    assert!((trained[0] - 3.0).abs() < 1e-4);
+
+    // Random samples are never folded
+    let mut graph = Graph::new();
+    let like = graph.constant(vec![0.0f32; 4], Shape::from([4]));
+    let key = graph.constant(vec![7i64], Shape::new([]));
+    let noise = like.uniform_like(1, &key)?;
+    let executable = graph.compile(&[&noise])?;
+    assert_eq!(executable.folded_nodes(), 0);
+
+    // Kernel failures are still reported when executing
+    let mut graph = Graph::new();
+    let values = graph.constant(vec![1.0f32, 2.0, 3.0], Shape::from([3]));
+    let indices = graph.constant(vec![5i64], Shape::from([1]));
+    let picked = values.index_select(&indices, 0)?;
+    let mut executable = graph.compile(&[&picked])?;
+    assert_eq!(executable.folded_nodes(), 0);
+    let err = executable
+        .execute(HashMap::new())
+        .expect_err("index out of range");
+    println!("{err}");
+    let ExecutionError::Kernel { node, .. } = err else {
+        panic!("expected a kernel error");
+    };
+    assert_eq!(node, picked.node_id());
+
+    println!("✓ constant subgraphs are folded at compile time");
+
+    Ok(())
+}
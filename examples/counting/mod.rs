@@ -0,0 +1,47 @@
+//! A global allocator for the examples that check how much memory
+//! execution takes. Declaring `mod counting;` installs it.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Tracks the bytes currently allocated, the most there were since the
+/// last reset, and the total asked for.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        PEAK.fetch_max(live + layout.size(), Ordering::Relaxed);
+        TOTAL.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Runs `f`, returning its result with the most bytes it held at once on
+/// top of what was allocated before, and the bytes it asked for in all.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+    let before = LIVE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let total = TOTAL.load(Ordering::Relaxed);
+    let result = f();
+
+    (
+        result,
+        PEAK.load(Ordering::Relaxed) - before,
+        TOTAL.load(Ordering::Relaxed) - total,
+    )
+}
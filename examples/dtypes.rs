@@ -0,0 +1,101 @@
+use binah_core::{DType, Graph, GraphError, Shape, TypePromotion};
+use std::collections::HashMap;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Dtype Test ===");
+
+    // NumPy's promotion table
+    assert_eq!(DType::F32.promote(DType::F64), DType::F64);
+    assert_eq!(DType::I16.promote(DType::F32), DType::F32);
+    assert_eq!(DType::I32.promote(DType::F32), DType::F64);
+    assert_eq!(DType::U8.promote(DType::I8), DType::I16);
+    assert_eq!(DType::U8.promote(DType::I32), DType::I32);
+    assert_eq!(DType::U64.promote(DType::I64), DType::I128);
+    assert_eq!(DType::Bool.promote(DType::U16), DType::U16);
+
+    let mut graph = Graph::new();
+    let floats =
+        graph.constant(vec![1.5f32, -2.5, 0.0, 300.0], Shape::from([4]));
+    let ints = graph.constant(vec![1i32, 2, 3, 4], Shape::from([4]));
+
+    // Every node knows its dtype before anything runs
+    let sum = &floats + &ints;
+    let exp = ints.exp();
+    let small = floats.less(&ints);
+    let largest = floats.argmax(&[], false);
+    assert_eq!(sum.dtype(), DType::F64);
+    assert_eq!(exp.dtype(), DType::F64);
+    assert_eq!(small.dtype(), DType::Bool);
+    assert_eq!(largest.dtype(), DType::I64);
+
+    // Explicit casts convert like Rust's `as`
+    let truncated = floats.cast(DType::I32);
+    let wrapped = truncated.cast(DType::U8);
+    let nonzero = floats.cast(DType::Bool);
+    let ones = nonzero.cast(DType::F32);
+
+    let targets = [&sum, &small, &truncated, &wrapped, &nonzero, &ones];
+    let mut executable = graph.compile(&targets)?;
+    let results = executable.execute(HashMap::new())?;
+    let expected = [
+        "F64 { data: [2.5, -0.5, 3.0, 304.0], shape: [4] }",
+        "Bool { data: [false, true, true, false], shape: [4] }",
+        "I32 { data: [1, -2, 0, 300], shape: [4] }",
+        "U8 { data: [1, 254, 0, 44], shape: [4] }",
+        "Bool { data: [true, true, false, true], shape: [4] }",
+        "F32 { data: [1.0, 1.0, 0.0, 1.0], shape: [4] }",
+    ];
+    for (tensor, expected) in targets.iter().zip(expected) {
+        println!("{:?}", results[*tensor]);
+        assert_eq!(format!("{:?}", results[*tensor]), expected);
+    }
+
+    // Gradients flow back through casts in the input's own type
+    let w = graph.variable(vec![2.0f32], Shape::from([1]));
+    let x = graph.constant(vec![3.0f64], Shape::from([1]));
+    let loss = &w.cast(DType::F64) * &x;
+    let grads = graph.gradients(&loss, &[&w])?;
+    assert_eq!(grads[0].dtype(), DType::F32);
+    let mut executable = graph.compile(&[&grads[0]])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", results[&grads[0]]),
+        "F32 { data: [3.0], shape: [1] }"
+    );
+
+    // Strict graphs reject implicit casts
+    let mut strict = Graph::new();
+    strict.set_type_promotion(TypePromotion::Strict);
+    let floats = strict.constant(vec![1.0f32, 2.0], Shape::from([2]));
+    let ints = strict.constant(vec![0i32, 1], Shape::from([2]));
+
+    let err = floats.try_add(&ints).expect_err("mixed dtypes");
+    println!("{err}");
+    assert_eq!(
+        err.to_string(),
+        "Mixed dtypes f32 and i32 for add at node 1"
+    );
+    assert!(floats.try_add(&ints.cast(DType::F32)).is_ok());
+
+    // Some operands have a required type in every mode
+    let err = floats.index_select(&floats, 0).expect_err("float indices");
+    assert_eq!(
+        err,
+        GraphError::UnsupportedDtype {
+            op: "index_select",
+            node: floats.node_id(),
+            dtype: DType::F32,
+        }
+    );
+    let err = ints.select(&floats, &floats).expect_err("int condition");
+    println!("{err}");
+    assert_eq!(
+        err.to_string(),
+        "Unsupported dtype i32 for select at node 1"
+    );
+    assert!(ints.index_select(&ints, 0).is_ok());
+
+    println!("✓ dtypes are inferred, promoted and cast");
+
+    Ok(())
+}
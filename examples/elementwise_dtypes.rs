@@ -0,0 +1,110 @@
+use binah_core::{Graph, Shape};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Elementwise Dtypes Test ===");
+
+    let mut graph = Graph::new();
+
+    // Integer arithmetic wraps on overflow
+    let a = graph.constant(vec![250u8, 5, 0], Shape::from([3]));
+    let b = graph.constant(vec![10u8, 5, 1], Shape::from([3]));
+    check(
+        &mut graph,
+        "u8 add",
+        &(&a + &b),
+        "U8 { data: [4, 10, 1], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "u8 sub",
+        &(&b - &a),
+        "U8 { data: [16, 0, 1], shape: [3] }",
+    )?;
+
+    let a = graph.constant(vec![-128i8, 127], Shape::from([2]));
+    let b = graph.constant(vec![1i8, 1], Shape::from([2]));
+    check(
+        &mut graph,
+        "i8 sub",
+        &(&a - &b),
+        "I8 { data: [127, 126], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "i8 add",
+        &(&a + &b),
+        "I8 { data: [-127, -128], shape: [2] }",
+    )?;
+
+    let a = graph.constant(vec![i32::MAX, -7, 7, 5], Shape::from([4]));
+    let b = graph.constant(vec![2i32, 2, -2, 0], Shape::from([4]));
+    check(
+        &mut graph,
+        "i32 mul",
+        &(&a * &b),
+        "I32 { data: [-2, -14, -14, 0], shape: [4] }",
+    )?;
+    // Division truncates toward zero, and dividing by zero gives zero
+    check(
+        &mut graph,
+        "i32 div",
+        &(&a / &b),
+        "I32 { data: [1073741823, -3, -3, 0], shape: [4] }",
+    )?;
+
+    let a = graph.constant(vec![u64::MAX], Shape::from([1]));
+    let b = graph.constant(vec![1u64], Shape::from([1]));
+    check(
+        &mut graph,
+        "u64 add",
+        &(&a + &b),
+        "U64 { data: [0], shape: [1] }",
+    )?;
+
+    // Floats follow IEEE 754
+    let a = graph.constant(vec![1.0f64, -1.0, 0.0], Shape::from([3]));
+    let b = graph.constant(vec![0.0f64, 0.0, 4.0], Shape::from([3]));
+    check(
+        &mut graph,
+        "f64 div",
+        &(&a / &b),
+        "F64 { data: [inf, -inf, 0.0], shape: [3] }",
+    )?;
+
+    // Bool addition is OR and multiplication is AND; the others are
+    // rejected when building the graph
+    let a = graph.constant(vec![true, true, false, false], Shape::from([4]));
+    let b = graph.constant(vec![true, false, true, false], Shape::from([4]));
+    check(
+        &mut graph,
+        "bool add",
+        &(&a + &b),
+        "Bool { data: [true, true, true, false], shape: [4] }",
+    )?;
+    check(
+        &mut graph,
+        "bool mul",
+        &(&a * &b),
+        "Bool { data: [true, false, false, false], shape: [4] }",
+    )?;
+    assert!(a.try_sub(&b).is_err());
+    assert!(a.try_div(&b).is_err());
+
+    // Broadcasting works the same for every dtype
+    let column = graph.constant(vec![1i64, 2], Shape::from([2, 1]));
+    let row = graph.constant(vec![10i64, 20, 30], Shape::from([3]));
+    check(
+        &mut graph,
+        "i64 broadcast",
+        &(&column * &row),
+        "I64 { data: [10, 20, 30, 20, 40, 60], shape: [2, 3] }",
+    )?;
+
+    println!("✓ elementwise kernels cover every dtype");
+
+    Ok(())
+}
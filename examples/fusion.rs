@@ -0,0 +1,187 @@
+use binah_core::{
+    CompileOptions, DType, Graph, GraphExecutable, GraphTensor, Shape,
+    TensorKey, TensorStorage,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+mod counting;
+
+use counting::measure;
+
+const UNFUSED: CompileOptions = CompileOptions {
+    eliminate_common_subexpressions: true,
+    fuse_elementwise: false,
+};
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn input(
+    name: &str,
+    data: Vec<f32>,
+    shape: &[usize],
+) -> (TensorKey, TensorStorage) {
+    (
+        name.into(),
+        TensorStorage::F32 {
+            data,
+            shape: shape.to_vec(),
+        },
+    )
+}
+
+/// Runs both executables on the same inputs and checks that they agree on
+/// every target. Returns the bytes allocated and the time taken by each.
+fn compare(
+    fused: &mut GraphExecutable,
+    unfused: &mut GraphExecutable,
+    targets: &[&GraphTensor],
+    inputs: &HashMap<TensorKey, TensorStorage>,
+) -> [(usize, Duration); 2] {
+    let run = |executable: &mut GraphExecutable| {
+        let inputs = inputs.clone();
+        let start = Instant::now();
+        let (results, _, allocated) = measure(|| executable.execute(inputs));
+        let elapsed = start.elapsed();
+        let results = results.unwrap();
+        let values: Vec<_> = targets
+            .iter()
+            .map(|&target| format!("{:?}", results[target]))
+            .collect();
+        (values, allocated, elapsed)
+    };
+
+    let (fused_values, fused_bytes, fused_time) = run(fused);
+    let (unfused_values, unfused_bytes, unfused_time) = run(unfused);
+    assert_eq!(fused_values, unfused_values);
+
+    [(fused_bytes, fused_time), (unfused_bytes, unfused_time)]
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Fusion Test ===");
+
+    // (a + b) * c - d with broadcasting and a transposed operand
+    let mut graph = Graph::new();
+    let a = graph.placeholder("a", Shape::from([3, 4]), DType::F32)?;
+    let b = graph.placeholder("b", Shape::from([4]), DType::F32)?;
+    let c = graph.placeholder("c", Shape::from([4, 3]), DType::F32)?;
+    let d = graph.placeholder("d", Shape::from([3, 1]), DType::F32)?;
+    let chain = &(&(&a + &b) * &c.transpose(0, 1)?) - &d;
+    let smooth = ((&chain * 0.5).exp() + 1.0).log().tanh();
+
+    let targets = [&chain, &smooth];
+    let mut fused = graph.compile(&targets)?;
+    let mut unfused = graph.compile_with(&targets, UNFUSED)?;
+    println!("fused {} nodes", fused.fused_nodes());
+    // Add and Mul run inside Sub; Mul, Exp, Add, Log and the two scalar
+    // fills inside Tanh
+    assert_eq!(fused.fused_nodes(), 8);
+    assert_eq!(unfused.fused_nodes(), 0);
+
+    let inputs = HashMap::from([
+        input("a", (0..12).map(|i| i as f32 * 0.1).collect(), &[3, 4]),
+        input("b", vec![1.0, -1.0, 0.5, 2.0], &[4]),
+        input("c", (0..12).map(|i| (i as f32).sin()).collect(), &[4, 3]),
+        input("d", vec![0.25, -0.5, 1.0], &[3, 1]),
+    ]);
+    compare(&mut fused, &mut unfused, &targets, &inputs);
+    let results = fused.execute(inputs)?;
+    let expected = (0.0 + 1.0) * 0.0f32.sin() - 0.25;
+    assert_eq!(floats(&results[&chain])[0], expected);
+
+    // A value read twice is computed once, and results that are targets
+    // are not absorbed
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([5]), DType::F32)?;
+    let shared = (&x * 3.0).sin();
+    let both = &shared.exp() + &shared.cos();
+    let squared = &shared * &shared;
+    let targets = [&both, &squared, &shared];
+    let mut fused = graph.compile(&targets)?;
+    let mut unfused = graph.compile_with(&targets, UNFUSED)?;
+    // Mul and its fill into Sin; Exp and Cos into Add
+    assert_eq!(fused.fused_nodes(), 4);
+    let inputs =
+        HashMap::from([input("x", vec![0.0, 0.5, 1.0, -2.0, 3.0], &[5])]);
+    compare(&mut fused, &mut unfused, &targets, &inputs);
+
+    // Integers fuse as well
+    let mut graph = Graph::new();
+    let i = graph.constant(vec![1i32, -2, 3], Shape::from([3]));
+    let j = graph.placeholder("j", Shape::from([3]), DType::I32)?;
+    let k = (&(&i + &j) * &j).abs().neg();
+    let mut fused = graph.compile(&[&k])?;
+    let mut unfused = graph.compile_with(&[&k], UNFUSED)?;
+    assert_eq!(fused.fused_nodes(), 3);
+    let inputs = HashMap::from([(
+        TensorKey::from("j"),
+        TensorStorage::I32 {
+            data: vec![4, 5, -6],
+            shape: vec![3],
+        },
+    )]);
+    compare(&mut fused, &mut unfused, &[&k], &inputs);
+    assert_eq!(
+        format!("{:?}", fused.execute(inputs)?[&k]),
+        "I32 { data: [-20, -15, -18], shape: [3] }"
+    );
+
+    // On large tensors, intermediates are neither allocated nor walked
+    let len = 1 << 20;
+    let mut graph = Graph::new();
+    let a = graph.placeholder("a", Shape::from([len]), DType::F32)?;
+    let b = graph.placeholder("b", Shape::from([len]), DType::F32)?;
+    let c = graph.placeholder("c", Shape::from([len]), DType::F32)?;
+    let d = graph.placeholder("d", Shape::from([len]), DType::F32)?;
+    let mut out = &(&(&a + &b) * &c) - &d;
+    for _ in 0..4 {
+        out = &(&out * &a) + &b;
+    }
+    let mut fused = graph.compile(&[&out])?;
+    let mut unfused = graph.compile_with(&[&out], UNFUSED)?;
+    assert_eq!(fused.fused_nodes(), 10);
+    let inputs = HashMap::from(["a", "b", "c", "d"].map(|name| {
+        input(
+            name,
+            (0..len).map(|i| (i % 7) as f32 * 0.1).collect(),
+            &[len],
+        )
+    }));
+
+    let mut totals = [(0, Duration::ZERO); 2];
+    for _ in 0..5 {
+        for (total, (bytes, time)) in totals.iter_mut().zip(compare(
+            &mut fused,
+            &mut unfused,
+            &[&out],
+            &inputs,
+        )) {
+            total.0 = bytes;
+            total.1 += time;
+        }
+    }
+    let [(fused_bytes, fused_time), (unfused_bytes, unfused_time)] = totals;
+    println!(
+        "fused: {} MiB in {fused_time:?}, unfused: {} MiB in {unfused_time:?}",
+        fused_bytes >> 20,
+        unfused_bytes >> 20
+    );
+    // Unfused operations overwrite their dead operands too, but the first
+    // one reads inputs that are still needed and takes a tensor of its own,
+    // where the fused kernel only needs a few blocks of scratch space, so
+    // the unfused chain asks for most of one more f32 tensor
+    assert!(unfused_bytes - fused_bytes >= len * 3);
+    assert!(fused_time < unfused_time);
+
+    println!("✓ elementwise chains run in single fused passes");
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use binah_core::{Graph, Shape};
+use std::collections::HashMap;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut graph = Graph::new();
+
+    // loss = (w * x + b - y)^2, with b broadcast across the batch
+    let x = graph.constant(vec![1.0f32, 2.0f32, 3.0f32], Shape::from([3]));
+    let y = graph.constant(vec![2.0f32, 4.0f32, 6.0f32], Shape::from([3]));
+    let w = graph.variable(vec![0.5f32], Shape::from([1]));
+    let b = graph.variable(vec![0.0f32], Shape::from([1]));
+
+    let error = &w * &x + &b - &y;
+    let loss = &error * &error;
+
+    let grads = graph.gradients(&loss, &[&w, &b])?;
+
+    // A single executable computes the loss and both gradients
+    let mut executable = graph.compile(&[&loss, &grads[0], &grads[1]])?;
+    let results = executable.execute(HashMap::new())?;
+
+    println!("loss: {:?}", results[&loss]);
+    println!("dloss/dw: {:?}", results[&grads[0]]);
+    println!("dloss/db: {:?}", results[&grads[1]]);
+    assert_eq!(
+        format!("{:?}", results[&grads[0]]),
+        "F32 { data: [-42.0], shape: [1] }"
+    );
+    assert_eq!(
+        format!("{:?}", results[&grads[1]]),
+        "F32 { data: [-18.0], shape: [1] }"
+    );
+
+    // The seed and the reduction back to the variable's shape keep the dtype
+    let mut graph = Graph::new();
+    let x = graph.constant(vec![1.0f64, 2.0, 3.0], Shape::from([3]));
+    let w = graph.variable(vec![0.5f64], Shape::from([1]));
+    let loss = &w * &x;
+    let grads = graph.gradients(&loss, &[&w])?;
+    let mut executable = graph.compile(&[&grads[0]])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", results[&grads[0]]),
+        "F64 { data: [6.0], shape: [1] }"
+    );
+
+    // The gradient of a product is the product of the other elements, also
+    // where some of them are zero
+    let mut graph = Graph::new();
+    let x = graph.variable(
+        vec![0.0f32, 2.0, 3.0, 1.0, 2.0, 3.0, 0.0, 0.0, 5.0],
+        Shape::from([3, 3]),
+    );
+    let y = graph.variable(vec![2.0f32, 0.0, 3.0, 4.0], Shape::from([2, 2]));
+    let rows = x.prod(&[1], false);
+    let all = y.prod(&[], true);
+    let row_grads = graph.gradients(&rows, &[&x])?;
+    let all_grads = graph.gradients(&all, &[&y])?;
+    let mut executable = graph.compile(&[&row_grads[0], &all_grads[0]])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", results[&row_grads[0]]),
+        "F32 { data: [6.0, 0.0, 0.0, 6.0, 3.0, 2.0, 0.0, 0.0, 0.0], \
+         shape: [3, 3] }"
+    );
+    assert_eq!(
+        format!("{:?}", results[&all_grads[0]]),
+        "F32 { data: [0.0, 24.0, 0.0, 0.0], shape: [2, 2] }"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use binah_core::{DType, Graph, GraphError, Shape, TypePromotion};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Graph Errors Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph.constant(vec![1.0f32; 6], Shape::from([2, 3]));
+    let y = graph.constant(vec![1.0f32; 4], Shape::from([4]));
+    let flags = graph.constant(vec![true, false], Shape::from([2]));
+
+    // Every builder has a fallible form that reports what went wrong
+    let err = x.try_add(&y).expect_err("shapes do not broadcast");
+    assert_eq!(
+        err,
+        GraphError::IncompatibleShapes {
+            op: "add",
+            node: 1.into(),
+            lhs: Shape::from([2, 3]),
+            rhs: Shape::from([4]),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Incompatible shapes [2, 3] and [4] for add at node 1"
+    );
+
+    let err = x.try_greater(&y).expect_err("shapes do not broadcast");
+    assert_eq!(
+        err.to_string(),
+        "Incompatible shapes [2, 3] and [4] for greater at node 1"
+    );
+
+    let err = x.try_sum(&[2], false).expect_err("axis out of range");
+    assert_eq!(
+        err.to_string(),
+        "Invalid sum at node 0: Axis 2 is out of range for a tensor of rank 2"
+    );
+    assert!(x.try_mean(&[0, -3], true).is_err());
+    assert!(x.try_argmax(&[5], false).is_err());
+
+    let err = flags.try_max(&[], false).expect_err("bools have no max");
+    assert_eq!(err.to_string(), "Unsupported dtype bool for max at node 2");
+    let err = flags.try_neg().expect_err("bools have no negation");
+    assert_eq!(err.to_string(), "Unsupported dtype bool for neg at node 2");
+    assert!(flags.try_relu().is_err());
+
+    // Strict graphs do not convert integers for float-only operations
+    let mut strict = Graph::new();
+    strict.set_type_promotion(TypePromotion::Strict);
+    let ints = strict.constant(vec![4i32, 9], Shape::from([2]));
+    let err = ints.try_sqrt().expect_err("strict graphs keep integers");
+    assert_eq!(err.to_string(), "Unsupported dtype i32 for sqrt at node 0");
+    assert!(ints.try_abs().is_ok());
+    assert!(ints.try_cast(DType::F64)?.try_sqrt().is_ok());
+
+    // Valid operations build as before
+    assert_eq!(x.try_sum(&[-1], true)?.shape(), Shape::from([2, 1]));
+    assert_eq!(x.try_exp()?.dtype(), DType::F32);
+    assert_eq!(x.try_less_equal(&x)?.dtype(), DType::Bool);
+
+    // Handles that outlive their graph report it instead of panicking
+    let orphan = {
+        let mut graph = Graph::new();
+        graph.constant(vec![1.0f32], Shape::from([1]))
+    };
+    let err = orphan.try_cast(DType::F64).expect_err("graph was dropped");
+    assert_eq!(err, GraphError::GraphDropped { node: 0.into() });
+    assert!(orphan.try_sum(&[], false).is_err());
+    assert!(orphan.try_equal(&orphan).is_err());
+    assert!(orphan.try_tanh().is_err());
+
+    println!("✓ invalid graphs are reported as errors");
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use binah_core::{
+    CompileOptions, DType, Graph, Shape, TensorKey, TensorStorage,
+};
+use std::collections::HashMap;
+
+mod counting;
+
+use counting::measure;
+
+const UNFUSED: CompileOptions = CompileOptions {
+    eliminate_common_subexpressions: true,
+    fuse_elementwise: false,
+};
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn input(
+    name: &str,
+    data: Vec<f32>,
+    shape: &[usize],
+) -> (TensorKey, TensorStorage) {
+    (
+        name.into(),
+        TensorStorage::F32 {
+            data,
+            shape: shape.to_vec(),
+        },
+    )
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== In-Place Test ===");
+
+    // The activation overwrites the product it reads for the last time
+    const N: usize = 256;
+    const MATRIX: usize = N * N * 4;
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, N]), DType::F32)?;
+    let w = graph.placeholder("w", Shape::from([N, N]), DType::F32)?;
+    let h = x.matmul(&w).relu();
+    let mut executable = graph.compile_with(&[&h], UNFUSED)?;
+    assert_eq!(executable.peak_memory(), Some(MATRIX));
+
+    let feed = || {
+        HashMap::from(["x", "w"].map(|name| {
+            input(
+                name,
+                (0..N * N).map(|i| ((i % 13) as f32 - 6.0) * 0.01).collect(),
+                &[N, N],
+            )
+        }))
+    };
+    let inputs = feed();
+    let (first, _, asked) = measure(|| executable.execute(inputs));
+    let first = floats(&first?[&h]);
+    println!("asked for {asked} bytes");
+    // The product and the returned copy, but no tensor for the activation
+    assert!(asked < 3 * MATRIX);
+    assert!(first.iter().all(|&value| value >= 0.0));
+
+    // Buffers written over come back with the next inputs
+    let second = executable.execute(feed())?;
+    assert_eq!(floats(&second[&h]), first);
+
+    // A dead input holds the sum, and a smaller broadcast operand is never
+    // written over
+    let len = 1 << 16;
+    let mut graph = Graph::new();
+    let a = graph.placeholder("a", Shape::from([len / 4, 4]), DType::F32)?;
+    let b = graph.placeholder("b", Shape::from([4]), DType::F32)?;
+    let sum = &b + &a;
+    let mut executable = graph.compile(&[&sum])?;
+    assert_eq!(executable.peak_memory(), Some(0));
+    let inputs = HashMap::from([
+        input("a", (0..len).map(|i| i as f32).collect(), &[len / 4, 4]),
+        input("b", vec![0.5, 1.5, 2.5, 3.5], &[4]),
+    ]);
+    let (results, _, asked) = measure(|| executable.execute(inputs));
+    let expected: Vec<f32> =
+        (0..len).map(|i| i as f32 + (i % 4) as f32 + 0.5).collect();
+    assert_eq!(floats(&results?[&sum]), expected);
+    // Only the returned copy
+    assert!(asked < 2 * len * 4);
+
+    // Tensors read later, or returned, keep their values
+    let mut graph = Graph::new();
+    let a = graph.placeholder("a", Shape::from([4]), DType::F32)?;
+    let doubled = &a + &a;
+    let squared = &a * &a;
+    let shifted = &doubled - &a;
+    let halved = &doubled * 0.5;
+    let targets = [&squared, &shifted, &doubled, &halved];
+    for options in [CompileOptions::default(), UNFUSED] {
+        let mut executable = graph.compile_with(&targets, options)?;
+        for _ in 0..2 {
+            let results = executable.execute(HashMap::from([input(
+                "a",
+                vec![1.0, -2.0, 3.0, -4.0],
+                &[4],
+            )]))?;
+            assert_eq!(floats(&results[&squared]), [1.0, 4.0, 9.0, 16.0]);
+            assert_eq!(floats(&results[&shifted]), [1.0, -2.0, 3.0, -4.0]);
+            assert_eq!(floats(&results[&doubled]), [2.0, -4.0, 6.0, -8.0]);
+            assert_eq!(floats(&results[&halved]), [1.0, -2.0, 3.0, -4.0]);
+        }
+    }
+
+    println!(
+        "✓ elementwise kernels write over operands read for the last time"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use binah_core::{Graph, Shape, Slice};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Indexing Test ===");
+
+    let mut graph = Graph::new();
+    // [[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]
+    let x = graph.constant((0..12i32).collect::<Vec<_>>(), Shape::from([3, 4]));
+
+    check(
+        &mut graph,
+        "x[1:, ::2]",
+        &x.slice(&[Slice::from(1..), Slice::full().with_step(2)])?,
+        "I32 { data: [4, 6, 8, 10], shape: [2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "x[-1]",
+        &x.slice(&[Slice::from(-1..)])?,
+        "I32 { data: [8, 9, 10, 11], shape: [1, 4] }",
+    )?;
+    check(
+        &mut graph,
+        "x[:, 1:-1]",
+        &x.slice(&[Slice::full(), Slice::new(Some(1), Some(-1), 1)])?,
+        "I32 { data: [1, 2, 5, 6, 9, 10], shape: [3, 2] }",
+    )?;
+    // Bounds past either end are clamped
+    check(
+        &mut graph,
+        "x[-10:10, 3:]",
+        &x.slice(&[Slice::new(Some(-10), Some(10), 1), Slice::from(3..)])?,
+        "I32 { data: [3, 7, 11], shape: [3, 1] }",
+    )?;
+    check(
+        &mut graph,
+        "x[::2, 5:]",
+        &x.slice(&[Slice::full().with_step(2), Slice::from(5..)])?,
+        "I32 { data: [], shape: [2, 0] }",
+    )?;
+    // Slices of transposed views
+    check(
+        &mut graph,
+        "x.T[1::2]",
+        &x.transpose(0, 1)?.slice(&[Slice::from(1..).with_step(2)])?,
+        "I32 { data: [1, 5, 9, 3, 7, 11], shape: [2, 3] }",
+    )?;
+
+    // Steps must be positive, and there is one slice per axis at most
+    assert!(x.slice(&[Slice::full().with_step(-1)]).is_err());
+    assert!(x.slice(&[Slice::full().with_step(0)]).is_err());
+    assert!(x.slice(&[Slice::full(); 3]).is_err());
+
+    // Negative indices count from the end of the axis
+    let rows = graph.constant(vec![2i64, -3, 2], Shape::from([3]));
+    check(
+        &mut graph,
+        "index_select rows",
+        &x.index_select(&rows, 0)?,
+        "I32 { data: [8, 9, 10, 11, 0, 1, 2, 3, 8, 9, 10, 11], shape: [3, 4] }",
+    )?;
+    let columns = graph.constant(vec![-1i64, 0], Shape::from([2]));
+    check(
+        &mut graph,
+        "index_select columns of x.T",
+        &x.transpose(0, 1)?.index_select(&columns, 1)?,
+        "I32 { data: [8, 0, 9, 1, 10, 2, 11, 3], shape: [4, 2] }",
+    )?;
+
+    let picks = graph.constant(vec![3i64, 0, 1, 1, 0, 2], Shape::from([3, 2]));
+    check(
+        &mut graph,
+        "gather axis 1",
+        &x.gather(&picks, 1)?,
+        "I32 { data: [3, 0, 5, 5, 8, 10], shape: [3, 2] }",
+    )?;
+    let top = graph.constant(vec![2i64, 0, 1, 2], Shape::from([1, 4]));
+    check(
+        &mut graph,
+        "gather axis 0",
+        &x.gather(&top, 0)?,
+        "I32 { data: [8, 1, 6, 11], shape: [1, 4] }",
+    )?;
+
+    let zeros = graph.constant(vec![0i32; 12], Shape::from([3, 4]));
+    let src = graph.constant(vec![1i32, 2, 3, 4, 5, 6], Shape::from([3, 2]));
+    check(
+        &mut graph,
+        "scatter axis 1",
+        &zeros.scatter(&picks, &src, 1)?,
+        "I32 { data: [2, 0, 0, 1, 0, 4, 0, 0, 5, 0, 6, 0], shape: [3, 4] }",
+    )?;
+    let repeated =
+        graph.constant(vec![1i64, 1, 0, 1, 2, 2], Shape::from([3, 2]));
+    check(
+        &mut graph,
+        "scatter_add axis 1",
+        &zeros.scatter_add(&repeated, &src, 1)?,
+        "I32 { data: [0, 3, 0, 0, 3, 4, 0, 0, 0, 0, 11, 0], shape: [3, 4] }",
+    )?;
+
+    // Indices must be integers within the other dimensions
+    let float_indices = graph.constant(vec![0.0f32], Shape::from([1]));
+    assert!(x.index_select(&float_indices, 0).is_err());
+    let too_tall = graph.constant(vec![0i64; 8], Shape::from([4, 2]));
+    assert!(x.gather(&too_tall, 1).is_err());
+
+    // Boolean masks
+    let mask = x.greater(&graph.constant(vec![5i32], Shape::from([1])));
+    check(
+        &mut graph,
+        "masked_fill",
+        &x.masked_fill(&mask, -1.0)?,
+        "I32 { data: [0, 1, 2, 3, 4, 5, -1, -1, -1, -1, -1, -1], \
+         shape: [3, 4] }",
+    )?;
+    let negated = -&x;
+    check(
+        &mut graph,
+        "select",
+        &mask.select(&negated, &x)?,
+        "I32 { data: [0, 1, 2, 3, 4, 5, -6, -7, -8, -9, -10, -11], \
+         shape: [3, 4] }",
+    )?;
+
+    println!("✓ slicing, gathering and scattering pick the right elements");
+
+    Ok(())
+}
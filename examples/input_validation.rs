@@ -0,0 +1,147 @@
+use binah_core::{
+    DType, ExecutionError, Graph, Shape, TensorKey, TensorStorage,
+    cpu::KernelError,
+};
+use std::collections::HashMap;
+
+fn inputs(
+    entries: Vec<(&str, TensorStorage)>,
+) -> HashMap<TensorKey, TensorStorage> {
+    entries
+        .into_iter()
+        .map(|(name, storage)| (name.into(), storage))
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Input Validation Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([2, 2]), DType::F32)?;
+    let indices = graph.placeholder("indices", Shape::from([2]), DType::I64)?;
+    let offset = graph.constant(vec![1.0f64, 2.0], Shape::from([2]));
+
+    let picked = x.index_select(&indices, 0)?;
+    let mut executable = graph.compile(&[&picked])?;
+
+    let good = || TensorStorage::F32 {
+        data: vec![1.0, 2.0, 3.0, 4.0],
+        shape: vec![2, 2],
+    };
+    let results = executable.execute(inputs(vec![
+        ("x", good()),
+        (
+            "indices",
+            TensorStorage::I64 {
+                data: vec![1, -2],
+                shape: vec![2],
+            },
+        ),
+    ]))?;
+    assert_eq!(
+        format!("{:?}", results[&picked]),
+        "F32 { data: [3.0, 4.0, 1.0, 2.0], shape: [2, 2] }"
+    );
+
+    let index_storage = || TensorStorage::I64 {
+        data: vec![0, 1],
+        shape: vec![2],
+    };
+    let check = |result: Result<_, ExecutionError>, expected: &str| {
+        let err = result.expect_err(expected);
+        println!("{err}");
+        assert_eq!(err.to_string(), expected);
+    };
+
+    // Inputs are checked against their placeholders before running
+    check(
+        executable.execute(inputs(vec![
+            (
+                "x",
+                TensorStorage::F64 {
+                    data: vec![0.0; 4],
+                    shape: vec![2, 2],
+                },
+            ),
+            ("indices", index_storage()),
+        ])),
+        "Expected an input of dtype f32 for node 0, got f64",
+    );
+    check(
+        executable.execute(inputs(vec![
+            (
+                "x",
+                TensorStorage::F32 {
+                    data: vec![0.0; 4],
+                    shape: vec![4],
+                },
+            ),
+            ("indices", index_storage()),
+        ])),
+        "Expected an input of shape [2, 2] for node 0, got [4]",
+    );
+    check(
+        executable.execute(inputs(vec![
+            (
+                "x",
+                TensorStorage::F32 {
+                    data: vec![0.0; 3],
+                    shape: vec![2, 2],
+                },
+            ),
+            ("indices", index_storage()),
+        ])),
+        "The input fed to node 0 holds 3 elements but has shape [2, 2]",
+    );
+    check(
+        executable.execute(inputs(vec![
+            ("x", good()),
+            ("indices", index_storage()),
+            ("y", good()),
+        ])),
+        "No input is named \"y\"",
+    );
+    let mut fed = inputs(vec![("x", good()), ("indices", index_storage())]);
+    fed.insert(TensorKey::from(&offset), good());
+    check(executable.execute(fed), "Node 2 is not an input");
+
+    // Kernels report bad data instead of panicking
+    let result = executable.execute(inputs(vec![
+        ("x", good()),
+        (
+            "indices",
+            TensorStorage::I64 {
+                data: vec![0, 2],
+                shape: vec![2],
+            },
+        ),
+    ]));
+    match result {
+        Err(ExecutionError::Kernel { node, source }) => {
+            assert_eq!(node, picked.node_id());
+            assert_eq!(
+                source,
+                KernelError::IndexOutOfRange {
+                    op: "index_select",
+                    index: 2,
+                    len: 2,
+                }
+            );
+        }
+        other => panic!("expected a kernel error, got {other:?}"),
+    }
+
+    // Mixed dtypes are promoted when the graph is built
+    let mixed = &x + &offset;
+    assert_eq!(mixed.dtype(), DType::F64);
+    let mut executable = graph.compile(&[&mixed])?;
+    let results = executable.execute(inputs(vec![("x", good())]))?;
+    assert_eq!(
+        format!("{:?}", results[&mixed]),
+        "F64 { data: [2.0, 4.0, 4.0, 6.0], shape: [2, 2] }"
+    );
+
+    println!("All input validation checks passed");
+
+    Ok(())
+}
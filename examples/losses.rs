@@ -0,0 +1,149 @@
+use binah_core::{
+    DType, Graph, GraphError, GraphTensor, Shape, TensorStorage, op::Reduction,
+};
+use std::collections::HashMap;
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-4, "{actual:?} != {expected:?}");
+    }
+}
+
+fn run(graph: &mut Graph, tensors: &[&GraphTensor]) -> Vec<Vec<f32>> {
+    let mut executable = graph.compile(tensors).unwrap();
+    let results = executable.execute(HashMap::new()).unwrap();
+    tensors
+        .iter()
+        .map(|tensor| floats(&results[*tensor]))
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Losses Test ===");
+
+    // Regression losses and their reductions
+    let mut graph = Graph::new();
+    let input = graph.constant(vec![1.0f32, -2.0, 3.0, 0.5], Shape::from([4]));
+    let target = graph.constant(vec![0.0f32, 0.0, 0.0, 0.0], Shape::from([4]));
+
+    let losses = [
+        input.mse_loss(&target, Reduction::None)?,
+        input.mse_loss(&target, Reduction::Mean)?,
+        input.l1_loss(&target, Reduction::Sum)?,
+        input.huber_loss(&target, 1.5, Reduction::None)?,
+    ];
+    let results = run(&mut graph, &losses.iter().collect::<Vec<_>>());
+    assert_close(&results[0], &[1.0, 4.0, 9.0, 0.25]);
+    assert_close(&results[1], &[3.5625]);
+    assert_close(&results[2], &[6.5]);
+    assert_close(&results[3], &[0.5, 1.875, 3.375, 0.125]);
+    assert_eq!(losses[1].shape(), Shape::new([]));
+
+    // Binary cross-entropy stays finite for logits that overflow exp
+    let mut graph = Graph::new();
+    let logits =
+        graph.constant(vec![100.0f32, -100.0, -0.5, 2.0], Shape::from([4]));
+    let labels = graph.constant(vec![0.0f32, 1.0, 1.0, 0.25], Shape::from([4]));
+    let bce =
+        logits.binary_cross_entropy_with_logits(&labels, Reduction::None)?;
+    let grads = graph.gradients(&bce.sum(&[], false), &[&logits])?;
+    let results = run(&mut graph, &[&bce, &grads[0]]);
+    println!("bce: {:?}", results[0]);
+    let reference = |x: f64, y: f64| {
+        (x.max(0.0) - x * y + (1.0 + (-x.abs()).exp()).ln()) as f32
+    };
+    assert_close(
+        &results[0],
+        &[100.0, 100.0, reference(-0.5, 1.0), reference(2.0, 0.25)],
+    );
+    // The gradient is sigmoid(x) - y
+    let sigmoid = |x: f32| 1.0 / (1.0 + (-x).exp());
+    assert_close(
+        &results[1],
+        &[1.0, -1.0, sigmoid(-0.5) - 1.0, sigmoid(2.0) - 0.25],
+    );
+
+    // Cross-entropy picks the labelled class of every row, however large
+    // the logits
+    let mut graph = Graph::new();
+    let logits = graph.constant(
+        vec![1.0f32, 2.0, 3.0, 1000.0, 0.0, -1000.0],
+        Shape::from([2, 3]),
+    );
+    let classes = graph.constant(vec![2i64, 1], Shape::from([2]));
+    let per_row = logits.cross_entropy_loss(&classes, Reduction::None)?;
+    let mean = logits.cross_entropy_loss(&classes, Reduction::Mean)?;
+    let grads = graph.gradients(&mean, &[&logits])?;
+    let results = run(&mut graph, &[&per_row, &mean, &grads[0]]);
+    println!("cross-entropy: {:?}", results[0]);
+
+    let row = [1.0f64, 2.0, 3.0];
+    let log_sum_exp = row.iter().map(|x| x.exp()).sum::<f64>().ln();
+    let first = (log_sum_exp - 3.0) as f32;
+    assert_close(&results[0], &[first, 1000.0]);
+    assert_close(&results[1], &[(first + 1000.0) / 2.0]);
+    // The gradient is (softmax(x) - one_hot(label)) / rows
+    let softmax: Vec<f32> =
+        row.iter().map(|x| (x - log_sum_exp).exp() as f32).collect();
+    assert_close(
+        &results[2],
+        &[
+            softmax[0] / 2.0,
+            softmax[1] / 2.0,
+            (softmax[2] - 1.0) / 2.0,
+            0.5,
+            -0.5,
+            0.0,
+        ],
+    );
+
+    // KL divergence ignores classes the target rules out
+    let mut graph = Graph::new();
+    let log_probs = graph.constant(
+        vec![0.25f32.ln(), 0.25f32.ln(), 0.5f32.ln()],
+        Shape::from([3]),
+    );
+    let target = graph.constant(vec![0.5f32, 0.0, 0.5], Shape::from([3]));
+    let kl = log_probs.kl_div_loss(&target, Reduction::Sum)?;
+    let same = target.kl_div_loss(&target, Reduction::None);
+    assert!(same.is_ok());
+    let grads = graph.gradients(&kl, &[&log_probs])?;
+    let results = run(&mut graph, &[&kl, &grads[0]]);
+    assert_close(&results[0], &[0.5 * 2.0f32.ln()]);
+    assert_close(&results[1], &[-0.5, 0.0, -0.5]);
+
+    // Operands must line up
+    let err = log_probs
+        .mse_loss(&classes, Reduction::Mean)
+        .expect_err("mismatched shapes");
+    println!("{err}");
+    assert!(matches!(
+        err,
+        GraphError::IncompatibleShapes { op: "mse_loss", .. }
+    ));
+    let float_labels = logits.sum(&[-1], false);
+    let err = logits
+        .cross_entropy_loss(&float_labels, Reduction::Mean)
+        .expect_err("float labels");
+    println!("{err}");
+    assert!(matches!(
+        err,
+        GraphError::UnsupportedDtype {
+            op: "cross_entropy_loss",
+            dtype: DType::F32,
+            ..
+        }
+    ));
+
+    println!("✓ losses are stable and reduce as asked");
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use binah_core::{Graph, Shape, TensorStorage};
+use std::collections::HashMap;
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Matmul Test ===");
+
+    let mut graph = Graph::new();
+    let a = graph.constant(vec![1.0f32, 2.0, 3.0, 4.0], Shape::from([2, 2]));
+    let b = graph.constant(vec![5.0f32, 6.0, 7.0, 8.0], Shape::from([2, 2]));
+    let u = graph.constant(vec![1.0f32, 2.0], Shape::from([2]));
+    let v = graph.constant(vec![5.0f32, 6.0], Shape::from([2]));
+
+    check(
+        &mut graph,
+        "a @ b",
+        &a.matmul(&b),
+        "F32 { data: [19.0, 22.0, 43.0, 50.0], shape: [2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "a^T @ b",
+        &a.transpose(0, 1)?.matmul(&b),
+        "F32 { data: [26.0, 30.0, 38.0, 44.0], shape: [2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "a @ b^T",
+        &a.matmul(&b.transpose(0, 1)?),
+        "F32 { data: [17.0, 23.0, 39.0, 53.0], shape: [2, 2] }",
+    )?;
+
+    // Vectors lose the dimension they were promoted with
+    check(
+        &mut graph,
+        "u @ b",
+        &u.matmul(&b),
+        "F32 { data: [19.0, 22.0], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "a @ v",
+        &a.matmul(&v),
+        "F32 { data: [17.0, 39.0], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "u @ v",
+        &u.matmul(&v),
+        "F32 { data: [17.0], shape: [] }",
+    )?;
+
+    // Batch dimensions broadcast
+    let batch = graph.constant(
+        vec![1.0f32, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0],
+        Shape::from([2, 2, 2]),
+    );
+    check(
+        &mut graph,
+        "batch @ b",
+        &batch.matmul(&b),
+        "F32 { data: [5.0, 6.0, 7.0, 8.0, 10.0, 12.0, 14.0, 16.0], \
+         shape: [2, 2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "a @ batch",
+        &a.reshape([1, 2, 2])?.matmul(&batch),
+        "F32 { data: [1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 8.0], \
+         shape: [2, 2, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "batch @ v",
+        &batch.matmul(&v),
+        "F32 { data: [5.0, 6.0, 10.0, 12.0], shape: [2, 2] }",
+    )?;
+
+    // Inner dimensions must agree
+    let c = graph.constant(vec![0.0f32; 6], Shape::from([2, 3]));
+    assert!(c.try_matmul(&c).is_err());
+
+    // Sizes that do not divide the kernel's blocks match a plain triple loop
+    let (m, k, n) = (70, 130, 50);
+    let lhs: Vec<f64> = (0..m * k).map(|i| (i % 7) as f64 - 3.0).collect();
+    let rhs: Vec<f64> = (0..k * n).map(|i| (i % 5) as f64 - 2.0).collect();
+    let mut expected = vec![0.0; m * n];
+    for i in 0..m {
+        for j in 0..n {
+            for p in 0..k {
+                expected[i * n + j] += lhs[i * k + p] * rhs[p * n + j];
+            }
+        }
+    }
+    let mut graph = Graph::new();
+    let x = graph.constant(lhs, Shape::from([m, k]));
+    let w = graph.constant(rhs, Shape::from([k, n]));
+    let product = x.matmul(&w);
+    let mut executable = graph.compile(&[&product])?;
+    let results = executable.execute(HashMap::new())?;
+    match &results[&product] {
+        TensorStorage::F64 { data, shape } => {
+            assert_eq!(shape, &[m, n]);
+            assert_eq!(data, &expected);
+        }
+        other => panic!("expected f64, got {other:?}"),
+    }
+
+    println!("✓ matmul handles vectors, transposes and batches");
+
+    Ok(())
+}
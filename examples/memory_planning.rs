@@ -0,0 +1,108 @@
+use binah_core::{DType, Dim, Graph, Shape, TensorKey, TensorStorage};
+use std::collections::HashMap;
+
+mod counting;
+
+use counting::measure;
+
+fn matrix(n: usize, seed: f32) -> TensorStorage {
+    TensorStorage::F32 {
+        data: (0..n * n).map(|i| ((i % 13) as f32 - 6.0) * seed).collect(),
+        shape: vec![n, n],
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Memory Planning Test ===");
+
+    // A chain of products only ever needs the last two at once, and each
+    // one goes to the buffer of the one before the last, the first of them
+    // to the input
+    const N: usize = 256;
+    const MATRIX: usize = N * N * 4;
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, N]), DType::F32)?;
+    let w = graph.placeholder("w", Shape::from([N, N]), DType::F32)?;
+    let mut h = x.clone();
+    for _ in 0..8 {
+        h = h.matmul(&w.transpose(0, 1)?);
+    }
+    let total = h.sum(&[], false);
+
+    let mut executable = graph.compile(&[&total])?;
+    let peak = executable.peak_memory().unwrap();
+    println!("planned peak: {peak} bytes");
+    assert_eq!(peak, MATRIX);
+
+    let feed = || {
+        HashMap::from([
+            (TensorKey::from("x"), matrix(N, 0.01)),
+            (TensorKey::from("w"), matrix(N, 0.02)),
+        ])
+    };
+    let inputs = feed();
+    let (first, held, asked) = measure(|| executable.execute(inputs));
+    let first = format!("{:?}", first?[&total]);
+    println!("held {held} bytes on top of the inputs, asked for {asked}");
+    // Holding all eight products would take eight times as much
+    assert!(held <= peak + MATRIX / 8);
+    assert!(asked < 2 * MATRIX);
+
+    // Released tensors come back with the next inputs
+    let second = executable.execute(feed())?;
+    assert_eq!(format!("{:?}", second[&total]), first);
+
+    // Buffers released along the way hold the results of fused kernels and
+    // products
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, N]), DType::F32)?;
+    let w = graph.placeholder("w", Shape::from([N, N]), DType::F32)?;
+    let mut h = x.clone();
+    for _ in 0..4 {
+        h = (&h.matmul(&w) * 0.5).tanh();
+    }
+    let mut executable = graph.compile(&[&h])?;
+    assert_eq!(executable.fused_nodes(), 8);
+    let inputs = feed();
+    let (results, _, asked) = measure(|| executable.execute(inputs));
+    results?;
+    println!("asked for {asked} bytes");
+    // The first product and the returned copy: each fused result writes
+    // over its product, and every later product goes to the buffer of the
+    // input or of a product read for the last time before
+    assert!(asked < 3 * MATRIX);
+
+    // Softmaxes and reductions are handed released buffers as well: the
+    // second softmax takes the input's and the third the first's
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, N]), DType::F32)?;
+    let y = x.softmax(-1).log_softmax(-1).softmax(0).sum(&[], false);
+    let mut executable = graph.compile(&[&y])?;
+    assert_eq!(executable.peak_memory(), Some(MATRIX));
+    let inputs = HashMap::from([(TensorKey::from("x"), matrix(N, 0.01))]);
+    let (results, _, asked) = measure(|| executable.execute(inputs));
+    results?;
+    assert!(asked < 2 * MATRIX);
+
+    // Views share the buffer of the tensor they look at, here the input
+    // that the exponentials overwrite
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, N]), DType::F32)?;
+    let y = x.exp().transpose(0, 1)?;
+    let executable = graph.compile(&[&y.sum(&[], false)])?;
+    assert_eq!(executable.peak_memory(), Some(4));
+
+    // Symbolic shapes are only known once inputs arrive
+    let mut graph = Graph::new();
+    let x = graph.placeholder(
+        "x",
+        Shape::from([Dim::Sym("batch"), Dim::Fixed(N)]),
+        DType::F32,
+    )?;
+    let executable = graph.compile(&[&x.exp()])?;
+    assert_eq!(executable.peak_memory(), None);
+
+    println!("✓ intermediates are released after their last use");
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use binah_core::{
+    DType, ExecutionError, Graph, GraphError, Shape, TensorKey, TensorStorage,
+    tensor::Tensor,
+};
+use std::collections::HashMap;
+
+fn feed(data: Vec<f32>, shape: Shape) -> TensorStorage {
+    Tensor::from_data(data, shape).into_storage()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Named Inputs and Outputs Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([3]), DType::F32)?;
+    let scale = graph.placeholder("scale", Shape::from([1]), DType::F32)?;
+    let bias = graph.constant(vec![1.0f32, 2.0, 4.0], Shape::from([3]));
+
+    let scaled = &x * &scale;
+    let shifted = &scaled + &bias;
+    shifted.set_name("shifted")?;
+    assert_eq!(shifted.name().as_deref(), Some("shifted"));
+    assert_eq!(bias.name(), None);
+
+    // Names are unique across placeholders and outputs
+    match graph.placeholder("x", Shape::from([2]), DType::F32) {
+        Err(GraphError::DuplicateName { node, .. }) => {
+            assert_eq!(node, x.node_id())
+        }
+        other => panic!("expected a duplicate name error, got {other:?}"),
+    }
+    assert!(scaled.set_name("shifted").is_err());
+
+    // Outputs come back in the order passed to compile, repeats included
+    let mut executable = graph.compile(&[&shifted, &scaled, &shifted])?;
+
+    let mut inputs = HashMap::new();
+    inputs.insert("x".into(), feed(vec![1.0, 2.0, 3.0], Shape::from([3])));
+    inputs.insert(TensorKey::from(&scale), feed(vec![2.0], Shape::from([1])));
+    let results = executable.execute(inputs)?;
+
+    let printed: Vec<_> = results.iter().map(|r| format!("{r:?}")).collect();
+    println!("{printed:?}");
+    assert_eq!(
+        printed,
+        [
+            "F32 { data: [3.0, 6.0, 10.0], shape: [3] }",
+            "F32 { data: [2.0, 4.0, 6.0], shape: [3] }",
+            "F32 { data: [3.0, 6.0, 10.0], shape: [3] }",
+        ]
+    );
+    assert_eq!(
+        format!("{:?}", results["shifted"]),
+        format!("{:?}", results[&shifted])
+    );
+    assert!(results.get(&bias).is_none());
+    assert!(results.get("x").is_none());
+
+    // Unknown names and missing inputs are reported
+    let mut inputs = HashMap::new();
+    inputs.insert("y".into(), feed(vec![0.0; 3], Shape::from([3])));
+    assert!(matches!(
+        executable.execute(inputs),
+        Err(ExecutionError::UnexpectedInput(TensorKey::Name(name)))
+            if name == "y"
+    ));
+
+    let mut inputs = HashMap::new();
+    inputs.insert("x".into(), feed(vec![0.0; 3], Shape::from([3])));
+    assert!(matches!(
+        executable.execute(inputs),
+        Err(ExecutionError::MissingInput(node)) if node == scale.node_id()
+    ));
+
+    // Without targets, every sink is returned in graph order
+    let other = &bias * 2.0;
+    let mut executable = graph.compile(&[])?;
+    let mut inputs = HashMap::new();
+    inputs.insert("x".into(), feed(vec![1.0; 3], Shape::from([3])));
+    inputs.insert("scale".into(), feed(vec![1.0], Shape::from([1])));
+    let results = executable.execute(inputs)?;
+    assert_eq!(results.len(), 2);
+    assert!(results.get("shifted").is_some());
+    assert!(results.get(&other).is_some());
+
+    println!("All named input and output checks passed");
+
+    Ok(())
+}
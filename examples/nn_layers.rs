@@ -0,0 +1,320 @@
+use binah_core::{
+    DType, Dim, Graph, Shape, TensorStorage,
+    nn::{
+        BatchNorm, Conv2d, Dropout, Embedding, Init, LayerNorm, Linear, Module,
+        MultiHeadAttention, Rng,
+    },
+    optim::{Adam, Optimizer},
+};
+use std::collections::HashMap;
+
+fn floats(storage: &TensorStorage) -> Vec<f32> {
+    match storage {
+        TensorStorage::F32 { data, .. } => data.clone(),
+        other => panic!("expected f32, got {other:?}"),
+    }
+}
+
+fn assert_close(actual: &[f32], expected: &[f32], tolerance: f32) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < tolerance, "{actual:?} != {expected:?}");
+    }
+}
+
+/// Direct convolution of one `[channels, height, width]` image.
+fn reference_conv(
+    image: &[f32],
+    (channels, height, width): (usize, usize, usize),
+    weight: &[f32],
+    (out_channels, kernel): (usize, usize),
+    stride: usize,
+    padding: usize,
+) -> Vec<f32> {
+    let out_height = (height + 2 * padding - kernel) / stride + 1;
+    let out_width = (width + 2 * padding - kernel) / stride + 1;
+    let mut output = vec![0.0; out_channels * out_height * out_width];
+    for o in 0..out_channels {
+        for y in 0..out_height {
+            for x in 0..out_width {
+                let mut sum = 0.0;
+                for c in 0..channels {
+                    for i in 0..kernel {
+                        for j in 0..kernel {
+                            let row =
+                                (y * stride + i) as isize - padding as isize;
+                            let col =
+                                (x * stride + j) as isize - padding as isize;
+                            if row < 0
+                                || col < 0
+                                || row >= height as isize
+                                || col >= width as isize
+                            {
+                                continue;
+                            }
+                            let pixel = image[(c * height + row as usize)
+                                * width
+                                + col as usize];
+                            let w = weight[((o * channels + c) * kernel + i)
+                                * kernel
+                                + j];
+                            sum += pixel * w;
+                        }
+                    }
+                }
+                output[(o * out_height + y) * out_width + x] = sum;
+            }
+        }
+    }
+    output
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== NN Layers Test ===");
+
+    // Initializers are reproducible and respect their bounds
+    let mut rng = Rng::new(7);
+    let sample = Init::KaimingUniform.sample(&mut rng, 1000, 6, 3);
+    assert!(sample.iter().all(|x| x.abs() <= 1.0));
+    assert_eq!(
+        sample,
+        Init::KaimingUniform.sample(&mut Rng::new(7), 1000, 6, 3)
+    );
+    let normal = Init::Normal {
+        mean: 0.0,
+        std: 2.0,
+    }
+    .sample(&mut rng, 10000, 0, 0);
+    let mean = normal.iter().sum::<f32>() / 10000.0;
+    let var = normal.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / 10000.0;
+    println!("normal init: mean {mean:.3}, variance {var:.3}");
+    assert!(mean.abs() < 0.1 && (var - 4.0).abs() < 0.2);
+
+    let mut graph = Graph::new();
+    let mut rng = Rng::new(0);
+
+    // Linear maps the last axis and works with symbolic batch sizes
+    let linear =
+        Linear::with_init(&mut graph, &mut rng, 3, 2, Init::Ones, true);
+    let input = graph.placeholder(
+        "x",
+        Shape::from([Dim::Sym("batch"), Dim::Fixed(3)]),
+        DType::F32,
+    )?;
+    let linear_out = linear.forward(&input);
+    assert_eq!(linear.parameters().len(), 2);
+
+    // Conv2d matches a direct convolution, with padding and stride
+    let conv = Conv2d::new(&mut graph, &mut rng, 2, 3, (3, 3))
+        .with_stride((2, 2))
+        .with_padding((1, 1));
+    let image_data: Vec<f32> =
+        (0..2 * 2 * 5 * 4).map(|i| (i % 7) as f32 - 3.0).collect();
+    let image = graph.constant(image_data.clone(), Shape::from([2, 2, 5, 4]));
+    let conv_out = conv.forward(&image);
+    assert_eq!(conv_out.shape(), Shape::from([2, 3, 3, 2]));
+
+    // A zero stride is reported as an invalid stride, not a division by
+    // zero
+    let still = Conv2d::new(&mut graph, &mut Rng::new(0), 2, 3, (3, 3))
+        .with_stride((0, 1));
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        still.forward(&image)
+    }))
+    .expect_err("zero stride");
+    let message = panic.downcast_ref::<String>().expect("formatted panic");
+    assert!(
+        message.ends_with("Convolution stride must be positive, got (0, 1)"),
+        "{message}"
+    );
+
+    // Embedding looks up rows for indices of any shape
+    let embedding = Embedding::new(&mut graph, &mut rng, 5, 4);
+    let ids = graph.constant(vec![4i64, 0, 4, 1], Shape::from([2, 2]));
+    let embedded = embedding.forward(&ids);
+    assert_eq!(embedded.shape(), Shape::from([2, 2, 4]));
+
+    // LayerNorm normalizes each row
+    let layer_norm = LayerNorm::new(&mut graph, &[4]);
+    let rows = graph.constant(
+        vec![1.0f32, 2.0, 3.0, 4.0, -5.0, 0.0, 5.0, 10.0],
+        Shape::from([2, 4]),
+    );
+    let normalized = layer_norm.forward(&rows);
+
+    let targets = [&linear_out, &conv_out, &embedded, &normalized];
+    let mut executable = graph.compile(&targets)?;
+    let results = executable.execute(HashMap::from([(
+        "x".into(),
+        TensorStorage::F32 {
+            data: vec![1.0, 2.0, 3.0, -1.0, 0.0, 1.0],
+            shape: vec![2, 3],
+        },
+    )]))?;
+
+    assert_eq!(
+        format!("{:?}", results[&linear_out]),
+        "F32 { data: [6.0, 6.0, 0.0, 0.0], shape: [2, 2] }"
+    );
+
+    let weight = floats(executable.get_variable(&conv.weight).unwrap());
+    let conv_data = floats(&results[&conv_out]);
+    for n in 0..2 {
+        let image = &image_data[n * 40..(n + 1) * 40];
+        let expected = reference_conv(image, (2, 5, 4), &weight, (3, 3), 2, 1);
+        assert_close(&conv_data[n * 18..(n + 1) * 18], &expected, 1e-4);
+    }
+
+    let table = floats(executable.get_variable(&embedding.weight).unwrap());
+    let embedded_data = floats(&results[&embedded]);
+    for (position, id) in [4, 0, 4, 1].into_iter().enumerate() {
+        assert_eq!(
+            embedded_data[position * 4..(position + 1) * 4],
+            table[id * 4..(id + 1) * 4]
+        );
+    }
+
+    for row in floats(&results[&normalized]).chunks(4) {
+        let mean = row.iter().sum::<f32>() / 4.0;
+        let var = row.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / 4.0;
+        assert!(mean.abs() < 1e-5 && (var - 1.0).abs() < 1e-3);
+    }
+
+    // BatchNorm uses batch statistics while training and updates the
+    // running statistics, which evaluation then uses
+    let mut graph = Graph::new();
+    let mut batch_norm = BatchNorm::new(&mut graph, 2).with_momentum(0.5);
+    let batch = graph.constant(
+        vec![1.0f32, 10.0, 3.0, 30.0, 5.0, 20.0],
+        Shape::from([3, 2]),
+    );
+    let trained = batch_norm.forward(&batch);
+    let updates = batch_norm.updates();
+    batch_norm.set_training(false);
+    let evaluated = batch_norm.forward(&batch);
+
+    let mut targets = vec![&trained, &evaluated];
+    targets.extend(&updates);
+    let mut executable = graph.compile(&targets)?;
+    let results = executable.execute(HashMap::new())?;
+    assert_close(
+        &floats(&results[&trained]),
+        &[-1.2247, -1.2247, 0.0, 1.2247, 1.2247, 0.0],
+        1e-3,
+    );
+    // Mean [3, 20] and unbiased variance [4, 100], averaged with [0, 1]
+    assert_close(
+        &floats(executable.get_variable(&batch_norm.running_mean).unwrap()),
+        &[1.5, 10.0],
+        1e-5,
+    );
+    assert_close(
+        &floats(executable.get_variable(&batch_norm.running_var).unwrap()),
+        &[2.5, 50.5],
+        1e-4,
+    );
+    // Each execution reads the statistics it started with
+    let results = executable.execute(HashMap::new())?;
+    let expected: Vec<f32> = [1.0f32, 10.0, 3.0, 30.0, 5.0, 20.0]
+        .iter()
+        .zip(
+            [1.5f32, 10.0]
+                .iter()
+                .cycle()
+                .zip([2.5f32, 50.5].iter().cycle()),
+        )
+        .map(|(x, (mean, var))| (x - mean) / (var + 1e-5).sqrt())
+        .collect();
+    assert_close(&floats(&results[&evaluated]), &expected, 1e-4);
+
+    // Dropout draws a new mask on every execution and scales what it keeps
+    let mut graph = Graph::new();
+    let mut dropout = Dropout::new(&mut graph, &mut Rng::new(3), 0.25);
+    let ones = graph.constant(vec![1.0f32; 4000], Shape::from([4000]));
+    let dropped = dropout.forward(&ones);
+    dropout.set_training(false);
+    assert_eq!(dropout.forward(&ones).node_id(), ones.node_id());
+
+    let mut executable = graph.compile(&[&dropped])?;
+    let first = floats(&executable.execute(HashMap::new())?[&dropped]);
+    let second = floats(&executable.execute(HashMap::new())?[&dropped]);
+    let kept = first.iter().filter(|&&x| x != 0.0).count();
+    println!("dropout kept {kept} of 4000");
+    assert!((2850..3150).contains(&kept));
+    assert!(
+        first
+            .iter()
+            .all(|&x| x == 0.0 || (x - 4.0 / 3.0).abs() < 1e-6)
+    );
+    assert_ne!(first, second);
+
+    // Attention with a causal mask: earlier outputs ignore later inputs
+    let mut graph = Graph::new();
+    let mut rng = Rng::new(11);
+    let attention = MultiHeadAttention::new(&mut graph, &mut rng, 8, 2);
+    let tokens =
+        graph.placeholder("tokens", Shape::from([1, 3, 8]), DType::F32)?;
+    let causal = graph.constant(
+        vec![false, true, true, false, false, true, false, false, false],
+        Shape::from([3, 3]),
+    );
+    let attended = attention.attend(&tokens, &tokens, &tokens, Some(&causal));
+    let unmasked = attention.forward(&tokens);
+    assert_eq!(attended.shape(), Shape::from([1, 3, 8]));
+    assert_eq!(attention.parameters().len(), 8);
+
+    let mut executable = graph.compile(&[&attended, &unmasked])?;
+    let mut feed = |last: f32| {
+        let mut data: Vec<f32> =
+            (0..24).map(|i| (i as f32 * 0.37).sin()).collect();
+        data[16..].fill(last);
+        let inputs = HashMap::from([(
+            "tokens".into(),
+            TensorStorage::F32 {
+                data,
+                shape: vec![1, 3, 8],
+            },
+        )]);
+        let results = executable.execute(inputs).unwrap();
+        (floats(&results[&attended]), floats(&results[&unmasked]))
+    };
+    let (masked_a, unmasked_a) = feed(0.5);
+    let (masked_b, unmasked_b) = feed(-2.0);
+    assert_eq!(masked_a[..16], masked_b[..16]);
+    assert_ne!(masked_a[16..], masked_b[16..]);
+    assert_ne!(unmasked_a[..8], unmasked_b[..8]);
+
+    // Layers train end to end: a small network learns XOR
+    let mut graph = Graph::new();
+    let mut rng = Rng::new(5);
+    let hidden = Linear::new(&mut graph, &mut rng, 2, 8);
+    let output = Linear::new(&mut graph, &mut rng, 8, 1);
+    let x = graph.constant(
+        vec![0.0f32, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0],
+        Shape::from([4, 2]),
+    );
+    let y = graph.constant(vec![0.0f32, 1.0, 1.0, 0.0], Shape::from([4, 1]));
+    let prediction = output.forward(&hidden.forward(&x).tanh());
+    let error = &prediction - &y;
+    let loss = (&error * &error).mean(&[], false);
+
+    let params: Vec<_> = [&hidden, &output]
+        .into_iter()
+        .flat_map(Linear::parameters)
+        .collect();
+    let grads = graph.gradients(&loss, &params)?;
+    let step = Adam::new(0.05).minimize(&mut graph, &params, &grads)?;
+    let mut targets = step.targets();
+    targets.push(&loss);
+    let mut executable = graph.compile(&targets)?;
+    let mut last = f32::INFINITY;
+    for _ in 0..300 {
+        last = floats(&executable.execute(HashMap::new())?[&loss])[0];
+    }
+    println!("xor loss after training: {last:e}");
+    assert!(last < 1e-3);
+
+    println!("✓ nn layers build, run and train");
+
+    Ok(())
+}
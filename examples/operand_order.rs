@@ -0,0 +1,136 @@
+use binah_core::{Graph, Shape};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Operand Order Test ===");
+
+    let mut graph = Graph::new();
+
+    // Create the right-hand operands first so that node order and operand
+    // order disagree.
+    let b = graph.constant(vec![1.0f32, 2.0, 4.0], Shape::from([3]));
+    let a = graph.constant(vec![8.0f32, 6.0, 4.0], Shape::from([3]));
+    let column = graph.constant(vec![10.0f32, 20.0], Shape::from([2, 1]));
+
+    check(
+        &mut graph,
+        "a - b",
+        &(a.clone() - b.clone()),
+        "F32 { data: [7.0, 4.0, 0.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "b - a",
+        &(b.clone() - a.clone()),
+        "F32 { data: [-7.0, -4.0, 0.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "a / b",
+        &(a.clone() / b.clone()),
+        "F32 { data: [8.0, 3.0, 1.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "b / a",
+        &(b.clone() / a.clone()),
+        "F32 { data: [0.125, 0.33333334, 1.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "column - b",
+        &(column.clone() - b.clone()),
+        "F32 { data: [9.0, 8.0, 6.0, 19.0, 18.0, 16.0], shape: [2, 3] }",
+    )?;
+    check(
+        &mut graph,
+        "b / column",
+        &(b.clone() / column.clone()),
+        "F32 { data: [0.1, 0.2, 0.4, 0.05, 0.1, 0.2], shape: [2, 3] }",
+    )?;
+
+    // The same node feeding both slots
+    check(
+        &mut graph,
+        "a - a",
+        &(a.clone() - a.clone()),
+        "F32 { data: [0.0, 0.0, 0.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "a / a",
+        &(a.clone() / a.clone()),
+        "F32 { data: [1.0, 1.0, 1.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "(a - b) - a",
+        &((a.clone() - b.clone()) - a.clone()),
+        "F32 { data: [-1.0, -2.0, -4.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "b / (a - b)",
+        &(b.clone() / (a.clone() - b.clone())),
+        "F32 { data: [0.14285715, 0.5, inf], shape: [3] }",
+    )?;
+
+    let joined = graph.concat(&[&b, &a, &b], 0)?;
+    check(
+        &mut graph,
+        "concat [b, a, b]",
+        &joined,
+        "F32 { data: [1.0, 2.0, 4.0, 8.0, 6.0, 4.0, 1.0, 2.0, 4.0], shape: [9] }",
+    )?;
+
+    // Gradients follow the slots too
+    let mut graph = Graph::new();
+    let y = graph.variable(vec![4.0f32], Shape::from([1]));
+    let x = graph.variable(vec![8.0f32], Shape::from([1]));
+
+    let quotient = x.clone() / y.clone();
+    let grads = graph.gradients(&quotient, &[&x, &y])?;
+    check(
+        &mut graph,
+        "d(x / y)/dx",
+        &grads[0],
+        "F32 { data: [0.25], shape: [1] }",
+    )?;
+    check(
+        &mut graph,
+        "d(x / y)/dy",
+        &grads[1],
+        "F32 { data: [-0.5], shape: [1] }",
+    )?;
+
+    let difference = y.clone() - x.clone();
+    let grads = graph.gradients(&difference, &[&x, &y])?;
+    check(
+        &mut graph,
+        "d(y - x)/dx",
+        &grads[0],
+        "F32 { data: [-1.0], shape: [1] }",
+    )?;
+    check(
+        &mut graph,
+        "d(y - x)/dy",
+        &grads[1],
+        "F32 { data: [1.0], shape: [1] }",
+    )?;
+
+    let ratio = x.clone() / (x.clone() - y.clone());
+    let grads = graph.gradients(&ratio, &[&x])?;
+    check(
+        &mut graph,
+        "d(x / (x - y))/dx",
+        &grads[0],
+        "F32 { data: [-0.25], shape: [1] }",
+    )?;
+
+    println!("All operand order checks passed");
+
+    Ok(())
+}
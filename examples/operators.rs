@@ -0,0 +1,120 @@
+use binah_core::{DType, Graph, Shape, TypePromotion};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Operators Test ===");
+
+    let mut graph = Graph::new();
+    let a = graph.constant(vec![1.0f32, 2.0, 3.0], Shape::from([3]));
+    let b = graph.constant(vec![4.0f32, 5.0, 6.0], Shape::from([3]));
+
+    // Borrowed operands leave the handles usable
+    check(
+        &mut graph,
+        "&a * &a + &a",
+        &(&a * &a + &a),
+        "F32 { data: [2.0, 6.0, 12.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "&b - &a",
+        &(&b - &a),
+        "F32 { data: [3.0, 3.0, 3.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "&b / &a",
+        &(&b / &a),
+        "F32 { data: [4.0, 2.5, 2.0], shape: [3] }",
+    )?;
+
+    // Owned and borrowed operands mix, and clones are handles to the same
+    // node
+    let c = a.clone();
+    assert_eq!(c.node_id(), a.node_id());
+    check(
+        &mut graph,
+        "a.clone() - &b",
+        &(c - &b),
+        "F32 { data: [-3.0, -3.0, -3.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "&a * b.clone()",
+        &(&a * b.clone()),
+        "F32 { data: [4.0, 10.0, 18.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "a.clone() + b.clone()",
+        &(a.clone() + b.clone()),
+        "F32 { data: [5.0, 7.0, 9.0], shape: [3] }",
+    )?;
+
+    // Scalars take on the tensor's type on either side
+    check(
+        &mut graph,
+        "&a * 2.0",
+        &(&a * 2.0f32),
+        "F32 { data: [2.0, 4.0, 6.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "1.0 - &a",
+        &(1.0 - &a),
+        "F32 { data: [0.0, -1.0, -2.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "6 / a",
+        &(6 / a.clone()),
+        "F32 { data: [6.0, 3.0, 2.0], shape: [3] }",
+    )?;
+    let n = graph.constant(vec![1i32, 2, 3], Shape::from([3]));
+    check(
+        &mut graph,
+        "&n + 10u8",
+        &(&n + 10u8),
+        "I32 { data: [11, 12, 13], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "2 - &n",
+        &(2 - &n),
+        "I32 { data: [1, 0, -1], shape: [3] }",
+    )?;
+
+    // Float scalars make integer tensors float rather than truncating
+    let half = &n * 0.5;
+    assert_eq!(half.dtype(), DType::F64);
+    check(
+        &mut graph,
+        "&n * 0.5",
+        &half,
+        "F64 { data: [0.5, 1.0, 1.5], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "1.5 + &n",
+        &(1.5 + &n),
+        "F64 { data: [2.5, 3.5, 4.5], shape: [3] }",
+    )?;
+
+    // Strict graphs refuse to convert them
+    let mut strict = Graph::new();
+    strict.set_type_promotion(TypePromotion::Strict);
+    let ints = strict.constant(vec![1i32, 2, 3], Shape::from([3]));
+    assert_eq!((&ints * 2).dtype(), DType::I32);
+    let panic =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| &ints * 0.5))
+            .expect_err("strict graphs keep integers");
+    let message = panic.downcast_ref::<String>().expect("formatted panic");
+    assert_eq!(message, "Unsupported dtype i32 for mul at node 0");
+
+    println!("✓ operators take tensors and scalars by value or reference");
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use binah_core::{
+    DType, Graph, GraphError, GraphTensor, Shape, TensorStorage,
+    optim::{Adam, AdamW, LearningRate, Optimizer, RmsProp, Sgd},
+};
+use std::{collections::HashMap, num::NonZeroUsize};
+
+fn values(storage: &TensorStorage) -> Vec<f64> {
+    match storage {
+        TensorStorage::F32 { data, .. } => {
+            data.iter().map(|&x| x as f64).collect()
+        }
+        TensorStorage::F64 { data, .. } => data.clone(),
+        other => panic!("expected floats, got {other:?}"),
+    }
+}
+
+/// Fits `y = 2x - 1` and returns the loss before the last step.
+fn fit(optimizer: &dyn Optimizer, steps: usize) -> f64 {
+    let mut graph = Graph::new();
+    let x = graph.constant(vec![-1.0f32, 0.0, 1.0, 2.0], Shape::from([4]));
+    let y = graph.constant(vec![-3.0f32, -1.0, 1.0, 3.0], Shape::from([4]));
+    let w = graph.variable(vec![0.0f32], Shape::from([1]));
+    let b = graph.variable(vec![0.0f32], Shape::from([1]));
+
+    let error = &(&w * &x + &b) - &y;
+    let loss = (&error * &error).mean(&[], false);
+    let grads = graph.gradients(&loss, &[&w, &b]).unwrap();
+    let step = optimizer
+        .minimize(&mut graph, &[&w, &b], &grads)
+        .expect("float variables");
+
+    let mut targets = step.targets();
+    targets.push(&loss);
+    let mut executable = graph.compile(&targets).unwrap();
+    let mut last = f64::INFINITY;
+    for _ in 0..steps {
+        let results = executable.execute(HashMap::new()).unwrap();
+        last = values(&results[&loss])[0];
+    }
+    last
+}
+
+/// The learning rate of the first `steps` steps, read off a parameter
+/// whose gradient is always 1.
+fn rates(lr: LearningRate, steps: usize) -> Vec<f64> {
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![0.0f64], Shape::from([1]));
+    let loss = w.sum(&[], false);
+    let grads = graph.gradients(&loss, &[&w]).unwrap();
+    let step = Sgd::new(lr).minimize(&mut graph, &[&w], &grads).unwrap();
+
+    let mut executable = graph.compile(&step.targets()).unwrap();
+    let mut previous = 0.0;
+    (0..steps)
+        .map(|_| {
+            let results = executable.execute(HashMap::new()).unwrap();
+            let current = values(&results[&step.params[0]])[0];
+            let rate = previous - current;
+            previous = current;
+            rate
+        })
+        .collect()
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Optimizers Test ===");
+
+    let optimizers: [(&str, Box<dyn Optimizer>, usize); 6] = [
+        ("sgd", Box::new(Sgd::new(0.1)), 200),
+        ("momentum", Box::new(Sgd::new(0.05).with_momentum(0.9)), 200),
+        (
+            "nesterov",
+            Box::new(Sgd::new(0.05).with_momentum(0.9).with_nesterov(true)),
+            200,
+        ),
+        ("adam", Box::new(Adam::new(0.1)), 300),
+        (
+            "adamw",
+            Box::new(AdamW::new(0.1).with_weight_decay(0.0)),
+            300,
+        ),
+        ("rmsprop", Box::new(RmsProp::new(0.01)), 500),
+    ];
+    for (name, optimizer, steps) in &optimizers {
+        let loss = fit(optimizer.as_ref(), *steps);
+        println!("{name}: loss {loss:e} after {steps} steps");
+        assert!(loss < 1e-4, "{name} did not converge");
+    }
+
+    // Schedules are evaluated in the graph from the step counter
+    assert_close(&rates(LearningRate::Constant(0.5), 3), &[0.5, 0.5, 0.5]);
+    assert_close(
+        &rates(
+            LearningRate::StepDecay {
+                initial: 1.0,
+                gamma: 0.5,
+                every: NonZeroUsize::new(2).unwrap(),
+            },
+            5,
+        ),
+        &[1.0, 1.0, 0.5, 0.5, 0.25],
+    );
+    assert_close(
+        &rates(
+            LearningRate::Exponential {
+                initial: 1.0,
+                gamma: 0.1,
+            },
+            3,
+        ),
+        &[1.0, 0.1, 0.01],
+    );
+    assert_close(
+        &rates(
+            LearningRate::Cosine {
+                initial: 1.0,
+                min: 0.0,
+                steps: NonZeroUsize::new(2).unwrap(),
+            },
+            4,
+        ),
+        &[1.0, 0.5, 0.0, 0.0],
+    );
+    assert_close(
+        &rates(
+            LearningRate::Warmup {
+                steps: NonZeroUsize::new(4).unwrap(),
+                schedule: Box::new(LearningRate::Constant(1.0)),
+            },
+            5,
+        ),
+        &[0.25, 0.5, 0.75, 1.0, 1.0],
+    );
+
+    // A zero decay factor drops the rate to zero, and a negative one is
+    // rejected when the step is built
+    assert_close(
+        &rates(
+            LearningRate::StepDecay {
+                initial: 1.0,
+                gamma: 0.0,
+                every: NonZeroUsize::new(2).unwrap(),
+            },
+            4,
+        ),
+        &[1.0, 1.0, 0.0, 0.0],
+    );
+    assert_close(
+        &rates(
+            LearningRate::Exponential {
+                initial: 1.0,
+                gamma: 0.0,
+            },
+            3,
+        ),
+        &[1.0, 0.0, 0.0],
+    );
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![0.0f64], Shape::from([1]));
+    let grads = graph.gradients(&w.sum(&[], false), &[&w])?;
+    let negative = LearningRate::Warmup {
+        steps: NonZeroUsize::new(2).unwrap(),
+        schedule: Box::new(LearningRate::Exponential {
+            initial: 1.0,
+            gamma: -0.5,
+        }),
+    };
+    let err = Sgd::new(negative)
+        .minimize(&mut graph, &[&w], &grads)
+        .expect_err("negative decay");
+    assert_eq!(
+        err,
+        GraphError::InvalidDecay {
+            op: "sgd",
+            gamma: -0.5
+        }
+    );
+
+    // Adam's first step moves every parameter by the learning rate, and
+    // AdamW additionally shrinks it by lr * weight_decay
+    let first_step = |optimizer: &dyn Optimizer| {
+        let mut graph = Graph::new();
+        let w = graph.variable(vec![1.0f32, -2.0], Shape::from([2]));
+        let loss = (&w * &w).sum(&[], false);
+        let grads = graph.gradients(&loss, &[&w]).unwrap();
+        let step = optimizer.minimize(&mut graph, &[&w], &grads).unwrap();
+        let mut executable = graph.compile(&step.targets()).unwrap();
+        let results = executable.execute(HashMap::new()).unwrap();
+        values(&results[&step.params[0]])
+    };
+    let adam = first_step(&Adam::new(0.1));
+    let adamw = first_step(&AdamW::new(0.1).with_weight_decay(0.5));
+    println!("adam: {adam:?}, adamw: {adamw:?}");
+    assert_close(
+        &adam.iter().map(|x| (x * 1e5).round()).collect::<Vec<_>>(),
+        &[90000.0, -190000.0],
+    );
+    assert_close(
+        &adamw.iter().map(|x| (x * 1e5).round()).collect::<Vec<_>>(),
+        &[85000.0, -180000.0],
+    );
+
+    // Optimizer state lives in variables that can be saved and restored
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![1.0f32], Shape::from([1]));
+    let loss = (&w * &w).sum(&[], false);
+    let grads = graph.gradients(&loss, &[&w])?;
+    let step = Adam::new(0.1).minimize(&mut graph, &[&w], &grads)?;
+    assert_eq!(step.state.len(), 3);
+    assert_eq!(step.count.dtype(), DType::I64);
+
+    let mut executable = graph.compile(&step.targets())?;
+    executable.execute(HashMap::new())?;
+    let saved: Vec<_> = step
+        .state
+        .iter()
+        .chain([&w])
+        .map(|tensor| executable.get_variable(tensor).unwrap().clone())
+        .collect();
+    let next = executable.execute(HashMap::new())?;
+
+    executable.execute(HashMap::new())?;
+    for (tensor, data) in step.state.iter().chain([&w]).zip(saved) {
+        executable.set_variable(tensor, data)?;
+    }
+    let replayed = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", replayed[&step.count]),
+        "I64 { data: [2], shape: [] }"
+    );
+    assert_eq!(
+        format!("{:?}", replayed[&step.params[0]]),
+        format!("{:?}", next[&step.params[0]])
+    );
+
+    // Only float variables can be optimized
+    let mut graph = Graph::new();
+    let counts = graph.variable(vec![1i32, 2], Shape::from([2]));
+    let grads: Vec<GraphTensor> = vec![counts.clone()];
+    let err = Sgd::new(0.1)
+        .minimize(&mut graph, &[&counts], &grads)
+        .expect_err("integer parameter");
+    println!("{err}");
+    assert_eq!(
+        err,
+        GraphError::UnsupportedDtype {
+            op: "sgd",
+            node: counts.node_id(),
+            dtype: DType::I32,
+        }
+    );
+
+    // Each parameter needs exactly one gradient
+    let mut graph = Graph::new();
+    let w = graph.variable(vec![1.0f32], Shape::from([1]));
+    let b = graph.variable(vec![0.0f32], Shape::from([1]));
+    let grads = vec![w.ones_like()];
+    let err = Adam::new(0.1)
+        .minimize(&mut graph, &[&w, &b], &grads)
+        .expect_err("missing gradient");
+    println!("{err}");
+    assert_eq!(
+        err,
+        GraphError::GradientCountMismatch {
+            op: "adam",
+            params: 2,
+            grads: 1,
+        }
+    );
+
+    println!("✓ optimizers update variables in the graph");
+
+    Ok(())
+}
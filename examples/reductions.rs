@@ -0,0 +1,120 @@
+use binah_core::{Graph, Shape};
+
+mod common;
+
+use common::check;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Reductions Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph
+        .constant(vec![1.0f32, 5.0, 3.0, 4.0, 2.0, 6.0], Shape::from([2, 3]));
+
+    check(
+        &mut graph,
+        "sum axis 0",
+        &x.sum(&[0], false),
+        "F32 { data: [5.0, 7.0, 9.0], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "sum axis 1 keepdims",
+        &x.sum(&[1], true),
+        "F32 { data: [9.0, 12.0], shape: [2, 1] }",
+    )?;
+    check(
+        &mut graph,
+        "sum everything",
+        &x.sum(&[], false),
+        "F32 { data: [21.0], shape: [] }",
+    )?;
+    check(
+        &mut graph,
+        "sum everything keepdims",
+        &x.sum(&[], true),
+        "F32 { data: [21.0], shape: [1, 1] }",
+    )?;
+    check(
+        &mut graph,
+        "mean axis -1",
+        &x.mean(&[-1], false),
+        "F32 { data: [3.0, 4.0], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "max axis 1",
+        &x.max(&[1], false),
+        "F32 { data: [5.0, 6.0], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "min axis 0 keepdims",
+        &x.min(&[0], true),
+        "F32 { data: [1.0, 2.0, 3.0], shape: [1, 3] }",
+    )?;
+    check(
+        &mut graph,
+        "prod axes 0 and 1",
+        &x.prod(&[0, -1], false),
+        "F32 { data: [720.0], shape: [] }",
+    )?;
+
+    // Indices are i64, and ties go to the first extremum
+    check(
+        &mut graph,
+        "argmax axis 1",
+        &x.argmax(&[1], false),
+        "I64 { data: [1, 2], shape: [2] }",
+    )?;
+    check(
+        &mut graph,
+        "argmin axis 0 keepdims",
+        &x.argmin(&[0], true),
+        "I64 { data: [0, 1, 0], shape: [1, 3] }",
+    )?;
+    check(
+        &mut graph,
+        "argmax everything",
+        &x.argmax(&[], false),
+        "I64 { data: [5], shape: [] }",
+    )?;
+    let ties = graph.constant(vec![2i32, 7, 7, 1], Shape::from([4]));
+    check(
+        &mut graph,
+        "argmax ties",
+        &ties.argmax(&[0], false),
+        "I64 { data: [1], shape: [] }",
+    )?;
+
+    // Reductions keep integer dtypes
+    check(
+        &mut graph,
+        "i32 sum",
+        &ties.sum(&[], false),
+        "I32 { data: [17], shape: [] }",
+    )?;
+
+    // Middle axes of higher-rank tensors
+    let cube = graph.constant(
+        (0..24).map(|i| i as f64).collect::<Vec<_>>(),
+        Shape::from([2, 3, 4]),
+    );
+    check(
+        &mut graph,
+        "sum middle axis",
+        &cube.sum(&[1], false),
+        "F64 { data: [12.0, 15.0, 18.0, 21.0, 48.0, 51.0, 54.0, 57.0], \
+         shape: [2, 4] }",
+    )?;
+    check(
+        &mut graph,
+        "max outer axes keepdims",
+        &cube.max(&[0, 2], true),
+        "F64 { data: [15.0, 19.0, 23.0], shape: [1, 3, 1] }",
+    )?;
+
+    println!("✓ reductions follow their axes and keepdims");
+
+    Ok(())
+}
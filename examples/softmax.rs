@@ -0,0 +1,178 @@
+use binah_core::{DType, Graph, Shape, TensorStorage, TypePromotion};
+use std::collections::HashMap;
+
+fn floats(storage: &TensorStorage) -> Vec<f64> {
+    match storage {
+        TensorStorage::F32 { data, .. } => {
+            data.iter().map(|&x| x as f64).collect()
+        }
+        TensorStorage::F64 { data, .. } => data.clone(),
+        other => panic!("expected floats, got {other:?}"),
+    }
+}
+
+fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < tolerance, "{actual:?} != {expected:?}");
+    }
+}
+
+/// Softmax of every lane of `data` along the middle axis of `[outer, len,
+/// inner]`, in `f64` without any shift.
+fn reference(
+    data: &[f64],
+    (outer, len, inner): (usize, usize, usize),
+) -> Vec<f64> {
+    let mut result = vec![0.0; data.len()];
+    for o in 0..outer {
+        for i in 0..inner {
+            let at = |k: usize| (o * len + k) * inner + i;
+            let total: f64 = (0..len).map(|k| data[at(k)].exp()).sum();
+            for k in 0..len {
+                result[at(k)] = data[at(k)].exp() / total;
+            }
+        }
+    }
+    result
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Softmax Test ===");
+
+    // Every axis of a [2, 3, 4] tensor, and a transposed view of it
+    let mut graph = Graph::new();
+    let data: Vec<f64> =
+        (0..24).map(|i| (i as f64 * 0.7).sin() * 3.0).collect();
+    let input = graph.constant(data.clone(), Shape::from([2, 3, 4]));
+    let first = input.softmax(0);
+    let middle = input.softmax(1);
+    let last = input.softmax(-1);
+    let log_middle = input.log_softmax(-2);
+    let transposed = input.transpose(1, 2)?.softmax(-1);
+
+    let mut executable =
+        graph.compile(&[&first, &middle, &last, &log_middle, &transposed])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(results[&first].dtype(), DType::F64);
+    assert_close(
+        &floats(&results[&first]),
+        &reference(&data, (1, 2, 12)),
+        1e-12,
+    );
+    let expected_middle = reference(&data, (2, 3, 4));
+    assert_close(&floats(&results[&middle]), &expected_middle, 1e-12);
+    assert_close(
+        &floats(&results[&last]),
+        &reference(&data, (6, 4, 1)),
+        1e-12,
+    );
+    let expected_log: Vec<f64> =
+        expected_middle.iter().map(|p| p.ln()).collect();
+    assert_close(&floats(&results[&log_middle]), &expected_log, 1e-12);
+    // Softmax over axis 1 of the transposed view is the middle softmax,
+    // transposed
+    let transposed_data = floats(&results[&transposed]);
+    for o in 0..2 {
+        for k in 0..3 {
+            for i in 0..4 {
+                assert!(
+                    (transposed_data[(o * 4 + i) * 3 + k]
+                        - expected_middle[(o * 3 + k) * 4 + i])
+                        .abs()
+                        < 1e-12
+                );
+            }
+        }
+    }
+
+    // Large logits neither overflow nor lose the log-probabilities of
+    // classes whose probability underflows
+    let mut graph = Graph::new();
+    let logits = graph.constant(
+        vec![1000.0f32, 1001.0, 1002.0, 0.0, -1000.0, f32::NEG_INFINITY],
+        Shape::from([2, 3]),
+    );
+    let probs = logits.softmax(-1);
+    let log_probs = logits.log_softmax(-1);
+    let mut executable = graph.compile(&[&probs, &log_probs])?;
+    let results = executable.execute(HashMap::new())?;
+    println!("softmax: {:?}", floats(&results[&probs]));
+    println!("log_softmax: {:?}", floats(&results[&log_probs]));
+    let shifted = reference(&[0.0, 1.0, 2.0], (1, 3, 1));
+    assert_close(
+        &floats(&results[&probs]),
+        &[shifted[0], shifted[1], shifted[2], 1.0, 0.0, 0.0],
+        1e-6,
+    );
+    let log_probs = floats(&results[&log_probs]);
+    assert_close(
+        &log_probs[..5],
+        &[
+            shifted[0].ln(),
+            shifted[1].ln(),
+            shifted[2].ln(),
+            0.0,
+            -1000.0,
+        ],
+        1e-4,
+    );
+    assert_eq!(log_probs[5], f64::NEG_INFINITY);
+
+    // Integers are converted, and NaN spreads over its own lane only
+    let mut graph = Graph::new();
+    let counts = graph.constant(vec![0i32, 0, 0, 0], Shape::from([2, 2]));
+    let uniform = counts.softmax(1);
+    let with_nan =
+        graph.constant(vec![f32::NAN, 1.0, 2.0, 2.0], Shape::from([2, 2]));
+    let spread = with_nan.softmax(1);
+    let mut executable = graph.compile(&[&uniform, &spread])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(results[&uniform].dtype(), DType::F64);
+    assert_eq!(floats(&results[&uniform]), [0.5; 4]);
+    let spread = floats(&results[&spread]);
+    assert!(spread[0].is_nan() && spread[1].is_nan());
+    assert_eq!(spread[2..], [0.5, 0.5]);
+
+    // Axes out of range and integers in a strict graph are errors
+    let err = with_nan.try_softmax(2).expect_err("axis out of range");
+    assert_eq!(
+        err.to_string(),
+        "Invalid softmax at node 3: Axis 2 is out of range for a tensor of \
+         rank 2"
+    );
+    assert!(with_nan.try_log_softmax(-3).is_err());
+    graph.set_type_promotion(TypePromotion::Strict);
+    let err = counts.try_log_softmax(0).expect_err("strict graph");
+    assert_eq!(
+        err.to_string(),
+        "Unsupported dtype i32 for log_softmax at node 0"
+    );
+
+    // Gradients: softmax weighted by w gives p * (w - sum(p * w)), and
+    // log_softmax summed with weights w gives w - p * sum(w)
+    let mut graph = Graph::new();
+    let x = graph.constant(vec![0.5f64, -1.0, 2.0], Shape::from([3]));
+    let weights = graph.constant(vec![1.0f64, 2.0, 3.0], Shape::from([3]));
+    let p = x.softmax(0);
+    let softmax_loss = (&p * &weights).sum(&[], false);
+    let log_softmax_loss = (&x.log_softmax(0) * &weights).sum(&[], false);
+    let grads = graph.gradients(&softmax_loss, &[&x])?;
+    let log_grads = graph.gradients(&log_softmax_loss, &[&x])?;
+    let mut executable = graph.compile(&[&grads[0], &log_grads[0]])?;
+    let results = executable.execute(HashMap::new())?;
+
+    let p = reference(&[0.5, -1.0, 2.0], (1, 3, 1));
+    let w = [1.0, 2.0, 3.0];
+    let mean: f64 = p.iter().zip(w).map(|(p, w)| p * w).sum();
+    let expected: Vec<f64> =
+        p.iter().zip(w).map(|(p, w)| p * (w - mean)).collect();
+    assert_close(&floats(&results[&grads[0]]), &expected, 1e-12);
+    let expected: Vec<f64> =
+        p.iter().zip(w).map(|(p, w)| w - p * 6.0).collect();
+    assert_close(&floats(&results[&log_grads[0]]), &expected, 1e-12);
+
+    println!("✓ softmax and log_softmax are stable and differentiable");
+
+    Ok(())
+}
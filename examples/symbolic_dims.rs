@@ -0,0 +1,145 @@
+use binah_core::{
+    DType, Dim, ExecutionError, Graph, GraphError, Shape, TensorKey,
+    TensorStorage,
+};
+use std::collections::HashMap;
+
+fn batch(size: usize) -> HashMap<TensorKey, TensorStorage> {
+    let features = (0..size * 3).map(|i| i as f32).collect();
+    let labels = (0..size).map(|i| i as f32).collect();
+
+    HashMap::from([
+        (
+            "x".into(),
+            TensorStorage::F32 {
+                data: features,
+                shape: vec![size, 3],
+            },
+        ),
+        (
+            "y".into(),
+            TensorStorage::F32 {
+                data: labels,
+                shape: vec![size],
+            },
+        ),
+    ])
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Symbolic Dimensions Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph.placeholder(
+        "x",
+        Shape::from([Dim::Sym("batch"), Dim::Fixed(3)]),
+        DType::F32,
+    )?;
+    let y =
+        graph.placeholder("y", Shape::from([Dim::Sym("batch")]), DType::F32)?;
+    let w = graph.variable(vec![1.0f32, 0.0, -1.0], Shape::from([3, 1]));
+    let b = graph.variable(vec![0.5f32], Shape::from([1]));
+
+    // Symbols survive shape inference
+    let prediction = (x.matmul(&w) + &b).squeeze(&[-1])?;
+    assert_eq!(prediction.shape(), Shape::from([Dim::Sym("batch")]));
+    assert_eq!(format!("{:?}", x.shape().dims()), "[batch, 3]");
+
+    let error = &prediction - &y;
+    let loss = (&error * &error).mean(&[], false);
+    let grads = graph.gradients(&loss, &[&w, &b])?;
+    assert_eq!(grads[0].shape(), Shape::from([3, 1]));
+
+    // One executable serves every batch size
+    let mut executable = graph.compile(&[&prediction, &loss, &grads[1]])?;
+    for size in [2, 5] {
+        let results = executable.execute(batch(size))?;
+        println!("batch {size}: {:?}", results[&prediction]);
+        assert_eq!(results[&prediction].shape(), &[size]);
+    }
+
+    let results = executable.execute(batch(2))?;
+    // Predictions are [-1.5, -1.5] against labels [0, 1]
+    assert_eq!(
+        format!("{:?}", results[&loss]),
+        "F32 { data: [4.25], shape: [] }"
+    );
+    assert_eq!(
+        format!("{:?}", results[&grads[1]]),
+        "F32 { data: [-4.0], shape: [1] }"
+    );
+
+    let check = |result: Result<_, ExecutionError>, expected: &str| {
+        let err = result.expect_err(expected);
+        println!("{err}");
+        assert_eq!(err.to_string(), expected);
+    };
+
+    // Every placeholder must agree on the size of a symbol
+    let mut inputs = batch(2);
+    inputs.insert(
+        "y".into(),
+        TensorStorage::F32 {
+            data: vec![0.0; 3],
+            shape: vec![3],
+        },
+    );
+    check(
+        executable.execute(inputs),
+        "The input fed to node 1 sets batch to 3, but it is already 2",
+    );
+
+    // Fixed dimensions are still checked
+    let mut inputs = batch(2);
+    inputs.insert(
+        "x".into(),
+        TensorStorage::F32 {
+            data: vec![0.0; 8],
+            shape: vec![2, 4],
+        },
+    );
+    check(
+        executable.execute(inputs),
+        "Expected an input of shape [batch, 3] for node 0, got [2, 4]",
+    );
+
+    // Different symbols never broadcast with each other
+    let z = graph.placeholder(
+        "z",
+        Shape::from([Dim::Sym("time"), Dim::Fixed(3)]),
+        DType::F32,
+    )?;
+    assert!(matches!(
+        x.try_add(&z),
+        Err(GraphError::IncompatibleShapes { .. })
+    ));
+
+    // Concatenation needs a known size along its axis
+    let err = graph.concat(&[&x, &x], 0).expect_err("batch is symbolic");
+    println!("{err}");
+    assert_eq!(
+        err.to_string(),
+        "Invalid concat at node 0: Axis 0 has the symbolic size batch"
+    );
+    let both = graph.concat(&[&x, &x], 1)?;
+    assert_eq!(
+        both.shape(),
+        Shape::from([Dim::Sym("batch"), Dim::Fixed(6)])
+    );
+
+    // A symbol that no input binds is reported when it is needed
+    let ones = graph.constant(vec![1.0f32], Shape::from([1]));
+    let steps = ones.expand(Shape::from([Dim::Sym("steps")]))?;
+    let mut executable = graph.compile(&[&steps])?;
+    check(
+        executable.execute(HashMap::new()),
+        &format!(
+            "Node {} needs the size of steps, which no input sets",
+            steps.node_id().index()
+        ),
+    );
+
+    println!("✓ symbolic dimensions bind at execution");
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use binah_core::{Graph, GraphTensor, Shape, TensorStorage};
+use std::collections::HashMap;
+
+mod common;
+
+use common::check;
+
+/// Compiles the f64 `tensor` alone and checks it against `expected` up to
+/// rounding.
+fn check_close(
+    graph: &mut Graph,
+    name: &str,
+    tensor: &GraphTensor,
+    expected: &[f64],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut executable = graph.compile(&[tensor])?;
+    let results = executable.execute(HashMap::new())?;
+    let TensorStorage::F64 { data, .. } = &results[tensor] else {
+        panic!("{name}: expected f64, got {:?}", results[tensor]);
+    };
+
+    println!("{name}: {data:?}");
+    assert_eq!(data.len(), expected.len(), "{name}");
+    for (actual, expected) in data.iter().zip(expected) {
+        assert!((actual - expected).abs() < 1e-9, "{name}: {data:?}");
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Unary Ops Test ===");
+
+    let mut graph = Graph::new();
+    let x =
+        graph.constant(vec![-2.5f64, -0.5, 0.0, 1.5, 2.5], Shape::from([5]));
+
+    check(
+        &mut graph,
+        "-x",
+        &-&x,
+        "F64 { data: [2.5, 0.5, -0.0, -1.5, -2.5], shape: [5] }",
+    )?;
+    check(
+        &mut graph,
+        "abs",
+        &x.abs(),
+        "F64 { data: [2.5, 0.5, 0.0, 1.5, 2.5], shape: [5] }",
+    )?;
+    check(
+        &mut graph,
+        "relu",
+        &x.relu(),
+        "F64 { data: [0.0, 0.0, 0.0, 1.5, 2.5], shape: [5] }",
+    )?;
+    check(
+        &mut graph,
+        "sign",
+        &x.sign(),
+        "F64 { data: [-1.0, -1.0, 0.0, 1.0, 1.0], shape: [5] }",
+    )?;
+    check(
+        &mut graph,
+        "floor",
+        &x.floor(),
+        "F64 { data: [-3.0, -1.0, 0.0, 1.0, 2.0], shape: [5] }",
+    )?;
+    check(
+        &mut graph,
+        "ceil",
+        &x.ceil(),
+        "F64 { data: [-2.0, -0.0, 0.0, 2.0, 3.0], shape: [5] }",
+    )?;
+    // Halves round to even
+    check(
+        &mut graph,
+        "round",
+        &x.round(),
+        "F64 { data: [-2.0, -0.0, 0.0, 2.0, 2.0], shape: [5] }",
+    )?;
+
+    let p = graph.constant(vec![0.25f64, 1.0, 4.0], Shape::from([3]));
+    let values = [0.25f64, 1.0, 4.0];
+    let expected = |f: fn(f64) -> f64| values.map(f);
+    check_close(&mut graph, "exp", &p.exp(), &expected(f64::exp))?;
+    check_close(&mut graph, "log", &p.log(), &expected(f64::ln))?;
+    check_close(&mut graph, "sqrt", &p.sqrt(), &expected(f64::sqrt))?;
+    check_close(&mut graph, "rsqrt", &p.rsqrt(), &[2.0, 1.0, 0.5])?;
+    check_close(&mut graph, "sin", &p.sin(), &expected(f64::sin))?;
+    check_close(&mut graph, "cos", &p.cos(), &expected(f64::cos))?;
+    check_close(&mut graph, "tanh", &p.tanh(), &expected(f64::tanh))?;
+    check_close(
+        &mut graph,
+        "sigmoid",
+        &p.sigmoid(),
+        &expected(|x| 1.0 / (1.0 + (-x).exp())),
+    )?;
+    check_close(&mut graph, "reciprocal", &p.reciprocal(), &[4.0, 1.0, 0.25])?;
+
+    let q = graph.constant(vec![-1.0f64, 0.0, 1.0], Shape::from([3]));
+    check_close(
+        &mut graph,
+        "erf",
+        &q.erf(),
+        &[-0.8427007929497149, 0.0, 0.8427007929497149],
+    )?;
+    check_close(
+        &mut graph,
+        "gelu",
+        &q.gelu(),
+        &[-0.15865525393145707, 0.0, 0.8413447460685429],
+    )?;
+
+    // Integers keep their type where the operation is exact, and are
+    // converted to floats otherwise
+    let n = graph.constant(vec![-3i32, 0, 4], Shape::from([3]));
+    check(
+        &mut graph,
+        "i32 abs",
+        &n.abs(),
+        "I32 { data: [3, 0, 4], shape: [3] }",
+    )?;
+    check(
+        &mut graph,
+        "i32 sign",
+        &n.sign(),
+        "I32 { data: [-1, 0, 1], shape: [3] }",
+    )?;
+    check_close(
+        &mut graph,
+        "i32 sqrt",
+        &n.abs().sqrt(),
+        &[3f64.sqrt(), 0.0, 2.0],
+    )?;
+
+    println!("✓ unary ops match their definitions");
+
+    Ok(())
+}
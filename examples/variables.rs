@@ -0,0 +1,118 @@
+use binah_core::{ExecutionError, Graph, GraphError, Shape, TensorStorage};
+use std::collections::HashMap;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Variables Test ===");
+
+    // Fit y = 2x with plain gradient descent, without rebuilding the graph
+    let mut graph = Graph::new();
+    let x = graph.constant(vec![1.0f32, 2.0, 3.0], Shape::from([3]));
+    let y = graph.constant(vec![2.0f32, 4.0, 6.0], Shape::from([3]));
+    let w = graph.variable(vec![0.0f32], Shape::from([1]));
+    w.set_name("w")?;
+
+    let error = &w * &x - &y;
+    let loss = (&error * &error).mean(&[], false);
+    let grads = graph.gradients(&loss, &[&w])?;
+    let step = w.assign_add(&(&grads[0] * -0.05))?;
+
+    let mut executable = graph.compile(&[&loss, &step])?;
+    let first = executable.execute(HashMap::new())?;
+    // The step is computed from the value the execution started with
+    assert_eq!(
+        format!("{:?}", first[&loss]),
+        "F32 { data: [18.666666], shape: [] }"
+    );
+    for _ in 0..100 {
+        executable.execute(HashMap::new())?;
+    }
+
+    let fitted = executable.get_variable("w").expect("w is a variable");
+    println!("w after training: {fitted:?}");
+    let TensorStorage::F32 { data, .. } = fitted else {
+        panic!("w stays f32");
+    };
+    assert!((data[0] - 2.0).abs() < 1e-4);
+
+    // Weights can be replaced between executions
+    executable.set_variable(
+        &w,
+        TensorStorage::F32 {
+            data: vec![2.0],
+            shape: vec![1],
+        },
+    )?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", results[&loss]),
+        "F32 { data: [0.0], shape: [] }"
+    );
+
+    let check = |result: Result<_, ExecutionError>, expected: &str| {
+        let err = result.expect_err(expected);
+        println!("{err}");
+        assert_eq!(err.to_string(), expected);
+    };
+    check(
+        executable.set_variable(
+            "w",
+            TensorStorage::F32 {
+                data: vec![1.0, 2.0],
+                shape: vec![2],
+            },
+        ),
+        "Expected an input of shape [1] for node 2, got [2]",
+    );
+    check(
+        executable.set_variable(
+            &x,
+            TensorStorage::F32 {
+                data: vec![0.0; 3],
+                shape: vec![3],
+            },
+        ),
+        "Node 0 is not a variable",
+    );
+    assert!(executable.get_variable("loss").is_none());
+
+    // Assignments in one execution apply in order, and a broadcast value
+    // fills the whole variable
+    let mut graph = Graph::new();
+    let counter = graph.variable(vec![0i64, 10], Shape::from([2]));
+    let one = graph.constant(vec![1i64], Shape::from([1]));
+    let first = counter.assign_add(&one)?;
+    let second = counter.assign_add(&first)?;
+    let mut executable = graph.compile(&[&second])?;
+    let results = executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", results[&second]),
+        "I64 { data: [2, 22], shape: [2] }"
+    );
+    assert_eq!(
+        format!("{:?}", executable.get_variable(&counter)),
+        "Some(I64 { data: [2, 22], shape: [2] })"
+    );
+
+    let reset = counter.assign(&one)?;
+    let mut executable = graph.compile(&[&reset])?;
+    executable.execute(HashMap::new())?;
+    assert_eq!(
+        format!("{:?}", executable.get_variable(&counter)),
+        "Some(I64 { data: [1, 1], shape: [2] })"
+    );
+
+    // Only variables can be assigned to
+    let err = one.assign(&counter).expect_err("one is a constant");
+    println!("{err}");
+    assert_eq!(
+        err,
+        GraphError::NotAVariable {
+            op: "assign",
+            node: one.node_id(),
+        }
+    );
+
+    println!("✓ variables persist across executions");
+
+    Ok(())
+}
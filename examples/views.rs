@@ -0,0 +1,104 @@
+use binah_core::{DType, Graph, Shape, TensorKey, TensorStorage};
+use std::collections::HashMap;
+
+mod common;
+mod counting;
+
+use common::check;
+use counting::measure;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Views Test ===");
+
+    let mut graph = Graph::new();
+    let x = graph.constant(
+        (0..6).map(|i| i as f32).collect::<Vec<_>>(),
+        Shape::from([2, 3]),
+    );
+
+    check(
+        &mut graph,
+        "reshape",
+        &x.reshape([3, 2])?,
+        "F32 { data: [0.0, 1.0, 2.0, 3.0, 4.0, 5.0], shape: [3, 2] }",
+    )?;
+    check(
+        &mut graph,
+        "transpose",
+        &x.transpose(0, -1)?,
+        "F32 { data: [0.0, 3.0, 1.0, 4.0, 2.0, 5.0], shape: [3, 2] }",
+    )?;
+    // Reshaping a transpose has to copy, in the transposed order
+    check(
+        &mut graph,
+        "reshape of transpose",
+        &x.transpose(0, 1)?.reshape([6])?,
+        "F32 { data: [0.0, 3.0, 1.0, 4.0, 2.0, 5.0], shape: [6] }",
+    )?;
+    check(
+        &mut graph,
+        "unsqueeze",
+        &x.unsqueeze(&[0, -1])?,
+        "F32 { data: [0.0, 1.0, 2.0, 3.0, 4.0, 5.0], shape: [1, 2, 3, 1] }",
+    )?;
+    check(
+        &mut graph,
+        "squeeze",
+        &x.unsqueeze(&[1])?.squeeze(&[])?,
+        "F32 { data: [0.0, 1.0, 2.0, 3.0, 4.0, 5.0], shape: [2, 3] }",
+    )?;
+    check(
+        &mut graph,
+        "expand",
+        &x.reshape([2, 1, 3])?.expand([2, 2, 3])?,
+        "F32 { data: [0.0, 1.0, 2.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 3.0, 4.0, \
+         5.0], shape: [2, 2, 3] }",
+    )?;
+    let cube =
+        graph.constant((0..8i32).collect::<Vec<_>>(), Shape::from([2, 2, 2]));
+    check(
+        &mut graph,
+        "permute",
+        &cube.permute(&[2, 0, 1])?,
+        "I32 { data: [0, 2, 4, 6, 1, 3, 5, 7], shape: [2, 2, 2] }",
+    )?;
+
+    // Invalid shapes and axes are rejected when building the graph
+    assert!(x.reshape([4, 2]).is_err());
+    assert!(x.transpose(0, 2).is_err());
+    assert!(x.permute(&[0, 0]).is_err());
+    assert!(x.squeeze(&[0]).is_err());
+    assert!(x.unsqueeze(&[3]).is_err());
+    assert!(x.expand([3, 3]).is_err());
+
+    // A chain of views allocates nothing until the output is copied out
+    const N: usize = 256;
+    let mut graph = Graph::new();
+    let x = graph.placeholder("x", Shape::from([N, 1, N]), DType::F32)?;
+    let y = x
+        .squeeze(&[1])?
+        .transpose(0, 1)?
+        .unsqueeze(&[0])?
+        .permute(&[1, 0, 2])?
+        .reshape([N, N])?;
+    let mut executable = graph.compile(&[&y])?;
+    let inputs = HashMap::from([(
+        TensorKey::from("x"),
+        TensorStorage::F32 {
+            data: (0..N * N).map(|i| i as f32).collect(),
+            shape: vec![N, 1, N],
+        },
+    )]);
+    let (results, _, allocated) = measure(|| executable.execute(inputs));
+    let results = results?;
+    println!("views allocated {allocated} bytes");
+    assert!(allocated < 2 * N * N * 4);
+    let TensorStorage::F32 { data, .. } = &results[&y] else {
+        panic!("expected f32");
+    };
+    assert_eq!(data[1], N as f32);
+
+    println!("✓ views share storage and validate shapes");
+
+    Ok(())
+}